@@ -0,0 +1,14 @@
+use imexport_wasm::cli;
+use std::io::Write;
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.is_empty() || args.iter().any(|arg| arg == "--help" || arg == "-h") {
+        let _ = writeln!(std::io::stdout(), "{}", cli::USAGE);
+        return;
+    }
+    if let Err(error) = cli::run(&args) {
+        eprintln!("{}", cli::error_to_json(error.as_ref()));
+        std::process::exit(cli::get_exit_code(error.as_ref()));
+    }
+}
@@ -0,0 +1,21 @@
+use std::future::Future;
+use std::pin::Pin;
+
+pub type ImageFetchFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<Vec<u8>, Box<dyn std::error::Error>>> + Send + 'a>>;
+
+/// Receives export and import progress as a value between 0 and 1.
+pub trait ProgressReporter: Send + Sync {
+    fn report(&self, progress: f64);
+}
+
+impl<F: Fn(f64) + Send + Sync> ProgressReporter for F {
+    fn report(&self, progress: f64) {
+        self(progress)
+    }
+}
+
+/// Loads the bytes of an `image` column value, usually by URL.
+pub trait ImageFetcher: Send + Sync {
+    fn fetch<'a>(&'a self, url: &'a str) -> ImageFetchFuture<'a>;
+}
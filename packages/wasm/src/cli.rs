@@ -0,0 +1,350 @@
+use crate::{
+    create_structured_error, create_template_buffer, export_data_buffer, import_data_buffer,
+    import_dynamic_data_buffer, infer_excel_info_buffer, ExcelColumnData, ExcelData, ExcelInfo,
+    ExcelRowData, ExcelValue, ImageFetchFuture, ImageFetcher, ImportExportError,
+};
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use std::io::{Read, Write};
+
+pub const USAGE: &str = "Usage:
+  imexport template --schema <schema.json> [--output <file.xlsx>]
+  imexport export --schema <schema.json> --data <rows.json> [--output <file.xlsx>]
+  imexport import --schema <schema.json> --input <file.xlsx> [--output <rows.json>]
+  imexport import-dynamic --input <file.xlsx> [--sheet <name>] [--header-row <n>] [--format <xlsx|xlsb|xls|ods>] [--output <rows.json>]
+  imexport infer --input <file.xlsx> [--sheet <name>] [--header-row <n>] [--format <xlsx|xlsb|xls|ods>] [--output <schema.json>]
+
+Paths may be '-' to read from stdin or write to stdout. Output defaults to stdout.";
+
+/// Runs one CLI invocation. `args` excludes the program name.
+pub fn run(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let Some((command, options)) = args.split_first() else {
+        return Err(usage_error("Missing command"));
+    };
+    let options = parse_options(options)?;
+    match command.as_str() {
+        "template" => {
+            let info = read_schema(&options)?;
+            write_output(&options, &create_template_buffer(&info)?)
+        }
+        "export" => {
+            let info = read_schema(&options)?;
+            let data = parse_data(&read_json(&options, "data")?)?;
+            let buffer = futures::executor::block_on(export_data_buffer(&info, &data))?;
+            write_output(&options, &buffer)
+        }
+        "import" => {
+            let info = read_schema(&options)?;
+            let data = import_data_buffer(info, &read_input(&options, "input")?)?;
+            write_json(&options, &Value::Array(rows_to_json(&data.rows)))
+        }
+        "import-dynamic" => {
+            let header_row = get_header_row(&options)?;
+            let data = import_dynamic_data_buffer(
+                options.get("sheet").cloned(),
+                header_row,
+                &read_input(&options, "input")?,
+                options.get("format").map(String::as_str),
+            )?;
+            write_json(
+                &options,
+                &json!({
+                    "sheetName": data.sheet_name,
+                    "headers": data.headers,
+                    "rows": rows_to_json(&data.rows),
+                }),
+            )
+        }
+        "infer" => {
+            let info = infer_excel_info_buffer(
+                options.get("sheet").cloned(),
+                get_header_row(&options)?,
+                &read_input(&options, "input")?,
+                options.get("format").map(String::as_str),
+            )?;
+            write_json(&options, &serde_json::to_value(&info)?)
+        }
+        _ => Err(usage_error(format!("Unknown command '{}'", command))),
+    }
+}
+
+fn get_header_row(
+    options: &HashMap<String, String>,
+) -> Result<Option<u32>, Box<dyn std::error::Error>> {
+    options
+        .get("header-row")
+        .map(|value| {
+            value.parse::<u32>().map_err(|_| {
+                usage_error(format!(
+                    "Option '--header-row' expects a number, got '{}'",
+                    value
+                ))
+            })
+        })
+        .transpose()
+}
+
+/// Formats an error as a single JSON line with `code`, `message` and `params`.
+pub fn error_to_json(error: &(dyn std::error::Error + 'static)) -> String {
+    let (code, params) = match error.downcast_ref::<ImportExportError>() {
+        Some(error) => (
+            error.code(),
+            error
+                .params()
+                .iter()
+                .map(|(key, value)| (key.clone(), Value::String(value.clone())))
+                .collect(),
+        ),
+        None => ("CLI_OPERATION_FAILED", Map::new()),
+    };
+    json!({ "code": code, "message": error.to_string(), "params": params }).to_string()
+}
+
+/// Returns the process exit code for an error: 2 for usage errors, 1 otherwise.
+pub fn get_exit_code(error: &(dyn std::error::Error + 'static)) -> i32 {
+    match error.downcast_ref::<ImportExportError>() {
+        Some(error) if error.code() == "CLI_USAGE_INVALID" => 2,
+        _ => 1,
+    }
+}
+
+fn usage_error(message: impl AsRef<str>) -> Box<dyn std::error::Error> {
+    create_structured_error(
+        message.as_ref(),
+        "CLI_USAGE_INVALID",
+        &[("reason", message.as_ref().to_string())],
+    )
+    .into()
+}
+
+fn parse_options(args: &[String]) -> Result<HashMap<String, String>, Box<dyn std::error::Error>> {
+    let mut options = HashMap::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let Some(name) = arg.strip_prefix("--") else {
+            return Err(usage_error(format!("Unexpected argument '{}'", arg)));
+        };
+        if ![
+            "schema",
+            "data",
+            "input",
+            "output",
+            "sheet",
+            "header-row",
+            "format",
+        ]
+        .contains(&name)
+        {
+            return Err(usage_error(format!("Unknown option '--{}'", name)));
+        }
+        let Some(value) = args.next() else {
+            return Err(usage_error(format!("Option '--{}' expects a value", name)));
+        };
+        options.insert(name.to_string(), value.clone());
+    }
+    Ok(options)
+}
+
+fn read_input(
+    options: &HashMap<String, String>,
+    option: &str,
+) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let Some(path) = options.get(option) else {
+        return Err(usage_error(format!(
+            "Missing required option '--{}'",
+            option
+        )));
+    };
+    let read_failed = |error: std::io::Error| {
+        create_structured_error(
+            format!("Failed to read '{}': {}", path, error),
+            "CLI_FILE_READ_FAILED",
+            &[("path", path.clone()), ("reason", error.to_string())],
+        )
+    };
+    if path == "-" {
+        let mut bytes = Vec::new();
+        std::io::stdin()
+            .read_to_end(&mut bytes)
+            .map_err(read_failed)?;
+        return Ok(bytes);
+    }
+    Ok(std::fs::read(path).map_err(read_failed)?)
+}
+
+fn read_json(
+    options: &HashMap<String, String>,
+    option: &str,
+) -> Result<Value, Box<dyn std::error::Error>> {
+    let bytes = read_input(options, option)?;
+    serde_json::from_slice(&bytes).map_err(|error| {
+        create_structured_error(
+            format!("Invalid JSON in '--{}': {}", option, error),
+            "CLI_JSON_INVALID",
+            &[
+                ("option", option.to_string()),
+                ("reason", error.to_string()),
+            ],
+        )
+        .into()
+    })
+}
+
+fn write_output(
+    options: &HashMap<String, String>,
+    bytes: &[u8],
+) -> Result<(), Box<dyn std::error::Error>> {
+    let path = options.get("output").map(String::as_str).unwrap_or("-");
+    let result = if path == "-" {
+        let mut stdout = std::io::stdout().lock();
+        stdout.write_all(bytes).and_then(|_| stdout.flush())
+    } else {
+        std::fs::write(path, bytes)
+    };
+    result.map_err(|error| {
+        create_structured_error(
+            format!("Failed to write '{}': {}", path, error),
+            "CLI_FILE_WRITE_FAILED",
+            &[("path", path.to_string()), ("reason", error.to_string())],
+        )
+        .into()
+    })
+}
+
+fn write_json(
+    options: &HashMap<String, String>,
+    value: &Value,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut text = serde_json::to_string_pretty(value)?;
+    text.push('\n');
+    write_output(options, text.as_bytes())
+}
+
+fn read_schema(options: &HashMap<String, String>) -> Result<ExcelInfo, Box<dyn std::error::Error>> {
+    let schema = read_input(options, "schema")?;
+    let info = ExcelInfo::from_json(&String::from_utf8_lossy(&schema)).map_err(|reason| {
+        create_structured_error(&reason, "INVALID_DEFINITION", &[("reason", reason.clone())])
+    })?;
+    Ok(info.with_image_fetcher(LocalImageFetcher))
+}
+
+/// Converts a JSON array of row objects into `ExcelData`. Arrays of objects
+/// become grouped children, and `{ "value", "children" }` sets the group value.
+pub fn parse_data(data: &Value) -> Result<ExcelData, Box<dyn std::error::Error>> {
+    let Value::Array(rows) = data else {
+        return Err(data_error("Row data must be a JSON array of objects"));
+    };
+    Ok(ExcelData::new(
+        rows.iter().map(parse_row).collect::<Result<Vec<_>, _>>()?,
+    ))
+}
+
+fn parse_row(row: &Value) -> Result<ExcelRowData, Box<dyn std::error::Error>> {
+    let Value::Object(row) = row else {
+        return Err(data_error("Every row must be a JSON object"));
+    };
+    let mut columns = Vec::with_capacity(row.len());
+    for (key, value) in row.iter() {
+        let column_data = match value {
+            Value::Null => ExcelColumnData::new(key.as_str(), ""),
+            Value::String(value) => ExcelColumnData::new(key.as_str(), value.as_str()),
+            Value::Number(value) => ExcelColumnData::with_typed_value(
+                key.as_str(),
+                ExcelValue::Number(value.as_f64().unwrap_or_default()),
+            ),
+            Value::Bool(value) => {
+                ExcelColumnData::with_typed_value(key.as_str(), ExcelValue::Bool(*value))
+            }
+            Value::Array(children) => {
+                ExcelColumnData::new(key.as_str(), "").with_children(parse_children(children)?)
+            }
+            Value::Object(link) if link.contains_key("url") && !link.contains_key("children") => {
+                let url = get_json_text(link.get("url"));
+                let text = get_json_text(link.get("text"));
+                ExcelColumnData::with_typed_value(key.as_str(), ExcelValue::Link { url, text })
+            }
+            Value::Object(group) => {
+                let value = get_json_text(group.get("value"));
+                let children = match group.get("children") {
+                    Some(Value::Array(children)) => parse_children(children)?,
+                    _ => Vec::new(),
+                };
+                ExcelColumnData::new(key.clone(), value).with_children(children)
+            }
+        };
+        columns.push(column_data);
+    }
+    Ok(ExcelRowData::new(columns))
+}
+
+fn get_json_text(value: Option<&Value>) -> String {
+    match value {
+        Some(Value::String(value)) => value.clone(),
+        Some(Value::Null) | None => String::new(),
+        Some(value) => value.to_string(),
+    }
+}
+
+fn parse_children(children: &[Value]) -> Result<Vec<ExcelRowData>, Box<dyn std::error::Error>> {
+    children.iter().map(parse_row).collect()
+}
+
+fn data_error(message: &str) -> Box<dyn std::error::Error> {
+    create_structured_error(
+        message,
+        "CLI_DATA_INVALID",
+        &[("reason", message.to_string())],
+    )
+    .into()
+}
+
+fn rows_to_json(rows: &[ExcelRowData]) -> Vec<Value> {
+    rows.iter()
+        .map(|row| {
+            let mut object = Map::new();
+            for column in row.columns.iter() {
+                let value = if column.children.is_empty() {
+                    typed_value_to_json(column.typed_value())
+                } else {
+                    json!({
+                        "value": column.value(),
+                        "children": rows_to_json(&column.children),
+                    })
+                };
+                object.insert(column.key.clone(), value);
+            }
+            Value::Object(object)
+        })
+        .collect()
+}
+
+fn typed_value_to_json(value: &ExcelValue) -> Value {
+    match value {
+        ExcelValue::Empty => Value::Null,
+        ExcelValue::Number(number) if number.fract() == 0.0 && number.abs() < 1e15 => {
+            json!(*number as i64)
+        }
+        ExcelValue::Number(number) => json!(number),
+        ExcelValue::Bool(boolean) => Value::Bool(*boolean),
+        ExcelValue::Link { url, text } => json!({ "url": url, "text": text }),
+        _ => Value::String(value.to_display_string()),
+    }
+}
+
+/// Loads `image` column values as local file paths.
+struct LocalImageFetcher;
+
+impl ImageFetcher for LocalImageFetcher {
+    fn fetch<'a>(&'a self, url: &'a str) -> ImageFetchFuture<'a> {
+        Box::pin(async move {
+            std::fs::read(url).map_err(|error| {
+                create_structured_error(
+                    format!("Failed to read image '{}': {}", url, error),
+                    "CLI_FILE_READ_FAILED",
+                    &[("path", url.to_string()), ("reason", error.to_string())],
+                )
+                .into()
+            })
+        })
+    }
+}
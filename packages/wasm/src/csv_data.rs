@@ -0,0 +1,267 @@
+use crate::{
+    apply_import_value_options, check_cell_constraints, create_structured_error,
+    date_time_to_excel, find_column, flatten_row, format_date_value, get_dynamic_headers,
+    get_dynamic_rows_data, get_export_date, get_number_import_mode, get_rows_data,
+    is_date_data_type, parse_date_value, parse_export_boolean, parse_export_number,
+    read_boolean_cells, resolve_dynamic_header_row, validate_headers, CsvOptions, DynamicExcelData,
+    ExcelColumnPosition, ExcelData, ExcelInfo,
+};
+use calamine::Data;
+use encoding_rs::{Encoding, UTF_8};
+
+const UTF_8_BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
+
+/// Writes the leaf columns of `data` as delimited text, flattening grouped rows.
+pub fn export_csv_buffer(
+    info: &ExcelInfo,
+    data: &ExcelData,
+    options: &CsvOptions,
+) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let leaf_columns = info.get_leaf_columns();
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(get_single_byte_option("delimiter", &options.delimiter)?)
+        .quote(get_single_byte_option("quote", &options.quote)?)
+        .from_writer(Vec::new());
+    writer.write_record(leaf_columns.iter().map(|column| column.name.as_str()))?;
+
+    for row in data.rows.iter() {
+        for line in flatten_row(row) {
+            let mut record = Vec::with_capacity(leaf_columns.len());
+            for column in leaf_columns.iter() {
+                let value = match line.get(column.key.as_str()) {
+                    // Text dates keep their spelling unless the column sets `dateOptions`.
+                    Some(column_data) if is_date_data_type(&column.data_type) => {
+                        let is_native = column_data.typed_value().is_native();
+                        match get_export_date(column_data, column)? {
+                            Some(date_time) if is_native || column.date_options.is_some() => {
+                                format_date_value(&date_time, column)
+                            }
+                            _ if is_native => column_data.typed_value().to_display_string(),
+                            _ => column_data.value().to_string(),
+                        }
+                    }
+                    Some(column_data) if column_data.typed_value().is_native() => {
+                        column_data.typed_value().to_display_string()
+                    }
+                    Some(column_data) => {
+                        let trimmed_value = column_data.value().trim();
+                        if !trimmed_value.is_empty() {
+                            if column.data_type.eq_ignore_ascii_case("number") {
+                                parse_export_number(column_data.value(), column)?;
+                            } else if column.data_type.eq_ignore_ascii_case("boolean") {
+                                parse_export_boolean(column_data.value(), column)?;
+                            }
+                        }
+                        column_data.value().to_string()
+                    }
+                    None => String::new(),
+                };
+                record.push(value);
+            }
+            writer.write_record(&record)?;
+        }
+    }
+
+    let text = String::from_utf8(writer.into_inner()?)?;
+    encode_text(&text, options)
+}
+
+/// Reads delimited text whose first line matches the leaf column names.
+pub fn import_csv_buffer(
+    info: ExcelInfo,
+    csv_bytes: &[u8],
+    options: &CsvOptions,
+) -> Result<ExcelData, Box<dyn std::error::Error>> {
+    let mut range = read_csv_range(csv_bytes, options)?;
+    let bound_headers = validate_headers(
+        &info,
+        &range,
+        &get_csv_column_positions(&info),
+        &info.sheet_name,
+    )?;
+    coerce_csv_range(&info, &mut range, &bound_headers.positions)?;
+    check_cell_constraints(&info, &range, &bound_headers.positions, &info.sheet_name)?;
+    read_boolean_cells(
+        &info,
+        &mut range,
+        &bound_headers.positions,
+        &info.sheet_name,
+    )?;
+    let mut rows = get_rows_data(&info, &bound_headers.positions, &range, &info.sheet_name);
+    apply_import_value_options(&info, get_number_import_mode(&info)?, &mut rows);
+    Ok(ExcelData {
+        rows,
+        column_bindings: bound_headers.bindings,
+    })
+}
+
+/// Reads delimited text without a schema, using the header line as column keys.
+pub fn import_dynamic_csv_buffer(
+    header_row: Option<u32>,
+    csv_bytes: &[u8],
+    options: &CsvOptions,
+) -> Result<DynamicExcelData, Box<dyn std::error::Error>> {
+    let range = read_csv_range(csv_bytes, options)?;
+    let header_row = resolve_dynamic_header_row(&range, header_row)?;
+    let headers = get_dynamic_headers(&range, header_row)?;
+    let rows = get_dynamic_rows_data(&range, header_row, &headers, "");
+
+    Ok(DynamicExcelData {
+        sheet_name: "".into(),
+        headers: headers.into_iter().map(|(header, _)| header).collect(),
+        rows,
+    })
+}
+
+fn get_csv_column_positions(info: &ExcelInfo) -> Vec<ExcelColumnPosition> {
+    info.get_leaf_columns()
+        .iter()
+        .enumerate()
+        .map(|(index, column)| ExcelColumnPosition {
+            x1: index as u16,
+            y1: 0,
+            x2: index as u16,
+            y2: 0,
+            key: column.key.clone(),
+            data_type: column.data_type.clone(),
+            is_leaf: true,
+        })
+        .collect()
+}
+
+fn coerce_csv_range(
+    info: &ExcelInfo,
+    range: &mut calamine::Range<Data>,
+    column_positions: &[ExcelColumnPosition],
+) -> Result<(), Box<dyn std::error::Error>> {
+    let Some((range_end_y, _)) = range.end() else {
+        return Ok(());
+    };
+    for position in column_positions.iter() {
+        let column = find_column(info, &position.key)?;
+        let data_type = &column.data_type;
+        for row_index in 1..=range_end_y {
+            let cell_position = (row_index, position.x1 as u32);
+            let Some(Data::String(value)) = range.get_value(cell_position) else {
+                continue;
+            };
+            let coerced_value = if data_type.eq_ignore_ascii_case("number") {
+                value.trim().parse::<f64>().ok().map(Data::Float)
+            } else if is_date_data_type(data_type) {
+                parse_date_value(value, column)
+                    .ok()
+                    .map(|date_time| Data::Float(date_time_to_excel(&date_time)))
+            } else {
+                None
+            };
+            if let Some(coerced_value) = coerced_value {
+                range.set_value(cell_position, coerced_value);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn read_csv_range(
+    csv_bytes: &[u8],
+    options: &CsvOptions,
+) -> Result<calamine::Range<Data>, Box<dyn std::error::Error>> {
+    let text = decode_text(csv_bytes, options)?;
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .delimiter(get_single_byte_option("delimiter", &options.delimiter)?)
+        .quote(get_single_byte_option("quote", &options.quote)?)
+        .from_reader(text.as_bytes());
+
+    let mut records = Vec::new();
+    for record in reader.records() {
+        let record = record.map_err(|error| {
+            create_structured_error(
+                format!("Failed to parse CSV: {}", error),
+                "CSV_PARSE_FAILED",
+                &[("reason", error.to_string())],
+            )
+        })?;
+        records.push(record);
+    }
+
+    let width = records.iter().map(|record| record.len()).max().unwrap_or(0);
+    if records.is_empty() || width == 0 {
+        return Ok(calamine::Range::empty());
+    }
+    let mut range = calamine::Range::new((0, 0), (records.len() as u32 - 1, width as u32 - 1));
+    for (row_index, record) in records.iter().enumerate() {
+        for (column_index, value) in record.iter().enumerate() {
+            if !value.is_empty() {
+                range.set_value(
+                    (row_index as u32, column_index as u32),
+                    Data::String(value.to_string()),
+                );
+            }
+        }
+    }
+    Ok(range)
+}
+
+fn get_single_byte_option(name: &str, value: &str) -> Result<u8, Box<dyn std::error::Error>> {
+    match value.as_bytes() {
+        [byte] => Ok(*byte),
+        _ => Err(create_structured_error(
+            format!("CSV option '{}' must be a single ASCII character", name),
+            "CSV_OPTION_INVALID",
+            &[("option", name.to_string()), ("value", value.to_string())],
+        )
+        .into()),
+    }
+}
+
+fn get_encoding(label: &str) -> Result<&'static Encoding, Box<dyn std::error::Error>> {
+    Encoding::for_label(label.trim().as_bytes()).ok_or_else(|| {
+        create_structured_error(
+            format!("Unsupported CSV encoding '{}'", label),
+            "CSV_ENCODING_UNSUPPORTED",
+            &[("encoding", label.to_string())],
+        )
+        .into()
+    })
+}
+
+fn decode_text(bytes: &[u8], options: &CsvOptions) -> Result<String, Box<dyn std::error::Error>> {
+    let encoding = get_encoding(&options.encoding)?;
+    let (text, _, had_errors) = encoding.decode(bytes);
+    if had_errors {
+        return Err(create_structured_error(
+            format!("CSV content is not valid {}", encoding.name()),
+            "CSV_DECODE_FAILED",
+            &[("encoding", encoding.name().to_string())],
+        )
+        .into());
+    }
+    Ok(text.into_owned())
+}
+
+fn encode_text(text: &str, options: &CsvOptions) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let encoding = get_encoding(&options.encoding)?;
+    if encoding == UTF_8 {
+        let mut bytes = Vec::with_capacity(text.len() + UTF_8_BOM.len());
+        if options.with_bom {
+            bytes.extend_from_slice(UTF_8_BOM);
+        }
+        bytes.extend_from_slice(text.as_bytes());
+        return Ok(bytes);
+    }
+    let (bytes, _, had_errors) = encoding.encode(text);
+    if had_errors {
+        return Err(create_structured_error(
+            format!(
+                "CSV content contains characters that cannot be encoded as {}",
+                encoding.name()
+            ),
+            "CSV_ENCODING_UNMAPPABLE",
+            &[("encoding", encoding.name().to_string())],
+        )
+        .into());
+    }
+    Ok(bytes.into_owned())
+}
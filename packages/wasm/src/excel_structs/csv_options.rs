@@ -0,0 +1,72 @@
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+#[cfg_attr(feature = "wasm", wasm_bindgen(getter_with_clone))]
+#[derive(Clone)]
+pub struct CsvOptions {
+    pub delimiter: String,
+    pub quote: String,
+    pub encoding: String,
+    pub with_bom: bool,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        CsvOptions::new()
+    }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl CsvOptions {
+    #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+    pub fn new() -> CsvOptions {
+        CsvOptions {
+            delimiter: ",".into(),
+            quote: "\"".into(),
+            encoding: "utf-8".into(),
+            with_bom: false,
+        }
+    }
+
+    pub fn tsv() -> CsvOptions {
+        CsvOptions::new().with_delimiter("\t")
+    }
+
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withDelimiter))]
+    pub fn bind_with_delimiter(self, delimiter: String) -> Self {
+        self.with_delimiter(delimiter)
+    }
+
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withQuote))]
+    pub fn bind_with_quote(self, quote: String) -> Self {
+        self.with_quote(quote)
+    }
+
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withEncoding))]
+    pub fn bind_with_encoding(self, encoding: String) -> Self {
+        self.with_encoding(encoding)
+    }
+
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withBom))]
+    pub fn with_bom(mut self, with_bom: bool) -> Self {
+        self.with_bom = with_bom;
+        self
+    }
+}
+
+impl CsvOptions {
+    pub fn with_delimiter<T: Into<String>>(mut self, delimiter: T) -> Self {
+        self.delimiter = delimiter.into();
+        self
+    }
+
+    pub fn with_quote<T: Into<String>>(mut self, quote: T) -> Self {
+        self.quote = quote.into();
+        self
+    }
+
+    pub fn with_encoding<T: Into<String>>(mut self, encoding: T) -> Self {
+        self.encoding = encoding.into();
+        self
+    }
+}
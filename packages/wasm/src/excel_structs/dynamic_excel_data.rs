@@ -1,8 +1,9 @@
 use super::excel_row_data::ExcelRowData;
 use std::fmt;
+#[cfg(feature = "wasm")]
 use wasm_bindgen::prelude::*;
 
-#[wasm_bindgen(getter_with_clone)]
+#[cfg_attr(feature = "wasm", wasm_bindgen(getter_with_clone))]
 #[derive(Clone)]
 pub struct DynamicExcelData {
     pub sheet_name: String,
@@ -10,9 +11,9 @@ pub struct DynamicExcelData {
     pub rows: Vec<ExcelRowData>,
 }
 
-#[wasm_bindgen]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
 impl DynamicExcelData {
-    #[wasm_bindgen(constructor)]
+    #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
     pub fn new(
         sheet_name: String,
         headers: Vec<String>,
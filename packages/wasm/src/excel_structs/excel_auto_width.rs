@@ -0,0 +1,106 @@
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+/// The widest column Excel allows, in characters.
+const EXCEL_MAX_WIDTH: f64 = 255.0;
+
+/// Fits column widths to the header text and the exported values, counting
+/// CJK and other full-width characters as two.
+#[cfg_attr(feature = "wasm", wasm_bindgen(getter_with_clone))]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ExcelAutoWidth {
+    pub min_width: f64,
+    pub max_width: f64,
+    pub padding: f64,
+}
+
+impl Default for ExcelAutoWidth {
+    fn default() -> Self {
+        ExcelAutoWidth {
+            min_width: 8.0,
+            max_width: 60.0,
+            padding: 2.0,
+        }
+    }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl ExcelAutoWidth {
+    #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+    pub fn new() -> ExcelAutoWidth {
+        ExcelAutoWidth::default()
+    }
+
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withMinWidth))]
+    pub fn with_min_width(mut self, min_width: f64) -> Self {
+        self.min_width = min_width;
+        self
+    }
+
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withMaxWidth))]
+    pub fn with_max_width(mut self, max_width: f64) -> Self {
+        self.max_width = max_width;
+        self
+    }
+
+    /// Extra characters added to the widest text, for the cell margins.
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withPadding))]
+    pub fn with_padding(mut self, padding: f64) -> Self {
+        self.padding = padding;
+        self
+    }
+}
+
+impl ExcelAutoWidth {
+    pub fn is_valid(&self) -> bool {
+        self.min_width >= 0.0
+            && self.padding >= 0.0
+            && self.min_width <= self.max_width
+            && self.max_width <= EXCEL_MAX_WIDTH
+    }
+
+    /// The column width for text `display_width` characters wide, clamped
+    /// to `minWidth` and `maxWidth`.
+    pub fn fit(&self, display_width: usize) -> f64 {
+        (display_width as f64 + self.padding)
+            .max(self.min_width)
+            .min(self.max_width)
+    }
+}
+
+/// The width of the longest line of `text`, with wide characters counted twice.
+pub fn get_display_width(text: &str) -> usize {
+    text.lines()
+        .map(|line| line.chars().map(get_char_width).sum())
+        .max()
+        .unwrap_or(0)
+}
+
+fn get_char_width(c: char) -> usize {
+    let is_wide = matches!(
+        u32::from(c),
+        0x1100..=0x115F
+            | 0x2E80..=0x303E
+            | 0x3041..=0x33FF
+            | 0x3400..=0x4DBF
+            | 0x4E00..=0x9FFF
+            | 0xA000..=0xA4CF
+            | 0xAC00..=0xD7A3
+            | 0xF900..=0xFAFF
+            | 0xFE30..=0xFE4F
+            | 0xFF00..=0xFF60
+            | 0xFFE0..=0xFFE6
+            | 0x1F300..=0x1F64F
+            | 0x1F900..=0x1F9FF
+            | 0x20000..=0x3FFFD
+    );
+    if is_wide {
+        2
+    } else if c.is_control() {
+        0
+    } else {
+        1
+    }
+}
@@ -0,0 +1,24 @@
+use std::fmt;
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+/// The header cell a schema column was read from on import.
+#[cfg_attr(feature = "wasm", wasm_bindgen(getter_with_clone))]
+#[derive(Clone)]
+pub struct ExcelColumnBinding {
+    pub sheet_name: String,
+    pub key: String,
+    pub header: String,
+    pub cell: String,
+    pub column_index: u16,
+}
+
+impl fmt::Debug for ExcelColumnBinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} -> {}!{} ({})",
+            self.key, self.sheet_name, self.cell, self.header
+        )
+    }
+}
@@ -1,24 +1,66 @@
 use super::excel_row_data::ExcelRowData;
+use super::excel_value::ExcelValue;
 use std::fmt;
+#[cfg(feature = "wasm")]
 use wasm_bindgen::prelude::*;
 
-#[wasm_bindgen(getter_with_clone)]
+#[cfg_attr(feature = "wasm", wasm_bindgen(getter_with_clone))]
 #[derive(Clone)]
 pub struct ExcelColumnData {
     pub key: String,
-    pub value: String,
+    value: String,
     pub children: Vec<ExcelRowData>,
+    typed_value: ExcelValue,
 }
 
 impl ExcelColumnData {
     pub fn new<T: Into<String>>(key: T, value: T) -> Self {
+        let value = value.into();
         ExcelColumnData {
             key: key.into(),
-            value: value.into(),
+            typed_value: ExcelValue::from_text(value.clone()),
+            value,
             children: Vec::new(),
         }
     }
 
+    pub fn with_typed_value<T: Into<String>>(key: T, typed_value: ExcelValue) -> Self {
+        ExcelColumnData {
+            key: key.into(),
+            value: typed_value.to_display_string(),
+            children: Vec::new(),
+            typed_value,
+        }
+    }
+
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    /// Replaces the text value and drops any typed value, so export writes
+    /// the new text instead of a stale number, boolean, date or link.
+    pub fn set_value<T: Into<String>>(&mut self, value: T) {
+        self.value = value.into();
+        self.typed_value = ExcelValue::from_text(self.value.clone());
+    }
+
+    pub fn typed_value(&self) -> &ExcelValue {
+        &self.typed_value
+    }
+
+    /// Replaces the typed value and sets the text value to its display string.
+    pub fn set_typed_value(&mut self, typed_value: ExcelValue) {
+        self.value = typed_value.to_display_string();
+        self.typed_value = typed_value;
+    }
+
+    /// Sets both values where import shows the typed value in its own format,
+    /// such as a rounded number or a date in the column `outputPattern`.
+    pub(crate) fn set_typed_value_with_text(&mut self, typed_value: ExcelValue, value: String) {
+        self.typed_value = typed_value;
+        self.value = value;
+    }
+
     pub fn get_children_len(&self) -> usize {
         let mut result = 0;
         for row in self.children.iter() {
@@ -32,32 +74,115 @@ impl ExcelColumnData {
     }
 }
 
-#[wasm_bindgen]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
 impl ExcelColumnData {
-    #[wasm_bindgen(constructor)]
+    #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
     pub fn bind_new(key: String, value: String) -> ExcelColumnData {
         ExcelColumnData::new(key, value)
     }
 
-    #[wasm_bindgen(js_name = newGroup)]
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = newNumber))]
+    pub fn new_number(key: String, value: f64) -> ExcelColumnData {
+        ExcelColumnData::with_typed_value(key, ExcelValue::Number(value))
+    }
+
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = newBool))]
+    pub fn new_bool(key: String, value: bool) -> ExcelColumnData {
+        ExcelColumnData::with_typed_value(key, ExcelValue::Bool(value))
+    }
+
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = newLink))]
+    pub fn new_link(key: String, url: String, text: String) -> ExcelColumnData {
+        let text = if text.is_empty() { url.clone() } else { text };
+        ExcelColumnData::with_typed_value(key, ExcelValue::Link { url, text })
+    }
+
+    #[cfg(feature = "wasm")]
+    #[wasm_bindgen(js_name = newDate)]
+    pub fn new_date(key: String, value: js_sys::Date) -> ExcelColumnData {
+        let typed_value = ExcelValue::from_js_date(&value)
+            .map(ExcelValue::DateTime)
+            .unwrap_or(ExcelValue::Empty);
+        ExcelColumnData::with_typed_value(key, typed_value)
+    }
+
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = newGroup))]
     pub fn new_group(group_name: String, value: String, children: Vec<ExcelRowData>) -> Self {
-        ExcelColumnData {
-            key: group_name.into(),
-            value: value.into(),
-            children,
-        }
+        ExcelColumnData::new(group_name, value).with_children(children)
     }
 
-    #[wasm_bindgen(js_name = newRootGroup)]
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = newRootGroup))]
     pub fn new_root_group(group_name: String, children: Vec<ExcelRowData>) -> Self {
         ExcelColumnData::new_group(group_name, "".into(), children)
     }
 
-    #[wasm_bindgen(js_name = withChildren)]
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withChildren))]
     pub fn with_children(mut self, children: Vec<ExcelRowData>) -> Self {
         self.children = children;
         self
     }
+
+    #[cfg_attr(feature = "wasm", wasm_bindgen(getter, js_name = value))]
+    pub fn bind_value(&self) -> String {
+        self.value.clone()
+    }
+
+    #[cfg_attr(feature = "wasm", wasm_bindgen(setter, js_name = value))]
+    pub fn bind_set_value(&mut self, value: String) {
+        self.set_value(value);
+    }
+
+    #[cfg_attr(feature = "wasm", wasm_bindgen(getter))]
+    pub fn value_type(&self) -> String {
+        self.typed_value.value_type().to_string()
+    }
+
+    #[cfg(feature = "wasm")]
+    #[wasm_bindgen(getter, js_name = typed_value)]
+    pub fn bind_typed_value(&self) -> JsValue {
+        self.typed_value.to_js_value()
+    }
+
+    #[cfg_attr(feature = "wasm", wasm_bindgen(getter))]
+    pub fn number_value(&self) -> Option<f64> {
+        match self.typed_value {
+            ExcelValue::Number(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    #[cfg_attr(feature = "wasm", wasm_bindgen(getter))]
+    pub fn bool_value(&self) -> Option<bool> {
+        match self.typed_value {
+            ExcelValue::Bool(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "wasm")]
+    #[wasm_bindgen(getter)]
+    pub fn date_value(&self) -> Option<js_sys::Date> {
+        match &self.typed_value {
+            ExcelValue::DateTime(value) => Some(ExcelValue::to_js_date(value)),
+            _ => None,
+        }
+    }
+
+    #[cfg_attr(feature = "wasm", wasm_bindgen(getter))]
+    pub fn link_url(&self) -> Option<String> {
+        match &self.typed_value {
+            ExcelValue::Link { url, .. } => Some(url.clone()),
+            _ => None,
+        }
+    }
+
+    #[cfg_attr(feature = "wasm", wasm_bindgen(getter))]
+    pub fn link_text(&self) -> Option<String> {
+        match &self.typed_value {
+            ExcelValue::Link { text, .. } => Some(text.clone()),
+            _ => None,
+        }
+    }
 }
 
 impl fmt::Debug for ExcelColumnData {
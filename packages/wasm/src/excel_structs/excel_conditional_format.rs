@@ -0,0 +1,136 @@
+use crate::excel_structs::excel_info::ExcelCellFormat;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+/// Rules that compare the cell with `value`, or with `value` and `endValue`.
+const CELL_RULES: [&str; 8] = [
+    "eq",
+    "ne",
+    "gt",
+    "gte",
+    "lt",
+    "lte",
+    "between",
+    "notBetween",
+];
+const TEXT_RULES: [&str; 2] = ["contains", "regex"];
+const BLANK_RULES: [&str; 2] = ["blank", "notBlank"];
+const SCALE_RULES: [&str; 2] = ["colorScale", "dataBar"];
+
+/// A rule written as an Excel conditional format over the exported rows of a
+/// column, so it keeps applying when values are edited in Excel.
+///
+/// Excel has no regex rule, so `regex` is written as a `REGEXTEST` formula.
+/// Only Excel for Microsoft 365 has that function. Excel 2021 and earlier,
+/// LibreOffice and Google Sheets treat the formula as an error and never
+/// apply the format.
+#[cfg_attr(feature = "wasm", wasm_bindgen(getter_with_clone))]
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ExcelConditionalFormat {
+    pub rule: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub value: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_value: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<ExcelCellFormat>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub colors: Vec<String>,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl ExcelConditionalFormat {
+    #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+    pub fn bind_new(rule: String) -> ExcelConditionalFormat {
+        ExcelConditionalFormat::new(rule)
+    }
+
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withValue))]
+    pub fn bind_with_value(self, value: String) -> Self {
+        self.with_value(value)
+    }
+
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withEndValue))]
+    pub fn bind_with_end_value(self, end_value: String) -> Self {
+        self.with_end_value(end_value)
+    }
+
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withFormat))]
+    pub fn with_format(mut self, format: ExcelCellFormat) -> Self {
+        self.format = Some(format);
+        self
+    }
+
+    /// The minimum, optional midpoint and maximum colors of a `colorScale`,
+    /// or the fill color of a `dataBar`.
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withColors))]
+    pub fn with_colors(mut self, colors: Vec<String>) -> Self {
+        self.colors = colors;
+        self
+    }
+}
+
+impl ExcelConditionalFormat {
+    pub fn new<T: Into<String>>(rule: T) -> Self {
+        ExcelConditionalFormat {
+            rule: rule.into(),
+            ..Default::default()
+        }
+    }
+
+    /// The compared number or text, the searched text, or the regex pattern.
+    pub fn with_value<T: Into<String>>(mut self, value: T) -> Self {
+        self.value = value.into();
+        self
+    }
+
+    /// The upper bound of `between` and `notBetween`.
+    pub fn with_end_value<T: Into<String>>(mut self, end_value: T) -> Self {
+        self.end_value = Some(end_value.into());
+        self
+    }
+
+    pub fn is_supported_rule(&self) -> bool {
+        get_rules().any(|rule| rule == self.rule)
+    }
+
+    /// Whether the rule paints cells with `format` rather than a scale.
+    pub fn needs_format(&self) -> bool {
+        !SCALE_RULES.contains(&self.rule.as_str())
+    }
+
+    pub fn needs_value(&self) -> bool {
+        CELL_RULES.contains(&self.rule.as_str()) || TEXT_RULES.contains(&self.rule.as_str())
+    }
+
+    pub fn needs_end_value(&self) -> bool {
+        self.rule == "between" || self.rule == "notBetween"
+    }
+
+    /// The number of colors the rule accepts besides none.
+    pub fn get_color_counts(&self) -> &'static [usize] {
+        match self.rule.as_str() {
+            "colorScale" => &[2, 3],
+            "dataBar" => &[1],
+            _ => &[],
+        }
+    }
+
+    pub fn get_supported_rules() -> String {
+        get_rules().collect::<Vec<_>>().join(", ")
+    }
+}
+
+fn get_rules() -> impl Iterator<Item = &'static str> {
+    [
+        CELL_RULES.as_slice(),
+        &TEXT_RULES,
+        &BLANK_RULES,
+        &SCALE_RULES,
+    ]
+    .into_iter()
+    .flatten()
+    .copied()
+}
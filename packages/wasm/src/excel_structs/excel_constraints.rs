@@ -0,0 +1,123 @@
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+/// Value rules of a column, written as Excel data validations in templates
+/// and checked on import.
+#[cfg_attr(feature = "wasm", wasm_bindgen(getter_with_clone))]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ExcelConstraints {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_length: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_length: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub input_message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_message: Option<String>,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl ExcelConstraints {
+    #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+    pub fn new() -> ExcelConstraints {
+        ExcelConstraints::default()
+    }
+
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withMin))]
+    pub fn with_min(mut self, min: f64) -> Self {
+        self.min = Some(min);
+        self
+    }
+
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withMax))]
+    pub fn with_max(mut self, max: f64) -> Self {
+        self.max = Some(max);
+        self
+    }
+
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withMinLength))]
+    pub fn with_min_length(mut self, min_length: u32) -> Self {
+        self.min_length = Some(min_length);
+        self
+    }
+
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withMaxLength))]
+    pub fn with_max_length(mut self, max_length: u32) -> Self {
+        self.max_length = Some(max_length);
+        self
+    }
+
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withPattern))]
+    pub fn bind_with_pattern(self, pattern: String) -> Self {
+        self.with_pattern(pattern)
+    }
+
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withInputMessage))]
+    pub fn bind_with_input_message(self, input_message: String) -> Self {
+        self.with_input_message(input_message)
+    }
+
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withErrorMessage))]
+    pub fn bind_with_error_message(self, error_message: String) -> Self {
+        self.with_error_message(error_message)
+    }
+}
+
+impl ExcelConstraints {
+    /// A regular expression the cell text must match, such as `^[A-Z]{3}$`.
+    pub fn with_pattern<T: Into<String>>(mut self, pattern: T) -> Self {
+        self.pattern = Some(pattern.into());
+        self
+    }
+
+    /// The hint Excel shows when a template cell is selected.
+    pub fn with_input_message<T: Into<String>>(mut self, input_message: T) -> Self {
+        self.input_message = Some(input_message.into());
+        self
+    }
+
+    /// The message Excel shows when a typed value breaks the rules.
+    pub fn with_error_message<T: Into<String>>(mut self, error_message: T) -> Self {
+        self.error_message = Some(error_message.into());
+        self
+    }
+
+    pub fn has_range(&self) -> bool {
+        self.min.is_some() || self.max.is_some()
+    }
+
+    pub fn has_length(&self) -> bool {
+        self.min_length.is_some() || self.max_length.is_some()
+    }
+
+    /// Describes the rules in one sentence each, for the validation messages.
+    pub fn describe(&self) -> Vec<String> {
+        let mut rules = Vec::new();
+        match (self.min, self.max) {
+            (Some(min), Some(max)) => {
+                rules.push(format!("Enter a number from {} to {}.", min, max))
+            }
+            (Some(min), None) => rules.push(format!("Enter a number of at least {}.", min)),
+            (None, Some(max)) => rules.push(format!("Enter a number of at most {}.", max)),
+            (None, None) => {}
+        }
+        match (self.min_length, self.max_length) {
+            (Some(min), Some(max)) => rules.push(format!("Enter {} to {} characters.", min, max)),
+            (Some(min), None) => rules.push(format!("Enter at least {} characters.", min)),
+            (None, Some(max)) => rules.push(format!("Enter at most {} characters.", max)),
+            (None, None) => {}
+        }
+        if let Some(pattern) = self.pattern.as_ref() {
+            rules.push(format!("Must match {}.", pattern));
+        }
+        rules
+    }
+}
@@ -1,25 +1,32 @@
+use super::excel_column_binding::ExcelColumnBinding;
 use super::excel_row_data::ExcelRowData;
 use std::fmt;
+#[cfg(feature = "wasm")]
 use wasm_bindgen::prelude::*;
 
-#[wasm_bindgen(getter_with_clone)]
+#[cfg_attr(feature = "wasm", wasm_bindgen(getter_with_clone))]
 #[derive(Clone)]
 pub struct ExcelData {
     pub rows: Vec<ExcelRowData>,
+    /// Header cells the schema columns were bound to. Filled on import only.
+    pub column_bindings: Vec<ExcelColumnBinding>,
 }
 
-#[wasm_bindgen]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
 impl ExcelData {
-    #[wasm_bindgen(constructor)]
+    #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
     pub fn new(rows: Vec<ExcelRowData>) -> ExcelData {
-        ExcelData { rows }
+        ExcelData {
+            rows,
+            column_bindings: Vec::new(),
+        }
     }
 }
 
 impl fmt::Debug for ExcelData {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         writeln!(f, "data")?;
-        for (_, column_data) in self.rows.iter().enumerate() {
+        for column_data in self.rows.iter() {
             writeln!(f, "  {:?}", column_data)?
         }
         Ok(())
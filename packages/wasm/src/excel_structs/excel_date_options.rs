@@ -0,0 +1,117 @@
+use chrono::format::{Item, StrftimeItems};
+use chrono::FixedOffset;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+/// How a `date`, `dateOnly` or `timeOnly` column is parsed, displayed and
+/// read back. Patterns use chrono `strftime` syntax, such as `%d/%m/%Y`.
+#[cfg_attr(feature = "wasm", wasm_bindgen(getter_with_clone))]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ExcelDateOptions {
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub parse_patterns: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_pattern: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub excel_pattern: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timezone_offset: Option<String>,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl ExcelDateOptions {
+    #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+    pub fn new() -> ExcelDateOptions {
+        ExcelDateOptions::default()
+    }
+
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withParsePatterns))]
+    pub fn with_parse_patterns(mut self, parse_patterns: Vec<String>) -> Self {
+        self.parse_patterns = parse_patterns;
+        self
+    }
+
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withOutputPattern))]
+    pub fn bind_with_output_pattern(self, output_pattern: String) -> Self {
+        self.with_output_pattern(output_pattern)
+    }
+
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withExcelPattern))]
+    pub fn bind_with_excel_pattern(self, excel_pattern: String) -> Self {
+        self.with_excel_pattern(excel_pattern)
+    }
+
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withTimezoneOffset))]
+    pub fn bind_with_timezone_offset(self, timezone_offset: String) -> Self {
+        self.with_timezone_offset(timezone_offset)
+    }
+}
+
+impl ExcelDateOptions {
+    /// The `strftime` pattern used for the `value` text of imported dates.
+    pub fn with_output_pattern<T: Into<String>>(mut self, output_pattern: T) -> Self {
+        self.output_pattern = Some(output_pattern.into());
+        self
+    }
+
+    /// The Excel number format of the written cells, such as `dd/mm/yyyy`.
+    pub fn with_excel_pattern<T: Into<String>>(mut self, excel_pattern: T) -> Self {
+        self.excel_pattern = Some(excel_pattern.into());
+        self
+    }
+
+    /// The offset of the sheet's wall-clock times, such as `+08:00` or `Z`.
+    pub fn with_timezone_offset<T: Into<String>>(mut self, timezone_offset: T) -> Self {
+        self.timezone_offset = Some(timezone_offset.into());
+        self
+    }
+
+    /// The parsed `timezoneOffset`. Unset and invalid offsets return `None`.
+    pub fn get_offset(&self) -> Option<FixedOffset> {
+        parse_timezone_offset(self.timezone_offset.as_deref()?)
+    }
+
+    /// Returns the first pattern chrono cannot read, if any.
+    pub fn find_invalid_pattern(&self) -> Option<&str> {
+        self.parse_patterns
+            .iter()
+            .chain(self.output_pattern.iter())
+            .map(String::as_str)
+            .find(|pattern| !is_valid_pattern(pattern))
+    }
+}
+
+fn is_valid_pattern(pattern: &str) -> bool {
+    !pattern.trim().is_empty() && !StrftimeItems::new(pattern).any(|item| item == Item::Error)
+}
+
+/// Parses `Z`, `UTC`, `+08:00`, `+0800` or `-05` into an offset.
+pub(crate) fn parse_timezone_offset(value: &str) -> Option<FixedOffset> {
+    let value = value.trim();
+    if value.eq_ignore_ascii_case("z") || value.eq_ignore_ascii_case("utc") {
+        return FixedOffset::east_opt(0);
+    }
+    let (sign, digits) = match value.split_at_checked(1)? {
+        ("+", digits) => (1, digits),
+        ("-", digits) => (-1, digits),
+        _ => return None,
+    };
+    let (hours, minutes) = match digits.split_once(':') {
+        Some(parts) => parts,
+        None if digits.len() > 2 => digits.split_at_checked(2)?,
+        None => (digits, "0"),
+    };
+    let is_number =
+        |part: &str| (1..=2).contains(&part.len()) && part.bytes().all(|b| b.is_ascii_digit());
+    if !is_number(hours) || !is_number(minutes) {
+        return None;
+    }
+    let hours: i32 = hours.parse().ok()?;
+    let minutes: i32 = minutes.parse().ok()?;
+    if hours > 14 || minutes > 59 {
+        return None;
+    }
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+}
@@ -0,0 +1,27 @@
+use super::excel_column_binding::ExcelColumnBinding;
+use std::fmt;
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+#[cfg_attr(feature = "wasm", wasm_bindgen(getter_with_clone))]
+#[derive(Clone)]
+pub struct ExcelImportSummary {
+    pub sheet_name: String,
+    pub total_rows: u32,
+    pub imported_rows: u32,
+    pub aborted: bool,
+    pub column_bindings: Vec<ExcelColumnBinding>,
+}
+
+impl fmt::Debug for ExcelImportSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {}/{} rows{}",
+            self.sheet_name,
+            self.imported_rows,
+            self.total_rows,
+            if self.aborted { " (aborted)" } else { "" }
+        )
+    }
+}
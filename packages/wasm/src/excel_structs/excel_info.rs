@@ -1,26 +1,82 @@
+use crate::callbacks::{ImageFetcher, ProgressReporter};
+use crate::excel_structs::excel_auto_width::ExcelAutoWidth;
+use crate::excel_structs::excel_conditional_format::ExcelConditionalFormat;
+use crate::excel_structs::excel_constraints::ExcelConstraints;
+use crate::excel_structs::excel_date_options::ExcelDateOptions;
+use crate::excel_structs::excel_number_format::ExcelNumberFormat;
+use crate::excel_structs::excel_row_format::ExcelRowFormat;
+use crate::excel_structs::excel_summary_options::{ExcelAggregate, ExcelSummaryOptions};
+use crate::excel_structs::excel_table_options::{ExcelTableOptions, ExcelTableTotal};
+#[cfg(feature = "wasm")]
+use crate::wasm::{JsImageFetcher, JsProgressReporter};
+#[cfg(feature = "wasm")]
 use js_sys::{Function, Object, Reflect};
+use serde::{Deserialize, Deserializer, Serialize};
 use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+#[cfg(feature = "wasm")]
 use wasm_bindgen::prelude::*;
 
-#[wasm_bindgen(getter_with_clone)]
+#[cfg_attr(feature = "wasm", wasm_bindgen(getter_with_clone))]
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct ExcelInfo {
     pub name: String,
+    #[serde(default = "default_sheet_name")]
     pub sheet_name: String,
     pub columns: Vec<ExcelColumnInfo>,
+    #[serde(default)]
     pub author: String,
+    #[serde(default)]
     pub create_time: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub title_format: Option<ExcelCellFormat>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub title_height: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub default_row_height: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub header_row_height: Option<f64>,
+    #[serde(default)]
     pub dx: u16,
+    #[serde(default)]
     pub dy: u32,
+    #[serde(default)]
     pub is_header_freeze: bool,
-    #[wasm_bindgen(skip)]
-    pub progress_callback: Option<Function>,
-    #[wasm_bindgen(skip)]
-    pub image_fetcher: Option<Function>,
+    #[serde(default)]
+    pub import_by_sheet_name: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub import_format: Option<String>,
+    #[serde(default)]
+    pub match_headers_by_name: bool,
+    #[serde(default)]
+    pub ignore_header_case: bool,
+    #[serde(default)]
+    pub normalize_header_whitespace: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub import_number_mode: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub row_formats: Vec<ExcelRowFormat>,
+    #[serde(default)]
+    pub autofilter: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub table: Option<ExcelTableOptions>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auto_width: Option<ExcelAutoWidth>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<ExcelSummaryOptions>,
+    #[cfg_attr(feature = "wasm", wasm_bindgen(skip))]
+    #[serde(skip)]
+    pub progress_callback: Option<Arc<dyn ProgressReporter>>,
+    #[cfg_attr(feature = "wasm", wasm_bindgen(skip))]
+    #[serde(skip)]
+    pub image_fetcher: Option<Arc<dyn ImageFetcher>>,
+}
+
+fn default_sheet_name() -> String {
+    "sheet1".into()
 }
 
 impl ExcelInfo {
@@ -46,18 +102,56 @@ impl ExcelInfo {
             dx: 0,
             dy: 0,
             is_header_freeze: false,
+            import_by_sheet_name: false,
+            import_format: None,
+            match_headers_by_name: false,
+            ignore_header_case: false,
+            normalize_header_whitespace: false,
+            import_number_mode: None,
+            row_formats: Vec::new(),
+            autofilter: false,
+            table: None,
+            auto_width: None,
+            summary: None,
             progress_callback: None,
             image_fetcher: None,
         })
     }
 
-    pub fn with_progress_callback(mut self, callback: Function) -> Self {
-        self.progress_callback = Some(callback);
+    /// Parses the camelCase JSON shape of a schema and validates the columns
+    /// the same way as [`ExcelInfo::new`].
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let info: ExcelInfo =
+            serde_json::from_str(json).map_err(|error| format!("Invalid definition: {}", error))?;
+        ExcelInfo::check_columns(&info.columns)?;
+        info.check_sheet_options()?;
+        Ok(info)
+    }
+
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string(self).map_err(|error| error.to_string())
+    }
+
+    pub fn with_progress_callback<R: ProgressReporter + 'static>(mut self, reporter: R) -> Self {
+        self.progress_callback = Some(Arc::new(reporter));
         self
     }
 
-    pub fn with_image_fetcher(mut self, fetcher: Function) -> Self {
-        self.image_fetcher = Some(fetcher);
+    pub fn with_image_fetcher<F: ImageFetcher + 'static>(mut self, fetcher: F) -> Self {
+        self.image_fetcher = Some(Arc::new(fetcher));
+        self
+    }
+
+    pub fn with_import_format<T: Into<String>>(mut self, import_format: T) -> Self {
+        self.import_format = Some(import_format.into());
+        self
+    }
+
+    /// Sets how imported numbers are returned: `raw` keeps the stored value,
+    /// `round` rounds to the column `numberFormat` and `format` also renders
+    /// `value` with it.
+    pub fn with_import_number_mode<T: Into<String>>(mut self, import_number_mode: T) -> Self {
+        self.import_number_mode = Some(import_number_mode.into());
         self
     }
 
@@ -93,10 +187,215 @@ impl ExcelInfo {
         leaf_columns
     }
 
-    fn is_supported_data_type(data_type: &str) -> bool {
-        ["text", "number", "date", "image"]
+    /// Validates `rowFormats`, `table` and `summary` against the columns. `from_json`
+    /// and exports call it, since both are set after the columns are checked.
+    pub fn check_sheet_options(&self) -> Result<(), String> {
+        let has_column = |key: &str| self.columns.iter().any(|column| column.key == key);
+        for row_format in self.row_formats.iter() {
+            if !has_column(&row_format.column_key) {
+                return Err(format!(
+                    "Invalid definition: rowFormat references unknown column '{}'.",
+                    row_format.column_key
+                ));
+            }
+            if !row_format.is_supported_rule() {
+                return Err(format!(
+                    "Invalid definition: rowFormat on column '{}' uses unsupported rule '{}'. Supported values are {}.",
+                    row_format.column_key,
+                    row_format.rule,
+                    ExcelRowFormat::get_supported_rules()
+                ));
+            }
+            if row_format.is_numeric_rule() && row_format.value.trim().parse::<f64>().is_err() {
+                return Err(format!(
+                    "Invalid definition: rowFormat on column '{}' compares with '{}', which is not a number.",
+                    row_format.column_key, row_format.value
+                ));
+            }
+            if row_format.format.is_none() {
+                return Err(format!(
+                    "Invalid definition: rowFormat on column '{}' must define a format.",
+                    row_format.column_key
+                ));
+            }
+            if let Some(key) = row_format.columns.iter().find(|key| !has_column(key)) {
+                return Err(format!(
+                    "Invalid definition: rowFormat on column '{}' targets unknown column '{}'.",
+                    row_format.column_key, key
+                ));
+            }
+        }
+        if let Some(table) = self.table.as_ref() {
+            self.check_table(table)?;
+        }
+        if let Some(auto_width) = self.auto_width.as_ref() {
+            if !auto_width.is_valid() {
+                return Err(format!(
+                    "Invalid definition: autoWidth needs 0 <= minWidth <= maxWidth <= 255 and a non-negative padding, got minWidth {}, maxWidth {} and padding {}.",
+                    auto_width.min_width, auto_width.max_width, auto_width.padding
+                ));
+            }
+        }
+        if let Some(summary) = self.summary.as_ref() {
+            self.check_summary(summary)?;
+        }
+        Ok(())
+    }
+
+    fn check_summary(&self, summary: &ExcelSummaryOptions) -> Result<(), String> {
+        if self.table.is_some() {
+            return Err(
+                "Invalid definition: summary rows cannot be combined with table output. Use table totals instead."
+                    .into(),
+            );
+        }
+        if summary.aggregates.is_empty() {
+            return Err("Invalid definition: summary must define at least one aggregate.".into());
+        }
+        if summary.get_label().trim().is_empty() || summary.get_subtotal_label().trim().is_empty() {
+            return Err("Invalid definition: summary labels cannot be blank.".into());
+        }
+        if summary.subtotals && self.autofilter {
+            return Err(
+                "Invalid definition: summary subtotals cannot be combined with autofilter, which would sort and filter the subtotal rows."
+                    .into(),
+            );
+        }
+        if summary.get_label() == summary.get_subtotal_label() {
+            return Err(format!(
+                "Invalid definition: summary label and subtotal label must differ, both are '{}'.",
+                summary.get_label()
+            ));
+        }
+        let parent_map = self.get_parent_map();
+        let label_column = self
+            .get_leaf_columns()
+            .first()
+            .map(|column| column.key.as_str());
+        for aggregate in summary.aggregates.iter() {
+            let Some(column) = self
+                .columns
+                .iter()
+                .find(|column| column.key == aggregate.column_key)
+            else {
+                return Err(format!(
+                    "Invalid definition: summary aggregate references unknown column '{}'.",
+                    aggregate.column_key
+                ));
+            };
+            if !aggregate.is_supported_function() {
+                return Err(format!(
+                    "Invalid definition: summary aggregate of column '{}' uses unsupported function '{}'. Supported values are {}.",
+                    aggregate.column_key,
+                    aggregate.function,
+                    ExcelAggregate::get_supported_functions()
+                ));
+            }
+            if parent_map.contains_key(&column.key) || !column.data_group.is_empty() {
+                return Err(format!(
+                    "Invalid definition: summary aggregate of column '{}' must target a leaf column.",
+                    aggregate.column_key
+                ));
+            }
+            if label_column == Some(column.key.as_str()) {
+                return Err(format!(
+                    "Invalid definition: summary aggregate cannot use column '{}', since the first leaf column holds the label.",
+                    aggregate.column_key
+                ));
+            }
+            if summary.precomputed && column.formula.is_some() {
+                return Err(format!(
+                    "Invalid definition: summary aggregate of formula column '{}' cannot be precomputed.",
+                    aggregate.column_key
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// The width fitting of `column`: the sheet `autoWidth`, unless the
+    /// column opts out, or the default clamps when only the column opts in.
+    pub fn get_auto_width(&self, column: &ExcelColumnInfo) -> Option<ExcelAutoWidth> {
+        match column.auto_width {
+            Some(false) => None,
+            Some(true) => Some(self.auto_width.clone().unwrap_or_default()),
+            None => self.auto_width.clone(),
+        }
+    }
+
+    fn check_table(&self, table: &ExcelTableOptions) -> Result<(), String> {
+        if self
+            .columns
+            .iter()
+            .any(|column| column.has_parent() || !column.data_group.is_empty())
+        {
+            return Err(
+                "Invalid definition: table output requires a single header row without parent or dataGroup columns."
+                    .into(),
+            );
+        }
+        if let Some(name) = table.name.as_deref() {
+            if !ExcelTableOptions::is_valid_name(name) {
+                return Err(format!(
+                    "Invalid definition: table name '{}' must start with a letter or underscore, use only letters, digits, underscores or periods, and not look like a cell reference.",
+                    name
+                ));
+            }
+        }
+        if let Some(style) = table.style.as_deref() {
+            if table.get_style().is_none() {
+                return Err(format!(
+                    "Invalid definition: table style '{}' is not a built-in Excel table style such as TableStyleMedium9.",
+                    style
+                ));
+            }
+        }
+        let mut names = HashSet::new();
+        if let Some(column) = self
+            .columns
             .iter()
-            .any(|candidate| data_type.eq_ignore_ascii_case(candidate))
+            .find(|column| !names.insert(column.name.to_lowercase()))
+        {
+            return Err(format!(
+                "Invalid definition: table output requires unique header names. Duplicate header '{}' found.",
+                column.name
+            ));
+        }
+        for total in table.totals.iter() {
+            let position = self
+                .columns
+                .iter()
+                .position(|column| column.key == total.column_key);
+            let Some(position) = position else {
+                return Err(format!(
+                    "Invalid definition: table total references unknown column '{}'.",
+                    total.column_key
+                ));
+            };
+            if !total.is_supported_function() {
+                return Err(format!(
+                    "Invalid definition: table total of column '{}' uses unsupported function '{}'. Supported values are {}.",
+                    total.column_key,
+                    total.function,
+                    ExcelTableTotal::get_supported_functions()
+                ));
+            }
+            if position == 0 {
+                return Err(format!(
+                    "Invalid definition: table total cannot use column '{}', since the first column holds the total label.",
+                    total.column_key
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn is_supported_data_type(data_type: &str) -> bool {
+        [
+            "text", "number", "date", "dateOnly", "timeOnly", "image", "boolean", "link",
+        ]
+        .iter()
+        .any(|candidate| data_type.eq_ignore_ascii_case(candidate))
     }
 
     fn check_columns(columns: &[ExcelColumnInfo]) -> Result<(), String> {
@@ -120,10 +419,40 @@ impl ExcelInfo {
             }
             if !ExcelInfo::is_supported_data_type(&column.data_type) {
                 return Err(format!(
-                    "Invalid definition: column '{}' uses unsupported dataType '{}'. Supported values are text, number, date, image.",
+                    "Invalid definition: column '{}' uses unsupported dataType '{}'. Supported values are text, number, date, dateOnly, timeOnly, image, boolean, link.",
                     column.key, column.data_type
                 ));
             }
+            if let Some(number_format) = column.number_format.as_ref() {
+                if !ExcelNumberFormat::is_supported_style(&number_format.style) {
+                    return Err(format!(
+                        "Invalid definition: column '{}' uses unsupported numberFormat style '{}'. Supported values are number, percent, currency, accounting.",
+                        column.key, number_format.style
+                    ));
+                }
+            }
+            if let Some(date_options) = column.date_options.as_ref() {
+                if let Some(pattern) = date_options.find_invalid_pattern() {
+                    return Err(format!(
+                        "Invalid definition: column '{}' uses invalid dateOptions pattern '{}'.",
+                        column.key, pattern
+                    ));
+                }
+                if let Some(offset) = date_options.timezone_offset.as_deref() {
+                    if date_options.get_offset().is_none() {
+                        return Err(format!(
+                            "Invalid definition: column '{}' uses invalid dateOptions timezoneOffset '{}'. Use Z or an offset such as +08:00.",
+                            column.key, offset
+                        ));
+                    }
+                }
+            }
+            if let Some(constraints) = column.constraints.as_ref() {
+                ExcelInfo::check_constraints(column, constraints)?;
+            }
+            for conditional_format in column.conditional_formats.iter() {
+                ExcelInfo::check_conditional_format(column, conditional_format)?;
+            }
             if column.has_parent() {
                 if column.parent == column.key {
                     return Err(format!(
@@ -165,10 +494,170 @@ impl ExcelInfo {
             }
             processed_columns.insert(column.key.clone());
         }
+        for column in columns.iter() {
+            let Some(formula) = column.formula.as_deref() else {
+                continue;
+            };
+            if !column.data_group.is_empty() {
+                return Err(format!(
+                    "Invalid definition: formula column '{}' cannot define a dataGroup.",
+                    column.key
+                ));
+            }
+            for token in split_formula_template(formula) {
+                let FormulaToken::Key(key) = token else {
+                    continue;
+                };
+                if key == column.key || !processed_columns.contains(key) {
+                    return Err(format!(
+                        "Invalid definition: formula of column '{}' references unknown column '{}'.",
+                        column.key, key
+                    ));
+                }
+            }
+        }
         Ok(())
     }
+
+    fn check_constraints(
+        column: &ExcelColumnInfo,
+        constraints: &ExcelConstraints,
+    ) -> Result<(), String> {
+        if constraints.has_range() && !column.data_type.eq_ignore_ascii_case("number") {
+            return Err(format!(
+                "Invalid definition: column '{}' can only use min and max constraints with the number dataType.",
+                column.key
+            ));
+        }
+        if let (Some(min), Some(max)) = (constraints.min, constraints.max) {
+            if min > max {
+                return Err(format!(
+                    "Invalid definition: column '{}' has a min constraint greater than its max.",
+                    column.key
+                ));
+            }
+        }
+        if let (Some(min_length), Some(max_length)) =
+            (constraints.min_length, constraints.max_length)
+        {
+            if min_length > max_length {
+                return Err(format!(
+                    "Invalid definition: column '{}' has a minLength constraint greater than its maxLength.",
+                    column.key
+                ));
+            }
+        }
+        if let Some(pattern) = constraints.pattern.as_deref() {
+            if regex::Regex::new(pattern).is_err() {
+                return Err(format!(
+                    "Invalid definition: column '{}' uses invalid constraint pattern '{}'.",
+                    column.key, pattern
+                ));
+            }
+        }
+        let messages = [&constraints.input_message, &constraints.error_message];
+        if messages
+            .iter()
+            .filter_map(|message| message.as_deref())
+            .any(|message| message.chars().count() > 255)
+        {
+            return Err(format!(
+                "Invalid definition: column '{}' has a constraint message longer than Excel's limit of 255 characters.",
+                column.key
+            ));
+        }
+        Ok(())
+    }
+
+    fn check_conditional_format(
+        column: &ExcelColumnInfo,
+        conditional_format: &ExcelConditionalFormat,
+    ) -> Result<(), String> {
+        let rule = &conditional_format.rule;
+        if !conditional_format.is_supported_rule() {
+            return Err(format!(
+                "Invalid definition: column '{}' uses unsupported conditionalFormat rule '{}'. Supported values are {}.",
+                column.key,
+                rule,
+                ExcelConditionalFormat::get_supported_rules()
+            ));
+        }
+        if conditional_format.needs_value() && conditional_format.value.is_empty() {
+            return Err(format!(
+                "Invalid definition: column '{}' uses conditionalFormat rule '{}' without a value.",
+                column.key, rule
+            ));
+        }
+        if conditional_format.needs_end_value() && conditional_format.end_value.is_none() {
+            return Err(format!(
+                "Invalid definition: column '{}' uses conditionalFormat rule '{}' without an endValue.",
+                column.key, rule
+            ));
+        }
+        if conditional_format.needs_format() && conditional_format.format.is_none() {
+            return Err(format!(
+                "Invalid definition: column '{}' uses conditionalFormat rule '{}' without a format.",
+                column.key, rule
+            ));
+        }
+        if rule == "regex" && regex::Regex::new(&conditional_format.value).is_err() {
+            return Err(format!(
+                "Invalid definition: column '{}' uses invalid conditionalFormat pattern '{}'.",
+                column.key, conditional_format.value
+            ));
+        }
+        let color_count = conditional_format.colors.len();
+        if color_count > 0 && !conditional_format.get_color_counts().contains(&color_count) {
+            return Err(format!(
+                "Invalid definition: column '{}' passes {} colors to conditionalFormat rule '{}'. colorScale takes 2 or 3 colors and dataBar takes 1.",
+                column.key, color_count, rule
+            ));
+        }
+        Ok(())
+    }
+}
+
+pub(crate) enum FormulaToken<'a> {
+    Text(&'a str),
+    Key(&'a str),
 }
 
+/// Splits a formula template into literal text and `{key}` placeholders.
+/// Braces that do not wrap a key-like name, such as array constants, stay literal.
+pub(crate) fn split_formula_template(template: &str) -> Vec<FormulaToken<'_>> {
+    let mut tokens = Vec::new();
+    let mut rest = template;
+    let mut text_start = 0;
+    let mut offset = 0;
+    while let Some(open) = rest.find('{') {
+        let Some(close) = rest[open + 1..].find('}') else {
+            break;
+        };
+        let key = &rest[open + 1..open + 1 + close];
+        let is_key = key
+            .chars()
+            .next()
+            .is_some_and(|first| first.is_alphabetic() || first == '_')
+            && key
+                .chars()
+                .all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.'));
+        if is_key {
+            if offset + open > text_start {
+                tokens.push(FormulaToken::Text(&template[text_start..offset + open]));
+            }
+            tokens.push(FormulaToken::Key(key));
+            text_start = offset + open + close + 2;
+        }
+        offset += open + 1;
+        rest = &template[offset..];
+    }
+    if text_start < template.len() {
+        tokens.push(FormulaToken::Text(&template[text_start..]));
+    }
+    tokens
+}
+
+#[cfg(feature = "wasm")]
 fn invalid_definition_error_to_js(reason: &str) -> JsValue {
     let error = js_sys::Error::new(reason);
     let value: JsValue = error.into();
@@ -193,8 +682,9 @@ fn invalid_definition_error_to_js(reason: &str) -> JsValue {
     value
 }
 
-#[wasm_bindgen]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
 impl ExcelInfo {
+    #[cfg(feature = "wasm")]
     #[wasm_bindgen(constructor)]
     pub fn bind_new(
         name: String,
@@ -207,61 +697,150 @@ impl ExcelInfo {
             .map_err(|error| invalid_definition_error_to_js(&error))
     }
 
-    #[wasm_bindgen(js_name = withTitle)]
+    #[cfg(feature = "wasm")]
+    #[wasm_bindgen(js_name = fromJson)]
+    pub fn bind_from_json(json: &str) -> Result<ExcelInfo, JsValue> {
+        ExcelInfo::from_json(json).map_err(|error| invalid_definition_error_to_js(&error))
+    }
+
+    #[cfg(feature = "wasm")]
+    #[wasm_bindgen(js_name = toJson)]
+    pub fn bind_to_json(&self) -> Result<String, JsValue> {
+        self.to_json()
+            .map_err(|error| invalid_definition_error_to_js(&error))
+    }
+
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withTitle))]
     pub fn bind_with_title(self, title: String) -> Self {
         self.with_title(title)
     }
 
-    #[wasm_bindgen(js_name = withDefaultRowHeight)]
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withDefaultRowHeight))]
     pub fn with_default_row_height(mut self, row_height: f64) -> Self {
         self.default_row_height = Some(row_height);
         self
     }
 
-    #[wasm_bindgen(js_name = withHeaderRowHeight)]
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withHeaderRowHeight))]
     pub fn with_header_row_height(mut self, row_height: f64) -> Self {
         self.header_row_height = Some(row_height);
         self
     }
 
-    #[wasm_bindgen(js_name = withTitleHeight)]
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withTitleHeight))]
     pub fn with_title_height(mut self, title_height: f64) -> Self {
         self.title_height = Some(title_height);
         self
     }
 
-    #[wasm_bindgen(js_name= withTitleFormat)]
+    /// Formats rows, or some of their columns, by the value of any column.
+    /// The first matching rule wins over the column `valueFormat`.
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withRowFormats))]
+    pub fn with_row_formats(mut self, row_formats: Vec<ExcelRowFormat>) -> Self {
+        self.row_formats = row_formats;
+        self
+    }
+
+    /// Adds filter buttons to the leaf header row over the exported rows.
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withAutofilter))]
+    pub fn with_autofilter(mut self, autofilter: bool) -> Self {
+        self.autofilter = autofilter;
+        self
+    }
+
+    /// Writes the header and exported rows as an Excel Table.
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withTable))]
+    pub fn with_table(mut self, table: ExcelTableOptions) -> Self {
+        self.table = Some(table);
+        self
+    }
+
+    /// Adds totals and subtotal rows with the aggregates of some columns.
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withSummary))]
+    pub fn with_summary(mut self, summary: ExcelSummaryOptions) -> Self {
+        self.summary = Some(summary);
+        self
+    }
+
+    /// Fits leaf column widths to the header and the exported values.
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withAutoWidth))]
+    pub fn with_auto_width(mut self, auto_width: ExcelAutoWidth) -> Self {
+        self.auto_width = Some(auto_width);
+        self
+    }
+
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name= withTitleFormat))]
     pub fn with_title_format(mut self, title_format: ExcelCellFormat) -> Self {
         self.title_format = Some(title_format);
         self
     }
 
-    #[wasm_bindgen(js_name = withOffset)]
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withOffset))]
     pub fn with_offset(mut self, dx: u16, dy: u32) -> Self {
         self.dx = dx;
         self.dy = dy;
         self
     }
 
-    #[wasm_bindgen(js_name = withIsHeaderFreeze)]
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withIsHeaderFreeze))]
     pub fn with_is_header_freeze(mut self, is_header_freeze: bool) -> Self {
         self.is_header_freeze = is_header_freeze;
         self
     }
 
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withImportBySheetName))]
+    pub fn with_import_by_sheet_name(mut self, import_by_sheet_name: bool) -> Self {
+        self.import_by_sheet_name = import_by_sheet_name;
+        self
+    }
+
+    /// Looks schema columns up by header name or alias anywhere in their
+    /// header row instead of requiring the template position.
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withMatchHeadersByName))]
+    pub fn with_match_headers_by_name(mut self, match_headers_by_name: bool) -> Self {
+        self.match_headers_by_name = match_headers_by_name;
+        self
+    }
+
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withIgnoreHeaderCase))]
+    pub fn with_ignore_header_case(mut self, ignore_header_case: bool) -> Self {
+        self.ignore_header_case = ignore_header_case;
+        self
+    }
+
+    /// Collapses runs of whitespace, including line breaks, before comparing headers.
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withNormalizeHeaderWhitespace))]
+    pub fn with_normalize_header_whitespace(mut self, normalize_header_whitespace: bool) -> Self {
+        self.normalize_header_whitespace = normalize_header_whitespace;
+        self
+    }
+
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withImportFormat))]
+    pub fn bind_with_import_format(self, import_format: String) -> Self {
+        self.with_import_format(import_format)
+    }
+
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withImportNumberMode))]
+    pub fn bind_with_import_number_mode(self, import_number_mode: String) -> Self {
+        self.with_import_number_mode(import_number_mode)
+    }
+
+    #[cfg(feature = "wasm")]
     #[wasm_bindgen(js_name = withProgressCallback)]
     pub fn bind_with_progress_callback(self, callback: Function) -> Self {
-        self.with_progress_callback(callback)
+        self.with_progress_callback(JsProgressReporter::new(callback))
     }
 
+    #[cfg(feature = "wasm")]
     #[wasm_bindgen(js_name = withImageFetcher)]
     pub fn bind_with_image_fetcher(self, fetcher: Function) -> Self {
-        self.with_image_fetcher(fetcher)
+        self.with_image_fetcher(JsImageFetcher::new(fetcher))
     }
 }
 
-#[wasm_bindgen(getter_with_clone)]
-#[derive(Clone)]
+#[cfg_attr(feature = "wasm", wasm_bindgen(getter_with_clone))]
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
 pub struct ExcelCellFormat {
     pub rule: String,
     pub value: String,
@@ -274,13 +853,21 @@ pub struct ExcelCellFormat {
     pub background_color: String,
     pub align: String,
     pub align_vertical: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub date_format: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub border_color: Option<String>,
 }
 
-#[wasm_bindgen]
+impl Default for ExcelCellFormat {
+    fn default() -> Self {
+        ExcelCellFormat::new()
+    }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
 impl ExcelCellFormat {
-    #[wasm_bindgen(constructor)]
+    #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
     pub fn new() -> ExcelCellFormat {
         ExcelCellFormat {
             rule: "default".into(),
@@ -299,76 +886,76 @@ impl ExcelCellFormat {
         }
     }
 
-    #[wasm_bindgen(js_name = withRule)]
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withRule))]
     pub fn with_rule(mut self, rule: String) -> Self {
         self.rule = rule;
         self
     }
 
-    #[wasm_bindgen(js_name = withValue)]
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withValue))]
     pub fn with_value(mut self, value: String) -> Self {
         self.value = value;
         self
     }
 
-    #[wasm_bindgen(js_name = withColor)]
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withColor))]
     pub fn bind_with_color(self, color: String) -> Self {
         self.with_color(color)
     }
 
-    #[wasm_bindgen(js_name = withBold)]
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withBold))]
     pub fn with_bold(mut self, bold: bool) -> Self {
         self.bold = bold;
         self
     }
 
-    #[wasm_bindgen(js_name = withItalic)]
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withItalic))]
     pub fn with_italic(mut self, italic: bool) -> Self {
         self.italic = italic;
         self
     }
 
-    #[wasm_bindgen(js_name = withUnderline)]
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withUnderline))]
     pub fn with_underline(mut self, underline: bool) -> Self {
         self.underline = underline;
         self
     }
 
-    #[wasm_bindgen(js_name = withStrikethrough)]
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withStrikethrough))]
     pub fn with_strikethrough(mut self, strikethrough: bool) -> Self {
         self.strikethrough = strikethrough;
         self
     }
 
-    #[wasm_bindgen(js_name = withFontSize)]
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withFontSize))]
     pub fn with_font_size(mut self, font_size: f64) -> Self {
         self.font_size = font_size;
         self
     }
 
-    #[wasm_bindgen(js_name = withBackgroundColor)]
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withBackgroundColor))]
     pub fn with_background_color(mut self, background_color: String) -> Self {
         self.background_color = background_color;
         self
     }
 
-    #[wasm_bindgen(js_name = withAlign)]
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withAlign))]
     pub fn bind_with_align(self, align: String) -> Self {
         self.with_align(align)
     }
 
-    #[wasm_bindgen(js_name = withAlignVertical)]
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withAlignVertical))]
     pub fn bind_with_align_vertical(self, align_vertical: String) -> Self {
         self.with_align_vertical(align_vertical)
     }
 
-    #[wasm_bindgen(js_name = withDateFormat)]
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withDateFormat))]
     pub fn with_date_format(mut self, date_format: String) -> Self {
         self.date_format = Some(date_format);
         self
     }
 
-    #[wasm_bindgen(js_name = withBorderColor)]
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withBorderColor))]
     pub fn bind_with_border_color(self, border_color: String) -> Self {
         self.with_border_color(border_color)
     }
@@ -396,75 +983,209 @@ impl ExcelCellFormat {
     }
 }
 
-#[wasm_bindgen(getter_with_clone)]
-#[derive(Clone)]
+#[cfg_attr(feature = "wasm", wasm_bindgen(getter_with_clone))]
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct ExcelColumnInfo {
     pub key: String,
     pub name: String,
+    #[serde(default = "default_column_width")]
     pub width: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub note: Option<String>,
+    #[serde(default = "default_data_type")]
     pub data_type: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub allowed_values: Vec<String>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub parent: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub format: Option<ExcelCellFormat>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_value_format",
+        skip_serializing_if = "Vec::is_empty"
+    )]
     pub value_format: Vec<ExcelCellFormat>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub data_group: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub data_group_parent: String,
+    #[serde(default)]
+    pub required: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+    #[serde(default)]
+    pub boolean_dropdown: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub true_label: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub false_label: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub link_text_key: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub formula: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub number_format: Option<ExcelNumberFormat>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub date_options: Option<ExcelDateOptions>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub constraints: Option<ExcelConstraints>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub conditional_formats: Vec<ExcelConditionalFormat>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auto_width: Option<bool>,
+}
+
+fn default_column_width() -> f64 {
+    10.0
+}
+
+fn default_data_type() -> String {
+    "text".into()
+}
+
+/// Accepts `valueFormat` as a single format or a list, like `ExcelDefinition`.
+fn deserialize_value_format<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<ExcelCellFormat>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum ValueFormat {
+        One(ExcelCellFormat),
+        Many(Vec<ExcelCellFormat>),
+    }
+    Ok(match ValueFormat::deserialize(deserializer)? {
+        ValueFormat::One(format) => vec![format],
+        ValueFormat::Many(formats) => formats,
+    })
 }
 
-#[wasm_bindgen]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
 impl ExcelColumnInfo {
-    #[wasm_bindgen(constructor)]
+    #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
     pub fn bind_new(key: String, name: String) -> ExcelColumnInfo {
         ExcelColumnInfo::new(key, name)
     }
 
-    #[wasm_bindgen(js_name = withNote)]
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withNote))]
     pub fn with_note(mut self, note: String) -> Self {
         self.note = Some(note);
         self
     }
 
-    #[wasm_bindgen(js_name = withDataType)]
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withDataType))]
     pub fn bind_with_data_type(self, data_type: String) -> Self {
         self.with_data_type(data_type)
     }
 
-    #[wasm_bindgen(js_name = withAllowedValues)]
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withAllowedValues))]
     pub fn with_allowed_values(mut self, allowed_values: Vec<String>) -> Self {
         self.allowed_values = allowed_values;
         self
     }
 
-    #[wasm_bindgen(js_name = withWidth)]
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withWidth))]
     pub fn with_width(mut self, width: f64) -> Self {
         self.width = width;
         self
     }
 
-    #[wasm_bindgen(js_name = withFormat)]
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withFormat))]
     pub fn with_format(mut self, format: ExcelCellFormat) -> Self {
         self.format = Some(format);
         self
     }
 
-    #[wasm_bindgen(js_name = withParent)]
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withParent))]
     pub fn bind_with_parent(self, parent: String) -> Self {
         self.with_parent(parent)
     }
 
-    #[wasm_bindgen(js_name = withValueFormat)]
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withValueFormat))]
     pub fn with_value_format(mut self, value_format: Vec<ExcelCellFormat>) -> Self {
         self.value_format = value_format;
         self
     }
 
-    #[wasm_bindgen(js_name = withDataGroup)]
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withRequired))]
+    pub fn with_required(mut self, required: bool) -> Self {
+        self.required = required;
+        self
+    }
+
+    /// Other header names accepted for this column on import.
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withAliases))]
+    pub fn with_aliases(mut self, aliases: Vec<String>) -> Self {
+        self.aliases = aliases;
+        self
+    }
+
+    /// Adds a TRUE/FALSE dropdown, or the custom labels, below the header.
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withBooleanDropdown))]
+    pub fn with_boolean_dropdown(mut self, boolean_dropdown: bool) -> Self {
+        self.boolean_dropdown = boolean_dropdown;
+        self
+    }
+
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withBooleanLabels))]
+    pub fn bind_with_boolean_labels(self, true_label: String, false_label: String) -> Self {
+        self.with_boolean_labels(true_label, false_label)
+    }
+
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withLinkTextKey))]
+    pub fn bind_with_link_text_key(self, link_text_key: String) -> Self {
+        self.with_link_text_key(link_text_key)
+    }
+
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withNumberFormat))]
+    pub fn with_number_format(mut self, number_format: ExcelNumberFormat) -> Self {
+        self.number_format = Some(number_format);
+        self
+    }
+
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withDateOptions))]
+    pub fn with_date_options(mut self, date_options: ExcelDateOptions) -> Self {
+        self.date_options = Some(date_options);
+        self
+    }
+
+    /// Numeric range, text length and pattern rules, checked on import.
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withConstraints))]
+    pub fn with_constraints(mut self, constraints: ExcelConstraints) -> Self {
+        self.constraints = Some(constraints);
+        self
+    }
+
+    /// Excel conditional formats written over the exported rows of the column.
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withConditionalFormats))]
+    pub fn with_conditional_formats(
+        mut self,
+        conditional_formats: Vec<ExcelConditionalFormat>,
+    ) -> Self {
+        self.conditional_formats = conditional_formats;
+        self
+    }
+
+    /// Fits this column to its content, or keeps `width` when `false`,
+    /// whatever the sheet `autoWidth` says.
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withAutoWidth))]
+    pub fn with_auto_width(mut self, auto_width: bool) -> Self {
+        self.auto_width = Some(auto_width);
+        self
+    }
+
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withFormula))]
+    pub fn bind_with_formula(self, formula: String) -> Self {
+        self.with_formula(formula)
+    }
+
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withDataGroup))]
     pub fn bind_with_data_group(self, group: String) -> Self {
         self.with_data_group(group)
     }
 
-    #[wasm_bindgen(js_name = withDataGroupParent)]
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withDataGroupParent))]
     pub fn bind_with_data_group_parent(self, group_parent: String) -> Self {
         self.with_data_group_parent(group_parent)
     }
@@ -484,6 +1205,18 @@ impl ExcelColumnInfo {
             value_format: Vec::new(),
             data_group: "".into(),
             data_group_parent: "".into(),
+            required: false,
+            aliases: Vec::new(),
+            boolean_dropdown: false,
+            true_label: None,
+            false_label: None,
+            link_text_key: None,
+            formula: None,
+            number_format: None,
+            date_options: None,
+            constraints: None,
+            conditional_formats: Vec::new(),
+            auto_width: None,
         }
     }
 
@@ -507,6 +1240,30 @@ impl ExcelColumnInfo {
         self
     }
 
+    /// Writes `boolean` values as these labels instead of native booleans.
+    pub fn with_boolean_labels<T: Into<String>>(mut self, true_label: T, false_label: T) -> Self {
+        self.true_label = Some(true_label.into());
+        self.false_label = Some(false_label.into());
+        self
+    }
+
+    /// Reads the display text of `link` cells from this key of the same row.
+    pub fn with_link_text_key<T: Into<String>>(mut self, link_text_key: T) -> Self {
+        self.link_text_key = Some(link_text_key.into());
+        self
+    }
+
+    /// Writes a formula such as `=SUM({hp}:{speed})` instead of the row value.
+    /// Each `{key}` becomes the cell of that column in the written row.
+    pub fn with_formula<T: Into<String>>(mut self, formula: T) -> Self {
+        self.formula = Some(formula.into());
+        self
+    }
+
+    pub fn get_boolean_labels(&self) -> Option<(&str, &str)> {
+        Some((self.true_label.as_deref()?, self.false_label.as_deref()?))
+    }
+
     pub fn is_root_group(&self) -> bool {
         !self.data_group.is_empty() && self.data_group_parent.is_empty()
     }
@@ -518,11 +1275,9 @@ impl ExcelColumnInfo {
     pub fn get_value_format<'a>(&'a self, value: &str) -> Option<&'a ExcelCellFormat> {
         let mut result = None;
         for vf in self.value_format.iter() {
-            if vf.rule == "eq" {
-                if vf.value == value {
-                    result = Some(vf);
-                    break;
-                }
+            if vf.rule == "eq" && vf.value == value {
+                result = Some(vf);
+                break;
             }
             if vf.rule == "default" {
                 result = Some(vf);
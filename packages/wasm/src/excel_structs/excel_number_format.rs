@@ -0,0 +1,235 @@
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+/// How a `number` column is displayed: thousands separators, fixed decimals,
+/// percentages, currency or accounting style, or a raw Excel `pattern`.
+#[cfg_attr(feature = "wasm", wasm_bindgen(getter_with_clone))]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ExcelNumberFormat {
+    pub style: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub decimals: Option<u8>,
+    pub thousands: bool,
+    pub currency_symbol: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+}
+
+impl Default for ExcelNumberFormat {
+    fn default() -> Self {
+        ExcelNumberFormat::new()
+    }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl ExcelNumberFormat {
+    #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+    pub fn new() -> ExcelNumberFormat {
+        ExcelNumberFormat {
+            style: "number".into(),
+            decimals: None,
+            thousands: false,
+            currency_symbol: "$".into(),
+            pattern: None,
+        }
+    }
+
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withStyle))]
+    pub fn bind_with_style(self, style: String) -> Self {
+        self.with_style(style)
+    }
+
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withDecimals))]
+    pub fn with_decimals(mut self, decimals: u8) -> Self {
+        self.decimals = Some(decimals);
+        self
+    }
+
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withThousands))]
+    pub fn with_thousands(mut self, thousands: bool) -> Self {
+        self.thousands = thousands;
+        self
+    }
+
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withCurrencySymbol))]
+    pub fn bind_with_currency_symbol(self, currency_symbol: String) -> Self {
+        self.with_currency_symbol(currency_symbol)
+    }
+
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withPattern))]
+    pub fn bind_with_pattern(self, pattern: String) -> Self {
+        self.with_pattern(pattern)
+    }
+}
+
+impl ExcelNumberFormat {
+    pub fn with_style<T: Into<String>>(mut self, style: T) -> Self {
+        self.style = style.into();
+        self
+    }
+
+    pub fn with_currency_symbol<T: Into<String>>(mut self, currency_symbol: T) -> Self {
+        self.currency_symbol = currency_symbol.into();
+        self
+    }
+
+    /// Uses an Excel number format such as `0.0,,"M"` as is.
+    pub fn with_pattern<T: Into<String>>(mut self, pattern: T) -> Self {
+        self.pattern = Some(pattern.into());
+        self
+    }
+
+    pub fn is_supported_style(style: &str) -> bool {
+        ["number", "percent", "currency", "accounting"]
+            .iter()
+            .any(|candidate| style.eq_ignore_ascii_case(candidate))
+    }
+
+    fn is_style(&self, style: &str) -> bool {
+        self.style.eq_ignore_ascii_case(style)
+    }
+
+    /// The decimals shown: the explicit value or 2 for currency and accounting,
+    /// 0 for percentages and thousands-separated numbers. Plain numbers keep
+    /// every decimal.
+    pub fn get_decimals(&self) -> Option<u8> {
+        if self.decimals.is_some() {
+            return self.decimals;
+        }
+        if self.is_style("currency") || self.is_style("accounting") {
+            Some(2)
+        } else if self.is_style("percent") || self.thousands {
+            Some(0)
+        } else {
+            None
+        }
+    }
+
+    /// The Excel number format written to the cells.
+    pub fn get_pattern(&self) -> String {
+        if let Some(pattern) = self.pattern.as_ref() {
+            return pattern.clone();
+        }
+        let Some(decimals) = self.get_decimals() else {
+            return "General".into();
+        };
+        let mut digits = String::from(if self.thousands || self.is_style("accounting") {
+            "#,##0"
+        } else {
+            "0"
+        });
+        if decimals > 0 {
+            digits.push('.');
+            digits.push_str(&"0".repeat(decimals as usize));
+        }
+        let symbol = format!("\"{}\"", self.currency_symbol.replace('"', ""));
+        if self.is_style("percent") {
+            format!("{}%", digits)
+        } else if self.is_style("currency") {
+            format!("{0}{1};-{0}{1}", symbol, digits)
+        } else if self.is_style("accounting") {
+            let zero = "?".repeat(decimals as usize);
+            format!(
+                "_({0}* {1}_);_({0}* \\({1}\\);_({0}* \"-\"{2}_);_(@_)",
+                symbol, digits, zero
+            )
+        } else {
+            digits
+        }
+    }
+
+    /// Rounds `number` to the shown decimals. Without fixed decimals it keeps
+    /// 15 significant digits, which drops binary noise such as `0.30000000000000004`.
+    pub fn round_number(&self, number: f64) -> f64 {
+        match self.get_decimals() {
+            Some(decimals) if self.pattern.is_none() => {
+                let decimals = if self.is_style("percent") {
+                    decimals + 2
+                } else {
+                    decimals
+                };
+                round_to_decimals(number, decimals)
+            }
+            _ => round_to_significant_digits(number),
+        }
+    }
+
+    /// Renders `number` the way the generated pattern displays it. Raw patterns
+    /// are not interpreted, so those numbers are only rounded.
+    pub fn format_number(&self, number: f64) -> String {
+        let number = self.round_number(number);
+        if self.pattern.is_some() || !number.is_finite() {
+            return number.to_string();
+        }
+        let Some(decimals) = self.get_decimals() else {
+            return number.to_string();
+        };
+        let shown = if self.is_style("percent") {
+            number * 100.0
+        } else {
+            number
+        };
+        let mut digits = format!("{:.*}", decimals as usize, shown.abs());
+        if self.thousands || self.is_style("accounting") {
+            digits = group_thousands(&digits);
+        }
+        let is_negative = shown < 0.0 && digits.chars().any(|c| c.is_ascii_digit() && c != '0');
+        if self.is_style("percent") {
+            digits.push('%');
+        } else if self.is_style("accounting") {
+            if !digits.chars().any(|c| c.is_ascii_digit() && c != '0') {
+                return format!("{} -", self.currency_symbol);
+            }
+            return if is_negative {
+                format!("{} ({})", self.currency_symbol, digits)
+            } else {
+                format!("{} {}", self.currency_symbol, digits)
+            };
+        } else if self.is_style("currency") {
+            digits = format!("{}{}", self.currency_symbol, digits);
+        }
+        if is_negative {
+            format!("-{}", digits)
+        } else {
+            digits
+        }
+    }
+}
+
+fn round_to_decimals(number: f64, decimals: u8) -> f64 {
+    let factor = 10f64.powi(decimals as i32);
+    let rounded = (number * factor).round() / factor;
+    if rounded.is_finite() {
+        rounded
+    } else {
+        number
+    }
+}
+
+pub(crate) fn round_to_significant_digits(number: f64) -> f64 {
+    if !number.is_finite() {
+        return number;
+    }
+    format!("{:.14e}", number).parse().unwrap_or(number)
+}
+
+fn group_thousands(digits: &str) -> String {
+    let (integer, fraction) = match digits.split_once('.') {
+        Some((integer, fraction)) => (integer, Some(fraction)),
+        None => (digits, None),
+    };
+    let mut grouped = String::with_capacity(digits.len() + integer.len() / 3);
+    for (index, digit) in integer.chars().enumerate() {
+        if index > 0 && (integer.len() - index) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    if let Some(fraction) = fraction {
+        grouped.push('.');
+        grouped.push_str(fraction);
+    }
+    grouped
+}
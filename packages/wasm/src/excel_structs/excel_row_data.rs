@@ -1,19 +1,21 @@
 use std::fmt;
 
+#[cfg(feature = "wasm")]
 use wasm_bindgen::prelude::*;
 
 use super::excel_column_data::ExcelColumnData;
 
-#[wasm_bindgen(getter_with_clone)]
+#[cfg_attr(feature = "wasm", wasm_bindgen(getter_with_clone))]
 #[derive(Clone)]
 pub struct ExcelRowData {
     pub columns: Vec<ExcelColumnData>,
+    pub sheet_name: Option<String>,
 }
 
 impl ExcelRowData {
     pub fn get_row_len(&self) -> u32 {
         let mut row_len = 1;
-        for (_, column_data) in self.columns.iter().enumerate() {
+        for column_data in self.columns.iter() {
             let len = column_data.get_children_len();
             if len > row_len {
                 row_len += len;
@@ -23,17 +25,20 @@ impl ExcelRowData {
     }
 }
 
-#[wasm_bindgen]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
 impl ExcelRowData {
-    #[wasm_bindgen(constructor)]
+    #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
     pub fn new(columns: Vec<ExcelColumnData>) -> ExcelRowData {
-        ExcelRowData { columns }
+        ExcelRowData {
+            columns,
+            sheet_name: None,
+        }
     }
 }
 
 impl fmt::Debug for ExcelRowData {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        for (_, column_data) in self.columns.iter().enumerate() {
+        for column_data in self.columns.iter() {
             write!(f, "{:?},", column_data)?
         }
         Ok(())
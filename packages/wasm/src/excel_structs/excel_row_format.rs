@@ -0,0 +1,107 @@
+use crate::excel_structs::excel_info::ExcelCellFormat;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+const ROW_RULES: [&str; 9] = [
+    "eq", "ne", "gt", "gte", "lt", "lte", "contains", "blank", "notBlank",
+];
+
+/// A format applied to a whole exported row, or to some of its columns, when
+/// the value of `columnKey` in that row matches the rule.
+#[cfg_attr(feature = "wasm", wasm_bindgen(getter_with_clone))]
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ExcelRowFormat {
+    pub column_key: String,
+    pub rule: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub value: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<ExcelCellFormat>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub columns: Vec<String>,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl ExcelRowFormat {
+    #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+    pub fn bind_new(column_key: String, rule: String) -> ExcelRowFormat {
+        ExcelRowFormat::new(column_key, rule)
+    }
+
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withValue))]
+    pub fn bind_with_value(self, value: String) -> Self {
+        self.with_value(value)
+    }
+
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withFormat))]
+    pub fn with_format(mut self, format: ExcelCellFormat) -> Self {
+        self.format = Some(format);
+        self
+    }
+
+    /// Limits the format to these column keys. By default every column of
+    /// the row is formatted.
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withColumns))]
+    pub fn with_columns(mut self, columns: Vec<String>) -> Self {
+        self.columns = columns;
+        self
+    }
+}
+
+impl ExcelRowFormat {
+    pub fn new<T: Into<String>>(column_key: T, rule: T) -> Self {
+        ExcelRowFormat {
+            column_key: column_key.into(),
+            rule: rule.into(),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_value<T: Into<String>>(mut self, value: T) -> Self {
+        self.value = value.into();
+        self
+    }
+
+    pub fn is_supported_rule(&self) -> bool {
+        ROW_RULES.contains(&self.rule.as_str())
+    }
+
+    pub fn get_supported_rules() -> String {
+        ROW_RULES.join(", ")
+    }
+
+    /// Whether the rule compares numbers, so `value` must be one.
+    pub fn is_numeric_rule(&self) -> bool {
+        matches!(self.rule.as_str(), "gt" | "gte" | "lt" | "lte")
+    }
+
+    pub fn applies_to(&self, key: &str) -> bool {
+        self.columns.is_empty() || self.columns.iter().any(|column| column == key)
+    }
+
+    /// Checks the rule against the text of `columnKey` in a row. Both sides
+    /// are trimmed, and numeric rules never match values that are not numbers.
+    pub fn matches(&self, row_value: &str) -> bool {
+        let row_value = row_value.trim();
+        let value = self.value.trim();
+        let compare =
+            |check: fn(f64, f64) -> bool| match (row_value.parse::<f64>(), value.parse::<f64>()) {
+                (Ok(left), Ok(right)) => check(left, right),
+                _ => false,
+            };
+        match self.rule.as_str() {
+            "eq" => row_value == value,
+            "ne" => row_value != value,
+            "gt" => compare(|left, right| left > right),
+            "gte" => compare(|left, right| left >= right),
+            "lt" => compare(|left, right| left < right),
+            "lte" => compare(|left, right| left <= right),
+            "contains" => row_value.contains(value),
+            "blank" => row_value.is_empty(),
+            "notBlank" => !row_value.is_empty(),
+            _ => false,
+        }
+    }
+}
@@ -0,0 +1,201 @@
+use crate::excel_structs::excel_info::ExcelCellFormat;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+const SUMMARY_FUNCTIONS: [&str; 5] = ["sum", "avg", "count", "min", "max"];
+
+/// Adds a totals row under the exported rows and, optionally, a subtotal row
+/// after each row with child rows, holding one aggregate per listed column.
+#[cfg_attr(feature = "wasm", wasm_bindgen(getter_with_clone))]
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ExcelSummaryOptions {
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub aggregates: Vec<ExcelAggregate>,
+    pub totals: bool,
+    pub subtotals: bool,
+    pub precomputed: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subtotal_label: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<ExcelCellFormat>,
+}
+
+impl Default for ExcelSummaryOptions {
+    fn default() -> Self {
+        ExcelSummaryOptions {
+            aggregates: Vec::new(),
+            totals: true,
+            subtotals: false,
+            precomputed: false,
+            label: None,
+            subtotal_label: None,
+            format: None,
+        }
+    }
+}
+
+/// The function of one column in the totals and subtotal rows.
+#[cfg_attr(feature = "wasm", wasm_bindgen(getter_with_clone))]
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ExcelAggregate {
+    pub column_key: String,
+    pub function: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<ExcelCellFormat>,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl ExcelSummaryOptions {
+    #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+    pub fn bind_new(aggregates: Vec<ExcelAggregate>) -> ExcelSummaryOptions {
+        ExcelSummaryOptions::new(aggregates)
+    }
+
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withTotals))]
+    pub fn with_totals(mut self, totals: bool) -> Self {
+        self.totals = totals;
+        self
+    }
+
+    /// Adds a subtotal row after each exported row with child rows.
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withSubtotals))]
+    pub fn with_subtotals(mut self, subtotals: bool) -> Self {
+        self.subtotals = subtotals;
+        self
+    }
+
+    /// Writes the computed values instead of `SUBTOTAL` formulas.
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withPrecomputed))]
+    pub fn with_precomputed(mut self, precomputed: bool) -> Self {
+        self.precomputed = precomputed;
+        self
+    }
+
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withLabel))]
+    pub fn bind_with_label(self, label: String) -> Self {
+        self.with_label(label)
+    }
+
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withSubtotalLabel))]
+    pub fn bind_with_subtotal_label(self, subtotal_label: String) -> Self {
+        self.with_subtotal_label(subtotal_label)
+    }
+
+    /// The format of the label and of aggregates without their own format.
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withFormat))]
+    pub fn with_format(mut self, format: ExcelCellFormat) -> Self {
+        self.format = Some(format);
+        self
+    }
+}
+
+impl ExcelSummaryOptions {
+    pub fn new(aggregates: Vec<ExcelAggregate>) -> Self {
+        ExcelSummaryOptions {
+            aggregates,
+            ..Default::default()
+        }
+    }
+
+    /// The text of the first leaf column in the totals row. Defaults to `Total`.
+    pub fn with_label<T: Into<String>>(mut self, label: T) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// The text of the first leaf column in subtotal rows. Defaults to `Subtotal`.
+    pub fn with_subtotal_label<T: Into<String>>(mut self, subtotal_label: T) -> Self {
+        self.subtotal_label = Some(subtotal_label.into());
+        self
+    }
+
+    pub fn get_label(&self) -> &str {
+        self.label.as_deref().unwrap_or("Total")
+    }
+
+    pub fn get_subtotal_label(&self) -> &str {
+        self.subtotal_label.as_deref().unwrap_or("Subtotal")
+    }
+
+    /// Whether `text` is the label of the totals row these options write.
+    pub fn is_totals_label(&self, text: &str) -> bool {
+        self.totals && text.trim() == self.get_label()
+    }
+
+    /// Whether `text` is the label of the subtotal rows these options write.
+    pub fn is_subtotal_label(&self, text: &str) -> bool {
+        self.subtotals && text.trim() == self.get_subtotal_label()
+    }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl ExcelAggregate {
+    #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+    pub fn bind_new(column_key: String, function: String) -> ExcelAggregate {
+        ExcelAggregate::new(column_key, function)
+    }
+
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withFormat))]
+    pub fn with_format(mut self, format: ExcelCellFormat) -> Self {
+        self.format = Some(format);
+        self
+    }
+}
+
+impl ExcelAggregate {
+    pub fn new<T: Into<String>>(column_key: T, function: T) -> Self {
+        ExcelAggregate {
+            column_key: column_key.into(),
+            function: function.into(),
+            format: None,
+        }
+    }
+
+    pub fn is_supported_function(&self) -> bool {
+        SUMMARY_FUNCTIONS.contains(&self.function.as_str())
+    }
+
+    pub fn get_supported_functions() -> String {
+        SUMMARY_FUNCTIONS.join(", ")
+    }
+
+    /// The `SUBTOTAL` function number, which skips nested subtotals.
+    pub fn get_subtotal_number(&self) -> u8 {
+        match self.function.as_str() {
+            "avg" => 1,
+            "count" => 3,
+            "max" => 4,
+            "min" => 5,
+            _ => 9,
+        }
+    }
+
+    /// The aggregate of the non-blank texts of a column. `count` counts them,
+    /// the others use the numbers among them and return `None` without any.
+    pub fn compute<'a>(&self, values: impl IntoIterator<Item = &'a str>) -> Option<f64> {
+        let values = values
+            .into_iter()
+            .map(str::trim)
+            .filter(|value| !value.is_empty());
+        if self.function == "count" {
+            return Some(values.count() as f64);
+        }
+        let numbers = values
+            .filter_map(|value| value.parse::<f64>().ok())
+            .collect::<Vec<f64>>();
+        if numbers.is_empty() {
+            return None;
+        }
+        Some(match self.function.as_str() {
+            "avg" => numbers.iter().sum::<f64>() / numbers.len() as f64,
+            "min" => numbers.iter().copied().fold(f64::INFINITY, f64::min),
+            "max" => numbers.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+            _ => numbers.iter().sum(),
+        })
+    }
+}
@@ -0,0 +1,236 @@
+use rust_xlsxwriter::TableStyle;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+const TABLE_FUNCTIONS: [&str; 8] = [
+    "sum",
+    "average",
+    "count",
+    "countNumbers",
+    "max",
+    "min",
+    "stdDev",
+    "var",
+];
+
+/// Turns the exported header and data rows into an Excel Table.
+#[cfg_attr(feature = "wasm", wasm_bindgen(getter_with_clone))]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ExcelTableOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub style: Option<String>,
+    pub banded_rows: bool,
+    pub banded_columns: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_label: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub totals: Vec<ExcelTableTotal>,
+}
+
+impl Default for ExcelTableOptions {
+    fn default() -> Self {
+        ExcelTableOptions {
+            name: None,
+            style: None,
+            banded_rows: true,
+            banded_columns: false,
+            total_label: None,
+            totals: Vec::new(),
+        }
+    }
+}
+
+/// The function of one column in the totals row of a table.
+#[cfg_attr(feature = "wasm", wasm_bindgen(getter_with_clone))]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ExcelTableTotal {
+    pub column_key: String,
+    pub function: String,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl ExcelTableOptions {
+    #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+    pub fn new() -> ExcelTableOptions {
+        ExcelTableOptions::default()
+    }
+
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withName))]
+    pub fn bind_with_name(self, name: String) -> Self {
+        self.with_name(name)
+    }
+
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withStyle))]
+    pub fn bind_with_style(self, style: String) -> Self {
+        self.with_style(style)
+    }
+
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withBandedRows))]
+    pub fn with_banded_rows(mut self, banded_rows: bool) -> Self {
+        self.banded_rows = banded_rows;
+        self
+    }
+
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withBandedColumns))]
+    pub fn with_banded_columns(mut self, banded_columns: bool) -> Self {
+        self.banded_columns = banded_columns;
+        self
+    }
+
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withTotalLabel))]
+    pub fn bind_with_total_label(self, total_label: String) -> Self {
+        self.with_total_label(total_label)
+    }
+
+    /// Adds a totals row with these column functions.
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withTotals))]
+    pub fn with_totals(mut self, totals: Vec<ExcelTableTotal>) -> Self {
+        self.totals = totals;
+        self
+    }
+}
+
+impl ExcelTableOptions {
+    /// The table name used in structured references, such as `Orders`.
+    pub fn with_name<T: Into<String>>(mut self, name: T) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// A built-in style such as `TableStyleMedium9`, `Light1` or `None`.
+    pub fn with_style<T: Into<String>>(mut self, style: T) -> Self {
+        self.style = Some(style.into());
+        self
+    }
+
+    /// The text of the first column in the totals row. Defaults to `Total`.
+    pub fn with_total_label<T: Into<String>>(mut self, total_label: T) -> Self {
+        self.total_label = Some(total_label.into());
+        self
+    }
+
+    pub fn has_totals_row(&self) -> bool {
+        self.total_label.is_some() || !self.totals.is_empty()
+    }
+
+    pub fn get_total_label(&self) -> &str {
+        self.total_label.as_deref().unwrap_or("Total")
+    }
+
+    /// Whether `name` can name a table: a letter or underscore followed by
+    /// letters, digits, underscores or periods, and not a cell reference.
+    pub fn is_valid_name(name: &str) -> bool {
+        let mut chars = name.chars();
+        let starts_well = chars
+            .next()
+            .is_some_and(|first| first.is_alphabetic() || first == '_');
+        let is_cell_ref = regex::Regex::new(r"(?i)^([a-z]{1,3}\d+|r\d*c\d*|r|c)$")
+            .is_ok_and(|pattern| pattern.is_match(name));
+        starts_well
+            && chars.all(|c| c.is_alphanumeric() || c == '_' || c == '.')
+            && name.chars().count() <= 255
+            && !is_cell_ref
+    }
+
+    /// The built-in style named by `style`, ignoring case and the optional
+    /// `TableStyle` prefix, or `None` when it is unset or not built in.
+    pub fn get_style(&self) -> Option<TableStyle> {
+        let style = self.style.as_deref()?.trim().to_ascii_lowercase();
+        let name = style.strip_prefix("tablestyle").unwrap_or(&style);
+        let style = match name {
+            "none" => TableStyle::None,
+            "light1" => TableStyle::Light1,
+            "light2" => TableStyle::Light2,
+            "light3" => TableStyle::Light3,
+            "light4" => TableStyle::Light4,
+            "light5" => TableStyle::Light5,
+            "light6" => TableStyle::Light6,
+            "light7" => TableStyle::Light7,
+            "light8" => TableStyle::Light8,
+            "light9" => TableStyle::Light9,
+            "light10" => TableStyle::Light10,
+            "light11" => TableStyle::Light11,
+            "light12" => TableStyle::Light12,
+            "light13" => TableStyle::Light13,
+            "light14" => TableStyle::Light14,
+            "light15" => TableStyle::Light15,
+            "light16" => TableStyle::Light16,
+            "light17" => TableStyle::Light17,
+            "light18" => TableStyle::Light18,
+            "light19" => TableStyle::Light19,
+            "light20" => TableStyle::Light20,
+            "light21" => TableStyle::Light21,
+            "medium1" => TableStyle::Medium1,
+            "medium2" => TableStyle::Medium2,
+            "medium3" => TableStyle::Medium3,
+            "medium4" => TableStyle::Medium4,
+            "medium5" => TableStyle::Medium5,
+            "medium6" => TableStyle::Medium6,
+            "medium7" => TableStyle::Medium7,
+            "medium8" => TableStyle::Medium8,
+            "medium9" => TableStyle::Medium9,
+            "medium10" => TableStyle::Medium10,
+            "medium11" => TableStyle::Medium11,
+            "medium12" => TableStyle::Medium12,
+            "medium13" => TableStyle::Medium13,
+            "medium14" => TableStyle::Medium14,
+            "medium15" => TableStyle::Medium15,
+            "medium16" => TableStyle::Medium16,
+            "medium17" => TableStyle::Medium17,
+            "medium18" => TableStyle::Medium18,
+            "medium19" => TableStyle::Medium19,
+            "medium20" => TableStyle::Medium20,
+            "medium21" => TableStyle::Medium21,
+            "medium22" => TableStyle::Medium22,
+            "medium23" => TableStyle::Medium23,
+            "medium24" => TableStyle::Medium24,
+            "medium25" => TableStyle::Medium25,
+            "medium26" => TableStyle::Medium26,
+            "medium27" => TableStyle::Medium27,
+            "medium28" => TableStyle::Medium28,
+            "dark1" => TableStyle::Dark1,
+            "dark2" => TableStyle::Dark2,
+            "dark3" => TableStyle::Dark3,
+            "dark4" => TableStyle::Dark4,
+            "dark5" => TableStyle::Dark5,
+            "dark6" => TableStyle::Dark6,
+            "dark7" => TableStyle::Dark7,
+            "dark8" => TableStyle::Dark8,
+            "dark9" => TableStyle::Dark9,
+            "dark10" => TableStyle::Dark10,
+            "dark11" => TableStyle::Dark11,
+            _ => return None,
+        };
+        Some(style)
+    }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl ExcelTableTotal {
+    #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+    pub fn bind_new(column_key: String, function: String) -> ExcelTableTotal {
+        ExcelTableTotal::new(column_key, function)
+    }
+}
+
+impl ExcelTableTotal {
+    pub fn new<T: Into<String>>(column_key: T, function: T) -> Self {
+        ExcelTableTotal {
+            column_key: column_key.into(),
+            function: function.into(),
+        }
+    }
+
+    pub fn is_supported_function(&self) -> bool {
+        TABLE_FUNCTIONS.contains(&self.function.as_str())
+    }
+
+    pub fn get_supported_functions() -> String {
+        TABLE_FUNCTIONS.join(", ")
+    }
+}
@@ -0,0 +1,70 @@
+use super::excel_data::ExcelData;
+use std::fmt;
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+#[cfg_attr(feature = "wasm", wasm_bindgen(getter_with_clone))]
+#[derive(Clone)]
+pub struct ExcelValidationIssue {
+    pub sheet_name: String,
+    pub cell: String,
+    pub row: u32,
+    pub column_key: String,
+    pub code: String,
+    pub value: String,
+}
+
+impl ExcelValidationIssue {
+    pub fn new<T: Into<String>>(
+        sheet_name: T,
+        cell: T,
+        row: u32,
+        column_key: T,
+        code: T,
+        value: T,
+    ) -> Self {
+        ExcelValidationIssue {
+            sheet_name: sheet_name.into(),
+            cell: cell.into(),
+            row,
+            column_key: column_key.into(),
+            code: code.into(),
+            value: value.into(),
+        }
+    }
+}
+
+impl fmt::Debug for ExcelValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}!{} [{}] {}: {}",
+            self.sheet_name, self.cell, self.column_key, self.code, self.value
+        )
+    }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen(getter_with_clone))]
+#[derive(Clone)]
+pub struct ExcelValidationReport {
+    pub data: ExcelData,
+    pub issues: Vec<ExcelValidationIssue>,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl ExcelValidationReport {
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = isValid))]
+    pub fn is_valid(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+impl fmt::Debug for ExcelValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "issues")?;
+        for issue in self.issues.iter() {
+            writeln!(f, "  {:?}", issue)?;
+        }
+        write!(f, "{:?}", self.data)
+    }
+}
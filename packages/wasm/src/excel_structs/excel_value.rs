@@ -0,0 +1,121 @@
+use chrono::{Datelike, NaiveDateTime, Timelike};
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ExcelValue {
+    Empty,
+    Text(String),
+    Number(f64),
+    Bool(bool),
+    DateTime(NaiveDateTime),
+    Link { url: String, text: String },
+    Error(String),
+}
+
+impl ExcelValue {
+    pub fn from_text<T: Into<String>>(value: T) -> Self {
+        let value = value.into();
+        if value.is_empty() {
+            ExcelValue::Empty
+        } else {
+            ExcelValue::Text(value)
+        }
+    }
+
+    /// Reads the `text|url` convention used by `link` columns; a bare value is
+    /// both the target and the display text.
+    pub fn parse_link(value: &str) -> Self {
+        let value = value.trim();
+        if value.is_empty() {
+            return ExcelValue::Empty;
+        }
+        let (text, url) = match value.rsplit_once('|') {
+            Some((text, url)) if !url.trim().is_empty() => (text.trim(), url.trim()),
+            _ => (value, value),
+        };
+        ExcelValue::Link {
+            url: url.to_string(),
+            text: if text.is_empty() { url } else { text }.to_string(),
+        }
+    }
+
+    pub fn value_type(&self) -> &'static str {
+        match self {
+            ExcelValue::Empty => "empty",
+            ExcelValue::Text(_) => "text",
+            ExcelValue::Number(_) => "number",
+            ExcelValue::Bool(_) => "bool",
+            ExcelValue::DateTime(_) => "date",
+            ExcelValue::Link { .. } => "link",
+            ExcelValue::Error(_) => "error",
+        }
+    }
+
+    pub fn is_native(&self) -> bool {
+        matches!(
+            self,
+            ExcelValue::Number(_)
+                | ExcelValue::Bool(_)
+                | ExcelValue::DateTime(_)
+                | ExcelValue::Link { .. }
+        )
+    }
+
+    pub fn to_display_string(&self) -> String {
+        match self {
+            ExcelValue::Empty => "".to_string(),
+            ExcelValue::Text(value) | ExcelValue::Error(value) => value.clone(),
+            ExcelValue::Number(value) => value.to_string(),
+            ExcelValue::Bool(value) => value.to_string(),
+            ExcelValue::DateTime(value) => value.format("%Y-%m-%d %H:%M:%S").to_string(),
+            ExcelValue::Link { url, text } if text.is_empty() || text == url => url.clone(),
+            ExcelValue::Link { url, text } => format!("{}|{}", text, url),
+        }
+    }
+
+    pub fn to_excel_date_time(
+        value: &NaiveDateTime,
+    ) -> Result<rust_xlsxwriter::ExcelDateTime, rust_xlsxwriter::XlsxError> {
+        rust_xlsxwriter::ExcelDateTime::from_ymd(
+            value.year() as u16,
+            value.month() as u8,
+            value.day() as u8,
+        )?
+        .and_hms_milli(
+            value.hour() as u16,
+            value.minute() as u8,
+            value.second() as u8,
+            (value.nanosecond() / 1_000_000) as u16,
+        )
+    }
+
+    #[cfg(feature = "wasm")]
+    pub fn to_js_date(value: &NaiveDateTime) -> js_sys::Date {
+        let millis = value.and_utc().timestamp_millis() as f64;
+        js_sys::Date::new(&JsValue::from_f64(millis))
+    }
+
+    #[cfg(feature = "wasm")]
+    pub fn from_js_date(date: &js_sys::Date) -> Option<NaiveDateTime> {
+        chrono::DateTime::from_timestamp_millis(date.get_time() as i64)
+            .map(|date_time| date_time.naive_utc())
+    }
+
+    #[cfg(feature = "wasm")]
+    pub fn to_js_value(&self) -> JsValue {
+        match self {
+            ExcelValue::Empty => JsValue::NULL,
+            ExcelValue::Text(value) | ExcelValue::Error(value) => JsValue::from_str(value),
+            ExcelValue::Number(value) => JsValue::from_f64(*value),
+            ExcelValue::Bool(value) => JsValue::from_bool(*value),
+            ExcelValue::DateTime(value) => ExcelValue::to_js_date(value).into(),
+            ExcelValue::Link { url, text } => {
+                let link = js_sys::Object::new();
+                let _ = js_sys::Reflect::set(&link, &"url".into(), &JsValue::from_str(url));
+                let _ = js_sys::Reflect::set(&link, &"text".into(), &JsValue::from_str(text));
+                link.into()
+            }
+        }
+    }
+}
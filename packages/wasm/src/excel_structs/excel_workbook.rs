@@ -0,0 +1,58 @@
+use super::excel_data::ExcelData;
+use super::excel_info::ExcelInfo;
+use crate::callbacks::ProgressReporter;
+#[cfg(feature = "wasm")]
+use crate::wasm::JsProgressReporter;
+#[cfg(feature = "wasm")]
+use js_sys::Function;
+use std::sync::Arc;
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub struct ExcelWorkbook {
+    #[cfg_attr(feature = "wasm", wasm_bindgen(skip))]
+    pub sheets: Vec<(ExcelInfo, ExcelData)>,
+    #[cfg_attr(feature = "wasm", wasm_bindgen(skip))]
+    pub progress_callback: Option<Arc<dyn ProgressReporter>>,
+}
+
+impl ExcelWorkbook {
+    pub fn with_progress_callback<R: ProgressReporter + 'static>(mut self, reporter: R) -> Self {
+        self.progress_callback = Some(Arc::new(reporter));
+        self
+    }
+
+    pub fn get_total_rows(&self) -> usize {
+        self.sheets.iter().map(|(_, data)| data.rows.len()).sum()
+    }
+}
+
+impl Default for ExcelWorkbook {
+    fn default() -> Self {
+        ExcelWorkbook::new()
+    }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl ExcelWorkbook {
+    #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+    pub fn new() -> ExcelWorkbook {
+        ExcelWorkbook {
+            sheets: Vec::new(),
+            progress_callback: None,
+        }
+    }
+
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = withSheet))]
+    pub fn with_sheet(mut self, info: ExcelInfo, data: ExcelData) -> Self {
+        self.sheets.push((info, data));
+        self
+    }
+
+    #[cfg(feature = "wasm")]
+    #[wasm_bindgen(js_name = withProgressCallback)]
+    pub fn bind_with_progress_callback(self, callback: Function) -> Self {
+        self.with_progress_callback(JsProgressReporter::new(callback))
+    }
+}
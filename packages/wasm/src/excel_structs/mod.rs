@@ -1,5 +1,19 @@
+pub mod csv_options;
 pub mod dynamic_excel_data;
+pub mod excel_auto_width;
+pub mod excel_column_binding;
 pub mod excel_column_data;
+pub mod excel_conditional_format;
+pub mod excel_constraints;
 pub mod excel_data;
+pub mod excel_date_options;
+pub mod excel_import_summary;
 pub mod excel_info;
+pub mod excel_number_format;
 pub mod excel_row_data;
+pub mod excel_row_format;
+pub mod excel_summary_options;
+pub mod excel_table_options;
+pub mod excel_validation_report;
+pub mod excel_value;
+pub mod excel_workbook;
@@ -1,39 +1,96 @@
-use calamine::{open_workbook_from_rs, Data, Reader, Xlsx};
-use excel_structs::excel_info::ExcelCellFormat;
-use js_sys::{Object, Reflect};
+use calamine::{Data, Reader};
+use excel_structs::excel_auto_width::get_display_width;
+use excel_structs::excel_info::{split_formula_template, ExcelCellFormat, FormulaToken};
+use excel_structs::excel_number_format::round_to_significant_digits;
 use rust_xlsxwriter::*;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt;
 use std::future::Future;
-use std::io::Cursor;
 use std::pin::Pin;
-use std::sync::LazyLock;
-use wasm_bindgen::prelude::*;
-use wasm_bindgen_futures::future_to_promise;
-use wasm_bindgen_futures::JsFuture;
+use std::sync::{Arc, LazyLock};
+use workbook_reader::{
+    get_hyperlinks, get_merged_row_ends, get_table_totals_rows, open_import_workbook,
+    read_sheet_cells, ImportWorkbook, SheetCell,
+};
 
+mod callbacks;
+#[doc(hidden)]
+#[cfg(feature = "cli")]
+pub mod cli;
+mod csv_data;
 mod excel_structs;
+mod schema_inference;
+mod stream_writer;
 #[cfg(test)]
 mod tests;
+#[cfg(feature = "wasm")]
+mod wasm;
+mod workbook_reader;
+
+pub use callbacks::{ImageFetchFuture, ImageFetcher, ProgressReporter};
+pub use csv_data::{export_csv_buffer, import_csv_buffer, import_dynamic_csv_buffer};
 
+pub use excel_structs::csv_options::CsvOptions;
 pub use excel_structs::dynamic_excel_data::DynamicExcelData;
+pub use excel_structs::excel_auto_width::ExcelAutoWidth;
+pub use excel_structs::excel_column_binding::ExcelColumnBinding;
 pub use excel_structs::excel_column_data::ExcelColumnData;
+pub use excel_structs::excel_conditional_format::ExcelConditionalFormat;
+pub use excel_structs::excel_constraints::ExcelConstraints;
 pub use excel_structs::excel_data::ExcelData;
+pub use excel_structs::excel_date_options::ExcelDateOptions;
+pub use excel_structs::excel_import_summary::ExcelImportSummary;
 pub use excel_structs::excel_info::ExcelColumnInfo;
 pub use excel_structs::excel_info::ExcelInfo;
+pub use excel_structs::excel_number_format::ExcelNumberFormat;
 pub use excel_structs::excel_row_data::ExcelRowData;
+pub use excel_structs::excel_row_format::ExcelRowFormat;
+pub use excel_structs::excel_summary_options::ExcelAggregate;
+pub use excel_structs::excel_summary_options::ExcelSummaryOptions;
+pub use excel_structs::excel_table_options::ExcelTableOptions;
+pub use excel_structs::excel_table_options::ExcelTableTotal;
+pub use excel_structs::excel_validation_report::ExcelValidationIssue;
+pub use excel_structs::excel_validation_report::ExcelValidationReport;
+pub use excel_structs::excel_value::ExcelValue;
+pub use excel_structs::excel_workbook::ExcelWorkbook;
+pub use schema_inference::infer_excel_info_buffer;
+pub use stream_writer::{export_data_streaming_buffer, ExcelStreamWriter};
 
 const SECONDS_IN_A_DAY: f64 = 86400.0;
+const DEFAULT_IMPORT_BATCH_SIZE: u32 = 1000;
 const EXCEL_BASE_DATE: i64 = 25569; // Number of days from 1899-12-30 to 1970-01-01
-
+const EXCEL_LAST_ROW: u32 = 1_048_575;
+/// Link targets both writers accept; `internal:` points at a cell in the workbook.
+const LINK_SCHEMES: [&str; 6] = [
+    "http://",
+    "https://",
+    "ftp://",
+    "ftps://",
+    "mailto:",
+    "internal:",
+];
+/// Report codes that make strict imports fail.
+const CONSTRAINT_ISSUE_CODES: [&str; 6] = [
+    "CELL_REQUIRED",
+    "CELL_BELOW_MIN",
+    "CELL_ABOVE_MAX",
+    "CELL_TOO_SHORT",
+    "CELL_TOO_LONG",
+    "CELL_PATTERN_MISMATCH",
+];
+const TRUE_VALUES: [&str; 7] = ["true", "yes", "y", "t", "1", "on", "是"];
+const FALSE_VALUES: [&str; 7] = ["false", "no", "n", "f", "0", "off", "否"];
+
+/// Error returned by every fallible operation, carrying a stable `code` and
+/// named `params` next to the human readable message.
 #[derive(Debug)]
-struct StructuredWasmError {
+pub struct ImportExportError {
     message: String,
     code: &'static str,
     params: Vec<(String, String)>,
 }
 
-impl StructuredWasmError {
+impl ImportExportError {
     fn new(message: String, code: &'static str, params: &[(&str, String)]) -> Self {
         Self {
             message,
@@ -44,53 +101,34 @@ impl StructuredWasmError {
                 .collect(),
         }
     }
+
+    pub fn code(&self) -> &'static str {
+        self.code
+    }
+
+    pub fn params(&self) -> &[(String, String)] {
+        &self.params
+    }
 }
 
-impl fmt::Display for StructuredWasmError {
+impl fmt::Display for ImportExportError {
     fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
         formatter.write_str(&self.message)
     }
 }
 
-impl std::error::Error for StructuredWasmError {}
+impl std::error::Error for ImportExportError {}
 
 fn create_structured_error(
     message: impl AsRef<str>,
     code: &'static str,
     params: &[(&str, String)],
-) -> StructuredWasmError {
-    StructuredWasmError::new(message.as_ref().to_string(), code, params)
-}
-
-fn structured_error_to_js(error: &StructuredWasmError) -> JsValue {
-    let js_error = js_sys::Error::new(&error.message);
-    let js_value: JsValue = js_error.into();
-    let params = Object::new();
-
-    for (key, value) in &error.params {
-        let _ = Reflect::set(&params, &JsValue::from_str(key), &JsValue::from_str(value));
-    }
-
-    let _ = Reflect::set(
-        &js_value,
-        &JsValue::from_str("name"),
-        &JsValue::from_str("ImportExportWasmError"),
-    );
-    let _ = Reflect::set(
-        &js_value,
-        &JsValue::from_str("code"),
-        &JsValue::from_str(error.code),
-    );
-    let _ = Reflect::set(&js_value, &JsValue::from_str("params"), &params);
-    js_value
+) -> ImportExportError {
+    ImportExportError::new(message.as_ref().to_string(), code, params)
 }
 
-fn error_to_js_value(error: &(dyn std::error::Error + 'static)) -> JsValue {
-    if let Some(structured_error) = error.downcast_ref::<StructuredWasmError>() {
-        return structured_error_to_js(structured_error);
-    }
-    JsError::new(&error.to_string()).into()
-}
+type WriteRowFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<u32, Box<dyn std::error::Error>>> + Send + 'a>>;
 
 static DEFAULT_FORMAT: LazyLock<Format> = LazyLock::new(|| {
     Format::new()
@@ -105,6 +143,13 @@ static DEFAULT_DATE_FORMAT: LazyLock<Format> = LazyLock::new(|| {
         .set_text_wrap()
 });
 
+static DEFAULT_LINK_FORMAT: LazyLock<Format> = LazyLock::new(|| {
+    Format::new()
+        .set_align(FormatAlign::VerticalCenter)
+        .set_text_wrap()
+        .set_hyperlink()
+});
+
 static DEFAULT_HEADER_FORMAT: LazyLock<Format> = LazyLock::new(|| {
     Format::new()
         .set_align(FormatAlign::VerticalCenter)
@@ -113,39 +158,11 @@ static DEFAULT_HEADER_FORMAT: LazyLock<Format> = LazyLock::new(|| {
         .set_text_wrap()
 });
 
-#[wasm_bindgen(js_name= createTemplate)]
-pub fn create_template(info: ExcelInfo) -> Result<Vec<u8>, JsValue> {
-    create_template_buffer(&info).map_err(|e| error_to_js_value(e.as_ref()))
-}
-
-#[wasm_bindgen(js_name = importData)]
-pub fn import_data(info: ExcelInfo, excel_bytes: &[u8]) -> Result<ExcelData, JsValue> {
-    import_data_buffer(info, excel_bytes).map_err(|e| error_to_js_value(e.as_ref()))
-}
-
-#[wasm_bindgen(js_name = importDynamicData)]
-pub fn import_dynamic_data(
-    sheet_name: Option<String>,
-    header_row: Option<u32>,
-    excel_bytes: &[u8],
-) -> Result<DynamicExcelData, JsValue> {
-    import_dynamic_data_buffer(sheet_name, header_row, excel_bytes)
-        .map_err(|e| error_to_js_value(e.as_ref()))
-}
-
-#[wasm_bindgen(js_name = exportData)]
-pub fn export_data(info: ExcelInfo, data: ExcelData) -> js_sys::Promise {
-    let future = async move {
-        match export_data_buffer(&info, &data).await {
-            Ok(buffer) => {
-                let uint8array = js_sys::Uint8Array::from(&buffer[..]);
-                Ok(uint8array.into())
-            }
-            Err(e) => Err(error_to_js_value(e.as_ref())),
-        }
-    };
-    future_to_promise(future)
-}
+static DEFAULT_SUMMARY_FORMAT: LazyLock<Format> = LazyLock::new(|| {
+    Format::new()
+        .set_align(FormatAlign::VerticalCenter)
+        .set_bold()
+});
 
 #[cfg(feature = "benchmarks")]
 #[doc(hidden)]
@@ -170,10 +187,19 @@ fn create_template_workbook(
 ) -> Result<(Workbook, Vec<ExcelColumnPosition>), Box<dyn std::error::Error>> {
     let mut workbook = Workbook::new();
     let worksheet = workbook.add_worksheet();
+    let column_positions = write_template_sheet(worksheet, info)?;
+    set_workbook_properties(&mut workbook, info)?;
+    Ok((workbook, column_positions))
+}
+
+fn write_template_sheet(
+    worksheet: &mut Worksheet,
+    info: &ExcelInfo,
+) -> Result<Vec<ExcelColumnPosition>, Box<dyn std::error::Error>> {
     if let Some(default_row_height) = info.default_row_height {
         worksheet.set_default_row_height(default_row_height);
     }
-    let column_positions = get_column_positions(&info);
+    let column_positions = get_column_positions(info);
     let max_column_x = column_positions.iter().map(|p| p.x2).max().unwrap_or(0);
     if let Some(title) = info.title.as_ref() {
         if let Some(title_format) = &info.title_format {
@@ -218,7 +244,7 @@ fn create_template_workbook(
             worksheet.insert_note(position.y1, position.x1, &note)?;
         }
         if position.is_leaf {
-            worksheet.set_column_width(position.x1, column.width)?;
+            worksheet.set_column_width(position.x1, get_header_width(info, column))?;
         }
         if let Some(header_row_height) = info.header_row_height {
             worksheet.set_row_height(position.y1, header_row_height)?;
@@ -226,227 +252,1118 @@ fn create_template_workbook(
     }
 
     if info.is_header_freeze {
-        let freeze_row = column_positions
-            .iter()
-            .map(|position| position.y2 + 1)
-            .max()
-            .unwrap_or(info.dy + u32::from(info.title.is_some()));
-        worksheet.set_freeze_panes(freeze_row, 0)?;
+        worksheet.set_freeze_panes(get_first_data_row(info, &column_positions), 0)?;
     }
 
     worksheet.set_name(info.sheet_name.as_str())?;
-    let create_time =
-        ExcelDateTime::parse_from_str(&info.create_time).map_err(|e| XlsxError::from(e))?;
+    Ok(column_positions)
+}
+
+fn get_first_data_row(info: &ExcelInfo, column_positions: &[ExcelColumnPosition]) -> u32 {
+    column_positions
+        .iter()
+        .map(|position| position.y2 + 1)
+        .max()
+        .unwrap_or(info.dy + u32::from(info.title.is_some()))
+}
+
+/// Adds the validation of every leaf column over `row_spans`, a list of
+/// `(first_row, last_row)` pairs, so exports can leave out summary rows.
+fn add_data_validations(
+    worksheet: &mut Worksheet,
+    column_positions: &[ExcelColumnPosition],
+    info: &ExcelInfo,
+    row_spans: &[(u32, u32)],
+) -> Result<(), Box<dyn std::error::Error>> {
+    let (Some((first_row, _)), Some((_, last_row))) = (row_spans.first(), row_spans.last()) else {
+        return Ok(());
+    };
+    for position in column_positions.iter().filter(|position| position.is_leaf) {
+        let column = find_column(info, &position.key)?;
+        let Some(data_validation) = get_data_validation(column)? else {
+            continue;
+        };
+        let x = position.x1;
+        let cells = row_spans
+            .iter()
+            .map(|(y1, y2)| {
+                format!(
+                    "{}:{}",
+                    get_excel_cell_ref(x, *y1),
+                    get_excel_cell_ref(x, *y2)
+                )
+            })
+            .collect::<Vec<String>>()
+            .join(" ");
+        worksheet.add_data_validation(
+            *first_row,
+            x,
+            *last_row,
+            x,
+            &data_validation.set_multi_range(cells),
+        )?;
+    }
+    Ok(())
+}
 
-    let properties = DocProperties::new()
+fn set_workbook_properties(
+    workbook: &mut Workbook,
+    info: &ExcelInfo,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut properties = DocProperties::new()
         .set_title(info.name.clone())
-        .set_author(info.author.clone().as_str())
-        .set_creation_datetime(&create_time);
+        .set_author(info.author.clone().as_str());
+    // A blank create time keeps the writer's default of the current time.
+    if !info.create_time.trim().is_empty() {
+        let create_time = ExcelDateTime::parse_from_str(&info.create_time)?;
+        properties = properties.set_creation_datetime(&create_time);
+    }
 
     workbook.set_properties(&properties);
-    Ok((workbook, column_positions))
+    Ok(())
 }
 
 fn get_rows_data<'a>(
-    columns: &'a Vec<ExcelColumnPosition>,
+    info: &ExcelInfo,
+    columns: &'a [ExcelColumnPosition],
     range: &'a calamine::Range<calamine::Data>,
+    sheet_name: &str,
 ) -> Vec<ExcelRowData> {
-    let header_max_y = columns.iter().map(|c| c.y2).max().unwrap_or(0) as usize + 1;
+    iter_rows_data(info, columns, range, sheet_name).collect()
+}
+
+fn iter_rows_data<'a>(
+    info: &ExcelInfo,
+    columns: &'a [ExcelColumnPosition],
+    range: &'a calamine::Range<calamine::Data>,
+    sheet_name: &'a str,
+) -> impl ExactSizeIterator<Item = ExcelRowData> + 'a {
     let leaf_columns = columns
         .iter()
         .filter(|c| c.is_leaf)
-        .map(|c| (c.key.clone(), c.x1 as usize, c.data_type.clone()))
-        .collect::<Vec<(String, usize, String)>>();
-    let Some((range_start_y, _)) = range.start() else {
-        return Vec::new();
-    };
-    let Some((range_end_y, _)) = range.end() else {
-        return Vec::new();
+        .map(|c| {
+            let column = info.columns.iter().find(|column| column.key == c.key);
+            let precision = get_date_precision(column);
+            (c.key.clone(), c.x1 as usize, c.data_type.clone(), precision)
+        })
+        .collect::<Vec<(String, usize, String, DatePrecision)>>();
+    let row_indexes = match get_data_row_span(columns, range) {
+        Some((first_data_row, range_end_y)) => first_data_row..range_end_y + 1,
+        None => 0..0,
     };
-    let first_data_row = std::cmp::max(header_max_y as u32, range_start_y);
-    if first_data_row > range_end_y {
-        return Vec::new();
-    }
 
-    (first_data_row..=range_end_y)
-        .map(|row_index| ExcelRowData {
-            columns: leaf_columns
-                .iter()
-                .map(|(key, x1, data_type)| {
-                    let value = range
-                        .get_value((row_index, *x1 as u32))
-                        .map(|cell| format_value(cell, data_type))
-                        .unwrap_or_default();
-                    ExcelColumnData {
-                        key: key.clone(),
-                        value,
-                        children: Vec::new(),
-                    }
-                })
-                .collect(),
-        })
-        .collect()
+    row_indexes.map(move |row_index| ExcelRowData {
+        columns: leaf_columns
+            .iter()
+            .map(|(key, x1, data_type, precision)| {
+                let cell = range.get_value((row_index, *x1 as u32));
+                create_column_data(key, cell, data_type, *precision)
+            })
+            .collect(),
+        sheet_name: Some(sheet_name.to_string()),
+    })
 }
 
-fn excel_to_date_string(excel_date: f64) -> String {
-    let days_since_epoch = excel_date - EXCEL_BASE_DATE as f64;
-    let seconds_since_epoch = days_since_epoch * SECONDS_IN_A_DAY;
-    let date_time = chrono::DateTime::from_timestamp(seconds_since_epoch as i64, 0);
-    match date_time {
-        Some(dt) => dt.format("%Y-%m-%d %H:%M:%S").to_string(),
-        None => "".to_string(),
+fn get_data_row_span(
+    columns: &[ExcelColumnPosition],
+    range: &calamine::Range<Data>,
+) -> Option<(u32, u32)> {
+    let header_max_y = columns.iter().map(|c| c.y2).max().unwrap_or(0) + 1;
+    let (range_start_y, _) = range.start()?;
+    let (range_end_y, _) = range.end()?;
+    let first_data_row = std::cmp::max(header_max_y, range_start_y);
+    if first_data_row > range_end_y {
+        return None;
     }
+    Some((first_data_row, range_end_y))
 }
 
-fn format_cell_value(data: &Data, treat_float_as_date: bool) -> String {
-    match data {
-        Data::Empty => "".to_string(),
-        Data::String(s) => s.clone(),
-        Data::Float(f) => {
-            if treat_float_as_date {
-                excel_to_date_string(*f)
-            } else {
-                f.to_string()
+fn get_cell_issues(
+    info: &ExcelInfo,
+    column_positions: &[ExcelColumnPosition],
+    range: &calamine::Range<Data>,
+    sheet_name: &str,
+) -> Result<Vec<ExcelValidationIssue>, Box<dyn std::error::Error>> {
+    let mut issues = Vec::new();
+    let Some((first_data_row, range_end_y)) = get_data_row_span(column_positions, range) else {
+        return Ok(issues);
+    };
+    let mut leaf_columns = Vec::new();
+    for position in column_positions.iter().filter(|p| p.is_leaf) {
+        let column = find_column(info, &position.key)?;
+        leaf_columns.push((position, column, get_constraint_regex(column)?));
+    }
+
+    for row_index in first_data_row..=range_end_y {
+        let is_blank_row = leaf_columns.iter().all(|(position, _, _)| {
+            range
+                .get_value((row_index, position.x1 as u32))
+                .map(|cell| format_dynamic_value(cell).trim().is_empty())
+                .unwrap_or(true)
+        });
+        if is_blank_row {
+            continue;
+        }
+        for (position, column, pattern) in leaf_columns.iter() {
+            let cell = range.get_value((row_index, position.x1 as u32));
+            let value = cell
+                .map(|cell| format_value(cell, &column.data_type))
+                .unwrap_or_default();
+            if let Some(code) = validate_cell_value(column, pattern.as_ref(), cell, &value) {
+                issues.push(ExcelValidationIssue::new(
+                    sheet_name.to_string(),
+                    get_excel_cell_ref(position.x1, row_index),
+                    row_index + 1,
+                    column.key.clone(),
+                    code.to_string(),
+                    value,
+                ));
             }
         }
-        Data::Int(i) => i.to_string(),
-        Data::Bool(b) => b.to_string(),
-        Data::DateTime(dt) => excel_to_date_string(dt.as_f64()),
-        _ => data.to_string(),
     }
+    Ok(issues)
 }
 
-fn format_value(data: &Data, data_type: &str) -> String {
-    format_cell_value(data, data_type.eq_ignore_ascii_case("date"))
-}
-
-fn format_dynamic_value(data: &Data) -> String {
-    format_cell_value(data, false)
-}
-
-fn format_header_value(data: Option<&Data>) -> String {
-    match data {
-        Some(Data::Empty) => "".to_string(),
-        Some(Data::String(s)) => s.trim().to_string(),
-        Some(value) => value.to_string().trim().to_string(),
-        None => "".to_string(),
+fn validate_cell_value(
+    column: &ExcelColumnInfo,
+    pattern: Option<&regex::Regex>,
+    cell: Option<&Data>,
+    value: &str,
+) -> Option<&'static str> {
+    if let Some(Data::Error(_)) = cell {
+        return Some("CELL_ERROR_VALUE");
+    }
+    let trimmed_value = value.trim();
+    if trimmed_value.is_empty() {
+        return column.required.then_some("CELL_REQUIRED");
+    }
+    if column.data_type.eq_ignore_ascii_case("number")
+        && !matches!(cell, Some(Data::Float(_) | Data::Int(_)))
+        && trimmed_value.parse::<f64>().is_err()
+    {
+        return Some("CELL_NUMBER_INVALID");
+    }
+    if is_date_data_type(&column.data_type)
+        && !matches!(
+            cell,
+            Some(Data::DateTime(_) | Data::DateTimeIso(_) | Data::Float(_) | Data::Int(_))
+        )
+        && parse_date_value(trimmed_value, column).is_err()
+    {
+        return Some("CELL_DATE_INVALID");
+    }
+    if is_boolean_data_type(&column.data_type)
+        && cell
+            .and_then(|cell| get_boolean_cell_value(cell, column))
+            .is_none()
+    {
+        return Some("CELL_BOOLEAN_INVALID");
+    }
+    if !column.allowed_values.is_empty()
+        && !column.allowed_values.iter().any(|allowed| allowed == value)
+    {
+        return Some("CELL_VALUE_NOT_ALLOWED");
     }
+    get_constraint_issue(column, pattern, cell, value)
 }
 
-fn get_excel_column_name(column_index: u16) -> String {
-    let mut column_number = column_index as usize + 1;
-    let mut letters = Vec::new();
-    while column_number > 0 {
-        let remainder = (column_number - 1) % 26;
-        letters.push((b'A' + remainder as u8) as char);
-        column_number = (column_number - 1) / 26;
+/// Checks a non-empty cell against the column `constraints`.
+fn get_constraint_issue(
+    column: &ExcelColumnInfo,
+    pattern: Option<&regex::Regex>,
+    cell: Option<&Data>,
+    value: &str,
+) -> Option<&'static str> {
+    let constraints = column.constraints.as_ref()?;
+    if constraints.has_range() {
+        let number = match cell {
+            Some(Data::Float(number)) => Some(*number),
+            Some(Data::Int(number)) => Some(*number as f64),
+            _ => value.trim().parse::<f64>().ok(),
+        };
+        if let Some(number) = number {
+            if constraints.min.is_some_and(|min| number < min) {
+                return Some("CELL_BELOW_MIN");
+            }
+            if constraints.max.is_some_and(|max| number > max) {
+                return Some("CELL_ABOVE_MAX");
+            }
+        }
     }
-    letters.into_iter().rev().collect()
+    let length = value.chars().count() as u32;
+    if constraints
+        .min_length
+        .is_some_and(|min_length| length < min_length)
+    {
+        return Some("CELL_TOO_SHORT");
+    }
+    if constraints
+        .max_length
+        .is_some_and(|max_length| length > max_length)
+    {
+        return Some("CELL_TOO_LONG");
+    }
+    if pattern.is_some_and(|pattern| !pattern.is_match(value)) {
+        return Some("CELL_PATTERN_MISMATCH");
+    }
+    None
 }
 
-fn get_excel_cell_ref(x: u16, y: u32) -> String {
-    format!("{}{}", get_excel_column_name(x), y + 1)
+fn get_constraint_regex(
+    column: &ExcelColumnInfo,
+) -> Result<Option<regex::Regex>, Box<dyn std::error::Error>> {
+    let pattern = column
+        .constraints
+        .as_ref()
+        .and_then(|constraints| constraints.pattern.as_deref());
+    match pattern {
+        Some(pattern) => Ok(Some(regex::Regex::new(pattern)?)),
+        None => Ok(None),
+    }
 }
 
-fn validate_headers(
+/// Fails on the first cell that misses a required value or breaks the column
+/// `constraints`. Blank rows are skipped like in the validation report.
+fn check_cell_constraints(
     info: &ExcelInfo,
     range: &calamine::Range<Data>,
     column_positions: &[ExcelColumnPosition],
-    actual_sheet_name: &str,
+    sheet_name: &str,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    for position in column_positions.iter() {
-        let expected_header = info
-            .columns
-            .iter()
-            .find(|column| column.key == position.key)
-            .map(|column| column.name.as_str())
-            .ok_or_else(|| {
-                create_structured_error(
-                    &format!("Column key missing: {}", position.key),
-                    "COLUMN_KEY_MISSING",
-                    &[("columnKey", position.key.clone())],
-                )
-            })?;
-        let actual_header = format_header_value(range.get_value((position.y1, position.x1 as u32)));
-        if actual_header != expected_header.trim() {
-            let cell_ref = get_excel_cell_ref(position.x1, position.y1);
-            return Err(create_structured_error(
-                &format!(
-                    "Header mismatch at {} in sheet '{}': expected '{}', got '{}'",
-                    cell_ref,
-                    actual_sheet_name,
-                    expected_header.trim(),
-                    actual_header
-                ),
-                "HEADER_MISMATCH",
-                &[
-                    ("cell", cell_ref),
-                    ("sheetName", actual_sheet_name.to_string()),
-                    ("expected", expected_header.to_string()),
-                    ("actual", actual_header),
-                ],
-            )
-            .into());
-        }
+    if !info
+        .columns
+        .iter()
+        .any(|column| column.required || column.constraints.is_some())
+    {
+        return Ok(());
     }
-    Ok(())
+    let issue = get_cell_issues(info, column_positions, range, sheet_name)?
+        .into_iter()
+        .find(|issue| CONSTRAINT_ISSUE_CODES.contains(&issue.code.as_str()));
+    let Some(issue) = issue else {
+        return Ok(());
+    };
+    Err(create_structured_error(
+        format!(
+            "Invalid value '{}' at {} in sheet '{}': {}",
+            issue.value, issue.cell, sheet_name, issue.code
+        ),
+        "IMPORT_CONSTRAINT_VIOLATED",
+        &[
+            ("cell", issue.cell),
+            ("sheetName", sheet_name.to_string()),
+            ("columnKey", issue.column_key),
+            ("value", issue.value),
+            ("issueCode", issue.code),
+        ],
+    )
+    .into())
 }
 
-fn resolve_sheet_name(
-    workbook: &Xlsx<Cursor<&[u8]>>,
-    requested_sheet_name: Option<&str>,
-) -> Result<String, Box<dyn std::error::Error>> {
-    if let Some(sheet_name) = requested_sheet_name
-        .map(|value| value.trim())
-        .filter(|value| !value.is_empty())
-    {
-        if workbook
-            .sheet_names()
-            .iter()
-            .any(|candidate| candidate == sheet_name)
-        {
-            return Ok(sheet_name.to_string());
-        }
+/// Reads a date serial to whole seconds, dropping any fraction.
+fn excel_to_date_time(excel_date: f64) -> Option<chrono::NaiveDateTime> {
+    let days_since_epoch = excel_date - EXCEL_BASE_DATE as f64;
+    let seconds_since_epoch = days_since_epoch * SECONDS_IN_A_DAY;
+    chrono::DateTime::from_timestamp(seconds_since_epoch as i64, 0).map(|dt| dt.naive_utc())
+}
+
+/// Reads a date serial rounded to the nearest millisecond.
+fn excel_to_precise_date_time(excel_date: f64) -> Option<chrono::NaiveDateTime> {
+    let days_since_epoch = excel_date - EXCEL_BASE_DATE as f64;
+    let seconds_since_epoch = days_since_epoch * SECONDS_IN_A_DAY;
+    chrono::DateTime::from_timestamp_millis((seconds_since_epoch * 1000.0).round() as i64)
+        .map(|dt| dt.naive_utc())
+}
+
+fn excel_to_date_string(excel_date: f64) -> String {
+    match excel_to_date_time(excel_date) {
+        Some(dt) => dt.format("%Y-%m-%d %H:%M:%S").to_string(),
+        None => "".to_string(),
     }
+}
 
-    workbook.sheet_names().first().cloned().ok_or_else(|| {
-        create_structured_error(
-            "Workbook contains no worksheets",
-            "WORKBOOK_NO_WORKSHEETS",
-            &[],
-        )
-        .into()
+fn date_time_to_excel(date_time: &chrono::NaiveDateTime) -> f64 {
+    let seconds_since_epoch = date_time.and_utc().timestamp_millis() as f64 / 1000.0;
+    seconds_since_epoch / SECONDS_IN_A_DAY + EXCEL_BASE_DATE as f64
+}
+
+fn parse_date_time_text(value: &str) -> Option<chrono::NaiveDateTime> {
+    let value = value.trim();
+    [
+        "%Y-%m-%dT%H:%M:%S%.f",
+        "%Y-%m-%d %H:%M:%S%.f",
+        "%Y-%m-%d %H:%M",
+    ]
+    .iter()
+    .find_map(|pattern| chrono::NaiveDateTime::parse_from_str(value, pattern).ok())
+    .or_else(|| {
+        ["%Y-%m-%d", "%Y/%m/%d"]
+            .iter()
+            .find_map(|pattern| chrono::NaiveDate::parse_from_str(value, pattern).ok())
+            .and_then(|date| date.and_hms_opt(0, 0, 0))
     })
 }
 
-fn resolve_dynamic_header_row(
-    range: &calamine::Range<Data>,
-    header_row: Option<u32>,
-) -> Result<u32, Box<dyn std::error::Error>> {
-    let Some((range_start_y, range_start_x)) = range.start() else {
-        return Err(
-            create_structured_error("Worksheet contains no cells", "WORKSHEET_EMPTY", &[]).into(),
-        );
+/// How finely date cells are read. Dates keep whole seconds, truncated, unless
+/// the column sets an `outputPattern`, which may print fractional seconds.
+#[derive(Clone, Copy, PartialEq)]
+enum DatePrecision {
+    Seconds,
+    Milliseconds,
+}
+
+fn get_date_precision(column: Option<&ExcelColumnInfo>) -> DatePrecision {
+    let has_output_pattern = column
+        .and_then(|column| column.date_options.as_ref())
+        .is_some_and(|date_options| date_options.output_pattern.is_some());
+    if has_output_pattern {
+        DatePrecision::Milliseconds
+    } else {
+        DatePrecision::Seconds
+    }
+}
+
+fn get_typed_cell_value(
+    data: &Data,
+    treat_float_as_date: bool,
+    precision: DatePrecision,
+) -> ExcelValue {
+    let excel_to_date_time = |excel_date: f64| match precision {
+        DatePrecision::Seconds => excel_to_date_time(excel_date),
+        DatePrecision::Milliseconds => excel_to_precise_date_time(excel_date),
     };
-    let Some((range_end_y, range_end_x)) = range.end() else {
-        return Err(
-            create_structured_error("Worksheet contains no cells", "WORKSHEET_EMPTY", &[]).into(),
-        );
+    let date_time_or_text = |date_time: Option<chrono::NaiveDateTime>| {
+        date_time
+            .map(ExcelValue::DateTime)
+            .unwrap_or_else(|| ExcelValue::from_text(format_cell_value(data, treat_float_as_date)))
     };
+    match data {
+        Data::Empty => ExcelValue::Empty,
+        Data::String(s) => ExcelValue::from_text(s.clone()),
+        Data::Float(f) if treat_float_as_date => date_time_or_text(excel_to_date_time(*f)),
+        Data::Float(f) => ExcelValue::Number(*f),
+        Data::Int(i) if treat_float_as_date => date_time_or_text(excel_to_date_time(*i as f64)),
+        Data::Int(i) => ExcelValue::Number(*i as f64),
+        Data::Bool(b) => ExcelValue::Bool(*b),
+        Data::DateTime(dt) => date_time_or_text(excel_to_date_time(dt.as_f64())),
+        Data::DateTimeIso(s) => date_time_or_text(parse_date_time_text(s)),
+        Data::Error(_) => ExcelValue::Error(data.to_string()),
+        _ => ExcelValue::from_text(data.to_string()),
+    }
+}
 
-    if let Some(header_row) = header_row {
-        if header_row == 0 {
-            return Err(create_structured_error(
-                "Dynamic import option 'headerRow' must be greater than or equal to 1",
-                "DYNAMIC_HEADER_ROW_MIN",
-                &[],
-            )
-            .into());
-        }
+fn create_column_data(
+    key: &str,
+    cell: Option<&Data>,
+    data_type: &str,
+    precision: DatePrecision,
+) -> ExcelColumnData {
+    let Some(cell) = cell else {
+        return ExcelColumnData::new(key, "");
+    };
+    if is_link_data_type(data_type) {
+        // Text that is not a link the writers accept, such as `N/A`, stays text
+        // so the imported rows can be exported again.
+        let text = format_cell_value(cell, false);
+        let typed_value = match ExcelValue::parse_link(&text) {
+            ExcelValue::Link { url, .. } if !is_link_url(&url) => ExcelValue::from_text(text),
+            typed_value => typed_value,
+        };
+        return ExcelColumnData::with_typed_value(key, typed_value);
+    }
+    let treat_float_as_date = is_date_data_type(data_type);
+    let mut column_data = ExcelColumnData::new(key, "");
+    column_data.set_typed_value_with_text(
+        get_typed_cell_value(cell, treat_float_as_date, precision),
+        format_cell_value(cell, treat_float_as_date),
+    );
+    column_data
+}
+
+fn is_date_data_type(data_type: &str) -> bool {
+    get_date_kind(data_type).is_some()
+}
+
+/// What a date column holds: a date and time, a calendar date or a clock time.
+#[derive(Clone, Copy, PartialEq)]
+enum DateKind {
+    DateTime,
+    Date,
+    Time,
+}
+
+fn get_date_kind(data_type: &str) -> Option<DateKind> {
+    if data_type.eq_ignore_ascii_case("date") {
+        Some(DateKind::DateTime)
+    } else if data_type.eq_ignore_ascii_case("dateOnly") {
+        Some(DateKind::Date)
+    } else if data_type.eq_ignore_ascii_case("timeOnly") {
+        Some(DateKind::Time)
+    } else {
+        None
+    }
+}
+
+/// The offset of the wall-clock times in a `date` column. Calendar dates and
+/// clock times are never shifted.
+fn get_column_offset(column: &ExcelColumnInfo) -> Option<chrono::FixedOffset> {
+    if get_date_kind(&column.data_type) != Some(DateKind::DateTime) {
+        return None;
+    }
+    column.date_options.as_ref()?.get_offset()
+}
+
+/// Keeps the part of `date_time` the date kind holds. Clock times are placed
+/// on 1899-12-30, the day Excel time serials count from.
+fn truncate_to_date_kind(
+    date_time: chrono::NaiveDateTime,
+    kind: DateKind,
+) -> chrono::NaiveDateTime {
+    match kind {
+        DateKind::DateTime => date_time,
+        DateKind::Date => date_time.date().and_time(chrono::NaiveTime::MIN),
+        DateKind::Time => get_excel_day_zero().and_time(date_time.time()),
+    }
+}
+
+fn get_excel_day_zero() -> chrono::NaiveDate {
+    chrono::NaiveDate::from_ymd_opt(1899, 12, 30).unwrap_or_default()
+}
+
+/// The wall-clock time of `date_time` at `offset`, or in UTC without one.
+fn to_wall_time(
+    date_time: &chrono::DateTime<chrono::FixedOffset>,
+    offset: Option<chrono::FixedOffset>,
+) -> chrono::NaiveDateTime {
+    match offset {
+        Some(offset) => date_time.with_timezone(&offset).naive_local(),
+        None => date_time.naive_utc(),
+    }
+}
+
+/// Parses a date value into the wall-clock time of the column. The column
+/// `parsePatterns` are tried before the built-in formats, and values carrying
+/// their own offset are moved to the column timezone.
+fn parse_date_value(
+    value: &str,
+    column: &ExcelColumnInfo,
+) -> Result<chrono::NaiveDateTime, String> {
+    let value = value.trim();
+    let offset = get_column_offset(column);
+    let parsed = column
+        .date_options
+        .iter()
+        .flat_map(|date_options| date_options.parse_patterns.iter())
+        .find_map(|pattern| parse_date_with_pattern(value, pattern, offset))
+        .or_else(|| {
+            chrono::DateTime::parse_from_rfc3339(value)
+                .ok()
+                .map(|date_time| to_wall_time(&date_time, offset))
+        })
+        .or_else(|| parse_date_time_text(value));
+    if let Some(date_time) = parsed {
+        return Ok(date_time);
+    }
+    let excel_date = ExcelDateTime::parse_from_str(value).map_err(|error| error.to_string())?;
+    excel_to_precise_date_time(excel_date.to_excel())
+        .ok_or_else(|| "date is out of range".to_string())
+}
+
+fn parse_date_with_pattern(
+    value: &str,
+    pattern: &str,
+    offset: Option<chrono::FixedOffset>,
+) -> Option<chrono::NaiveDateTime> {
+    if let Ok(date_time) = chrono::DateTime::parse_from_str(value, pattern) {
+        return Some(to_wall_time(&date_time, offset));
+    }
+    chrono::NaiveDateTime::parse_from_str(value, pattern)
+        .ok()
+        .or_else(|| {
+            chrono::NaiveDate::parse_from_str(value, pattern)
+                .ok()
+                .map(|date| date.and_time(chrono::NaiveTime::MIN))
+        })
+        .or_else(|| {
+            chrono::NaiveTime::parse_from_str(value, pattern)
+                .ok()
+                .map(|time| get_excel_day_zero().and_time(time))
+        })
+}
+
+/// Renders a wall-clock time with the column `outputPattern`, or with the
+/// default pattern of its date kind.
+fn format_date_value(date_time: &chrono::NaiveDateTime, column: &ExcelColumnInfo) -> String {
+    use std::fmt::Write as _;
+    let default_pattern = match get_date_kind(&column.data_type) {
+        Some(DateKind::Date) => "%Y-%m-%d",
+        Some(DateKind::Time) => "%H:%M:%S%.f",
+        _ => "%Y-%m-%d %H:%M:%S%.f",
+    };
+    let pattern = column
+        .date_options
+        .as_ref()
+        .and_then(|date_options| date_options.output_pattern.as_deref())
+        .unwrap_or(default_pattern);
+    let offset = get_column_offset(column).unwrap_or_else(|| chrono::Offset::fix(&chrono::Utc));
+    let mut text = String::new();
+    let result = match date_time.and_local_timezone(offset).single() {
+        Some(date_time) => write!(text, "{}", date_time.format(pattern)),
+        None => write!(text, "{}", date_time.format(pattern)),
+    };
+    match result {
+        Ok(()) => text,
+        Err(_) => date_time.format(default_pattern).to_string(),
+    }
+}
+
+/// Turns an imported wall-clock time into the UTC instant of `typedValue`.
+fn to_utc_date_time(
+    date_time: &chrono::NaiveDateTime,
+    column: &ExcelColumnInfo,
+) -> chrono::NaiveDateTime {
+    get_column_offset(column)
+        .and_then(|offset| date_time.and_local_timezone(offset).single())
+        .map(|date_time| date_time.naive_utc())
+        .unwrap_or(*date_time)
+}
+
+fn apply_date_import_options(column: &ExcelColumnInfo, column_data: &mut ExcelColumnData) {
+    let Some(kind) = get_date_kind(&column.data_type) else {
+        return;
+    };
+    let has_parse_patterns = column
+        .date_options
+        .as_ref()
+        .is_some_and(|date_options| !date_options.parse_patterns.is_empty());
+    let date_time = match column_data.typed_value() {
+        ExcelValue::DateTime(date_time) => *date_time,
+        ExcelValue::Text(value) if has_parse_patterns => match parse_date_value(value, column) {
+            Ok(date_time) => date_time,
+            Err(_) => return,
+        },
+        _ => return,
+    };
+    let date_time = truncate_to_date_kind(date_time, kind);
+    column_data.set_typed_value_with_text(
+        ExcelValue::DateTime(to_utc_date_time(&date_time, column)),
+        format_date_value(&date_time, column),
+    );
+}
+
+fn is_boolean_data_type(data_type: &str) -> bool {
+    data_type.eq_ignore_ascii_case("boolean")
+}
+
+/// Reads the custom labels of `column` and common spellings such as
+/// yes/no, y/n, 1/0 and on/off, ignoring case.
+fn parse_boolean_text(value: &str, column: &ExcelColumnInfo) -> Option<bool> {
+    let value = value.trim();
+    if let Some((true_label, false_label)) = column.get_boolean_labels() {
+        if value == true_label.trim() {
+            return Some(true);
+        }
+        if value == false_label.trim() {
+            return Some(false);
+        }
+    }
+    let value = value.to_lowercase();
+    if TRUE_VALUES.contains(&value.as_str()) {
+        Some(true)
+    } else if FALSE_VALUES.contains(&value.as_str()) {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+fn get_boolean_cell_value(cell: &Data, column: &ExcelColumnInfo) -> Option<bool> {
+    match cell {
+        Data::Bool(boolean) => Some(*boolean),
+        Data::Int(0) => Some(false),
+        Data::Int(1) => Some(true),
+        Data::Float(number) if *number == 0.0 || *number == 1.0 => Some(*number == 1.0),
+        Data::String(value) => parse_boolean_text(value, column),
+        _ => None,
+    }
+}
+
+struct InvalidBooleanCell<'a> {
+    position: &'a ExcelColumnPosition,
+    row_index: u32,
+    value: String,
+}
+
+/// Replaces readable values in `boolean` columns with `Data::Bool` and
+/// returns the cells that could not be read as a boolean.
+fn coerce_boolean_cells<'a>(
+    info: &ExcelInfo,
+    range: &mut calamine::Range<Data>,
+    column_positions: &'a [ExcelColumnPosition],
+) -> Result<Vec<InvalidBooleanCell<'a>>, Box<dyn std::error::Error>> {
+    let mut invalid_cells = Vec::new();
+    let Some((first_data_row, range_end_y)) = get_data_row_span(column_positions, range) else {
+        return Ok(invalid_cells);
+    };
+    for position in column_positions
+        .iter()
+        .filter(|position| position.is_leaf && is_boolean_data_type(&position.data_type))
+    {
+        let column = find_column(info, &position.key)?;
+        for row_index in first_data_row..=range_end_y {
+            let cell_position = (row_index, position.x1 as u32);
+            let Some(cell) = range.get_value(cell_position) else {
+                continue;
+            };
+            if format_dynamic_value(cell).trim().is_empty() {
+                continue;
+            }
+            match get_boolean_cell_value(cell, column) {
+                Some(boolean) => range.set_value(cell_position, Data::Bool(boolean)),
+                None => invalid_cells.push(InvalidBooleanCell {
+                    position,
+                    row_index,
+                    value: format_dynamic_value(cell),
+                }),
+            }
+        }
+    }
+    Ok(invalid_cells)
+}
+
+/// Coerces `boolean` columns and fails on the first value that is not a boolean.
+fn read_boolean_cells(
+    info: &ExcelInfo,
+    range: &mut calamine::Range<Data>,
+    column_positions: &[ExcelColumnPosition],
+    sheet_name: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let invalid_cells = coerce_boolean_cells(info, range, column_positions)?;
+    let Some(InvalidBooleanCell {
+        position,
+        row_index,
+        value,
+    }) = invalid_cells.into_iter().next()
+    else {
+        return Ok(());
+    };
+    let cell_ref = get_excel_cell_ref(position.x1, row_index);
+    Err(create_structured_error(
+        format!(
+            "Invalid boolean value '{}' at {} in sheet '{}'",
+            value, cell_ref, sheet_name
+        ),
+        "IMPORT_BOOLEAN_VALUE_INVALID",
+        &[
+            ("cell", cell_ref),
+            ("sheetName", sheet_name.to_string()),
+            ("columnKey", position.key.clone()),
+            ("value", value),
+        ],
+    )
+    .into())
+}
+
+/// Replaces hyperlinked cells in `link` columns with their `text|url` value.
+/// Hyperlinks to targets the writers cannot write keep the cell text.
+fn read_link_cells(
+    hyperlinks: &[(calamine::Dimensions, String)],
+    range: &mut calamine::Range<Data>,
+    column_positions: &[ExcelColumnPosition],
+) {
+    let link_columns = column_positions
+        .iter()
+        .filter(|position| position.is_leaf && is_link_data_type(&position.data_type))
+        .map(|position| position.x1 as u32)
+        .collect::<Vec<u32>>();
+    if link_columns.is_empty() {
+        return;
+    }
+    let Some((first_data_row, range_end_y)) = get_data_row_span(column_positions, range) else {
+        return;
+    };
+    for (dimensions, url) in hyperlinks {
+        let ((first_row, first_column), (last_row, last_column)) =
+            (dimensions.start, dimensions.end);
+        for x in link_columns
+            .iter()
+            .filter(|x| (first_column..=last_column).contains(*x))
+        {
+            for y in first_row.max(first_data_row)..=last_row.min(range_end_y) {
+                let text = range
+                    .get_value((y, *x))
+                    .map(format_dynamic_value)
+                    .filter(|text| !text.trim().is_empty())
+                    .unwrap_or_else(|| url.clone());
+                let link = ExcelValue::Link {
+                    url: url.clone(),
+                    text,
+                };
+                range.set_value((y, *x), Data::String(link.to_display_string()));
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum NumberImportMode {
+    Raw,
+    Round,
+    Format,
+}
+
+fn get_number_import_mode(
+    info: &ExcelInfo,
+) -> Result<NumberImportMode, Box<dyn std::error::Error>> {
+    let Some(mode) = info.import_number_mode.as_deref().map(str::trim) else {
+        return Ok(NumberImportMode::Raw);
+    };
+    match mode.to_ascii_lowercase().as_str() {
+        "" | "raw" => Ok(NumberImportMode::Raw),
+        "round" => Ok(NumberImportMode::Round),
+        "format" => Ok(NumberImportMode::Format),
+        _ => Err(create_structured_error(
+            format!(
+                "Unsupported import number mode '{}', expected raw, round or format",
+                mode
+            ),
+            "IMPORT_NUMBER_MODE_INVALID",
+            &[("mode", mode.to_string())],
+        )
+        .into()),
+    }
+}
+
+/// Rounds imported numbers to their column `numberFormat`, or to 15 significant
+/// digits without one, and renders `value` with the format in `format` mode.
+/// Dates are rendered with the column `dateOptions` whatever the mode.
+fn apply_import_value_options(info: &ExcelInfo, mode: NumberImportMode, rows: &mut [ExcelRowData]) {
+    if mode == NumberImportMode::Raw
+        && !info
+            .columns
+            .iter()
+            .any(|column| is_date_data_type(&column.data_type))
+    {
+        return;
+    }
+    for row in rows.iter_mut() {
+        for column_data in row.columns.iter_mut() {
+            apply_import_value_options(info, mode, &mut column_data.children);
+            let column = info
+                .columns
+                .iter()
+                .find(|column| column.key == column_data.key);
+            if let Some(column) = column.filter(|column| is_date_data_type(&column.data_type)) {
+                apply_date_import_options(column, column_data);
+                continue;
+            }
+            if mode == NumberImportMode::Raw {
+                continue;
+            }
+            let ExcelValue::Number(number) = *column_data.typed_value() else {
+                continue;
+            };
+            let number_format = column.and_then(|column| column.number_format.as_ref());
+            let rounded = match number_format {
+                Some(number_format) => number_format.round_number(number),
+                None => round_to_significant_digits(number),
+            };
+            let value = match (mode, number_format) {
+                (NumberImportMode::Format, Some(number_format)) => {
+                    number_format.format_number(number)
+                }
+                _ => rounded.to_string(),
+            };
+            column_data.set_typed_value_with_text(ExcelValue::Number(rounded), value);
+        }
+    }
+}
+
+fn format_cell_value(data: &Data, treat_float_as_date: bool) -> String {
+    match data {
+        Data::Empty => "".to_string(),
+        Data::String(s) => s.clone(),
+        Data::Float(f) => {
+            if treat_float_as_date {
+                excel_to_date_string(*f)
+            } else {
+                f.to_string()
+            }
+        }
+        Data::Int(i) => i.to_string(),
+        Data::Bool(b) => b.to_string(),
+        Data::DateTime(dt) => excel_to_date_string(dt.as_f64()),
+        _ => data.to_string(),
+    }
+}
+
+fn format_value(data: &Data, data_type: &str) -> String {
+    format_cell_value(data, is_date_data_type(data_type))
+}
+
+fn format_dynamic_value(data: &Data) -> String {
+    format_cell_value(data, false)
+}
+
+fn format_header_value(data: Option<&Data>) -> String {
+    match data {
+        Some(Data::Empty) => "".to_string(),
+        Some(Data::String(s)) => s.trim().to_string(),
+        Some(value) => value.to_string().trim().to_string(),
+        None => "".to_string(),
+    }
+}
+
+fn get_excel_column_name(column_index: u16) -> String {
+    let mut column_number = column_index as usize + 1;
+    let mut letters = Vec::new();
+    while column_number > 0 {
+        let remainder = (column_number - 1) % 26;
+        letters.push((b'A' + remainder as u8) as char);
+        column_number = (column_number - 1) / 26;
+    }
+    letters.into_iter().rev().collect()
+}
+
+fn get_excel_cell_ref(x: u16, y: u32) -> String {
+    format!("{}{}", get_excel_column_name(x), y + 1)
+}
+
+fn validate_headers(
+    info: &ExcelInfo,
+    range: &calamine::Range<Data>,
+    column_positions: &[ExcelColumnPosition],
+    actual_sheet_name: &str,
+) -> Result<BoundHeaders, Box<dyn std::error::Error>> {
+    let mut bound_headers = bind_headers(info, range, column_positions, actual_sheet_name)?;
+    if let Some(HeaderMismatch {
+        position,
+        code,
+        expected_header,
+        actual_header,
+    }) = bound_headers.mismatches.drain(..).next()
+    {
+        let cell_ref = get_excel_cell_ref(position.x1, position.y1);
+        let message = if code == "HEADER_NOT_FOUND" {
+            format!(
+                "Header '{}' not found in row {} of sheet '{}'",
+                expected_header.trim(),
+                position.y1 + 1,
+                actual_sheet_name
+            )
+        } else {
+            format!(
+                "Header mismatch at {} in sheet '{}': expected '{}', got '{}'",
+                cell_ref,
+                actual_sheet_name,
+                expected_header.trim(),
+                actual_header
+            )
+        };
+        return Err(create_structured_error(
+            message,
+            code,
+            &[
+                ("cell", cell_ref),
+                ("sheetName", actual_sheet_name.to_string()),
+                ("expected", expected_header),
+                ("actual", actual_header),
+            ],
+        )
+        .into());
+    }
+    Ok(bound_headers)
+}
+
+struct HeaderMismatch {
+    position: ExcelColumnPosition,
+    code: &'static str,
+    expected_header: String,
+    actual_header: String,
+}
+
+struct BoundHeaders {
+    positions: Vec<ExcelColumnPosition>,
+    bindings: Vec<ExcelColumnBinding>,
+    mismatches: Vec<HeaderMismatch>,
+}
+
+/// Binds each schema column to a header cell. A column matches by name or
+/// alias at its template position, or with `match_headers_by_name` anywhere
+/// in its header row within the cells bound to its parent.
+fn bind_headers(
+    info: &ExcelInfo,
+    range: &calamine::Range<Data>,
+    column_positions: &[ExcelColumnPosition],
+    sheet_name: &str,
+) -> Result<BoundHeaders, Box<dyn std::error::Error>> {
+    let mut bound_headers = BoundHeaders {
+        positions: Vec::with_capacity(column_positions.len()),
+        bindings: Vec::new(),
+        mismatches: Vec::new(),
+    };
+    let row_span = match (range.start(), range.end()) {
+        (Some((_, start_x)), Some((_, end_x))) => (start_x as u16, end_x as u16),
+        _ => (0, 0),
+    };
+    let mut bound_spans: HashMap<&str, (u16, u16)> = HashMap::new();
+    let mut claimed_cells = HashSet::new();
+    for position in column_positions.iter() {
+        let column = find_column(info, &position.key)?;
+        let header_names = std::iter::once(&column.name)
+            .chain(column.aliases.iter())
+            .map(|name| normalize_header(name, info))
+            .collect::<Vec<String>>();
+        let get_header = |x: u16| format_header_value(range.get_value((position.y1, x as u32)));
+        let is_match = |x: u16| {
+            !claimed_cells.contains(&(position.y1, x))
+                && header_names.contains(&normalize_header(&get_header(x), info))
+        };
+        let bound_x = if is_match(position.x1) {
+            Some(position.x1)
+        } else if info.match_headers_by_name {
+            let (x1, x2) = bound_spans
+                .get(column.parent.as_str())
+                .copied()
+                .unwrap_or(row_span);
+            (x1..=x2).find(|x| is_match(*x))
+        } else {
+            None
+        };
+        let Some(bound_x) = bound_x else {
+            bound_headers.mismatches.push(HeaderMismatch {
+                position: position.clone(),
+                code: if info.match_headers_by_name {
+                    "HEADER_NOT_FOUND"
+                } else {
+                    "HEADER_MISMATCH"
+                },
+                expected_header: column.name.clone(),
+                actual_header: get_header(position.x1),
+            });
+            // A column that was not found by name has no cells to read.
+            if !info.match_headers_by_name {
+                bound_headers.positions.push(position.clone());
+            }
+            continue;
+        };
+        let bound_position = ExcelColumnPosition {
+            x1: bound_x,
+            x2: bound_x + (position.x2 - position.x1),
+            ..position.clone()
+        };
+        claimed_cells.insert((position.y1, bound_x));
+        bound_spans.insert(&position.key, (bound_position.x1, bound_position.x2));
+        bound_headers.bindings.push(ExcelColumnBinding {
+            sheet_name: sheet_name.to_string(),
+            key: position.key.clone(),
+            header: get_header(bound_x),
+            cell: get_excel_cell_ref(bound_x, position.y1),
+            column_index: bound_x,
+        });
+        bound_headers.positions.push(bound_position);
+    }
+    Ok(bound_headers)
+}
+
+fn normalize_header(header: &str, info: &ExcelInfo) -> String {
+    let header = if info.normalize_header_whitespace {
+        header.split_whitespace().collect::<Vec<&str>>().join(" ")
+    } else {
+        header.trim().to_string()
+    };
+    if info.ignore_header_case {
+        header.to_lowercase()
+    } else {
+        header
+    }
+}
+
+fn resolve_sheet_name(
+    workbook: &ImportWorkbook,
+    requested_sheet_name: Option<&str>,
+) -> Result<String, Box<dyn std::error::Error>> {
+    if let Some(sheet_name) = requested_sheet_name
+        .map(|value| value.trim())
+        .filter(|value| !value.is_empty())
+    {
+        if workbook
+            .sheet_names()
+            .iter()
+            .any(|candidate| candidate == sheet_name)
+        {
+            return Ok(sheet_name.to_string());
+        }
+    }
+
+    workbook.sheet_names().first().cloned().ok_or_else(|| {
+        create_structured_error(
+            "Workbook contains no worksheets",
+            "WORKBOOK_NO_WORKSHEETS",
+            &[],
+        )
+        .into()
+    })
+}
+
+fn resolve_import_sheet_name(
+    workbook: &ImportWorkbook,
+    info: &ExcelInfo,
+) -> Result<String, Box<dyn std::error::Error>> {
+    if info.import_by_sheet_name {
+        find_sheet_name(workbook, &info.sheet_name)
+    } else {
+        resolve_sheet_name(workbook, None)
+    }
+}
+
+fn find_sheet_name(
+    workbook: &ImportWorkbook,
+    sheet_name: &str,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let sheet_name = sheet_name.trim();
+    workbook
+        .sheet_names()
+        .into_iter()
+        .find(|candidate| candidate == sheet_name)
+        .ok_or_else(|| {
+            create_structured_error(
+                format!("Worksheet '{}' not found in workbook", sheet_name),
+                "SHEET_NOT_FOUND",
+                &[("sheetName", sheet_name.to_string())],
+            )
+            .into()
+        })
+}
+
+fn resolve_dynamic_header_row(
+    range: &calamine::Range<Data>,
+    header_row: Option<u32>,
+) -> Result<u32, Box<dyn std::error::Error>> {
+    let Some((range_start_y, range_start_x)) = range.start() else {
+        return Err(
+            create_structured_error("Worksheet contains no cells", "WORKSHEET_EMPTY", &[]).into(),
+        );
+    };
+    let Some((range_end_y, range_end_x)) = range.end() else {
+        return Err(
+            create_structured_error("Worksheet contains no cells", "WORKSHEET_EMPTY", &[]).into(),
+        );
+    };
+
+    if let Some(header_row) = header_row {
+        if header_row == 0 {
+            return Err(create_structured_error(
+                "Dynamic import option 'headerRow' must be greater than or equal to 1",
+                "DYNAMIC_HEADER_ROW_MIN",
+                &[],
+            )
+            .into());
+        }
         let zero_based_row = header_row - 1;
         if zero_based_row < range_start_y || zero_based_row > range_end_y {
             return Err(create_structured_error(
-                &format!(
+                format!(
                     "Dynamic import option 'headerRow' must point to a row within the used range. Received {}.",
                     header_row
                 ),
@@ -498,7 +1415,7 @@ fn get_dynamic_headers(
         if header.is_empty() {
             let cell_ref = get_excel_cell_ref(column_index as u16, header_row);
             return Err(create_structured_error(
-                &format!(
+                format!(
                     "Dynamic import requires non-empty header names. Found an empty header at {}.",
                     cell_ref
                 ),
@@ -510,7 +1427,7 @@ fn get_dynamic_headers(
         if !seen_headers.insert(header.clone()) {
             let cell_ref = get_excel_cell_ref(column_index as u16, header_row);
             return Err(create_structured_error(
-                &format!(
+                format!(
                     "Dynamic import requires unique header names. Duplicate header '{}' found at {}.",
                     header, cell_ref
                 ),
@@ -529,6 +1446,7 @@ fn get_dynamic_rows_data(
     range: &calamine::Range<Data>,
     header_row: u32,
     headers: &[(String, u32)],
+    sheet_name: &str,
 ) -> Vec<ExcelRowData> {
     if range.start().is_none() {
         return Vec::new();
@@ -541,53 +1459,593 @@ fn get_dynamic_rows_data(
         return Vec::new();
     }
 
-    (first_data_row..=range_end_y)
-        .map(|row_index| ExcelRowData {
-            columns: headers
-                .iter()
-                .map(|(header, column_index)| {
-                    let value = range
-                        .get_value((row_index, *column_index))
-                        .map(format_dynamic_value)
-                        .unwrap_or_default();
-                    ExcelColumnData {
-                        key: header.clone(),
-                        value,
-                        children: Vec::new(),
-                    }
-                })
-                .collect(),
+    (first_data_row..=range_end_y)
+        .map(|row_index| ExcelRowData {
+            columns: headers
+                .iter()
+                .map(|(header, column_index)| {
+                    create_column_data(
+                        header,
+                        range.get_value((row_index, *column_index)),
+                        "text",
+                        DatePrecision::Seconds,
+                    )
+                })
+                .collect(),
+            sheet_name: Some(sheet_name.to_string()),
+        })
+        .collect()
+}
+
+/// How [`prepare_import_range`] handles cells that break the schema.
+#[derive(Clone, Copy)]
+enum CellCheck {
+    /// Fails on the first invalid cell.
+    Strict,
+    /// Collects every invalid cell as a validation issue.
+    Report,
+}
+
+struct PreparedRange {
+    issues: Vec<ExcelValidationIssue>,
+    skipped_rows: Vec<u32>,
+}
+
+/// What an import reads from the workbook besides the cell values: the
+/// totals rows of the sheet's tables and the hyperlinks of `link` columns.
+struct SheetExtras {
+    table_totals_rows: Vec<u32>,
+    hyperlinks: Vec<(calamine::Dimensions, String)>,
+}
+
+fn read_sheet_extras(
+    workbook: &mut ImportWorkbook,
+    excel_bytes: &[u8],
+    sheet_name: &str,
+    column_positions: &[ExcelColumnPosition],
+) -> Result<SheetExtras, Box<dyn std::error::Error>> {
+    let has_link_columns = column_positions
+        .iter()
+        .any(|position| position.is_leaf && is_link_data_type(&position.data_type));
+    let hyperlinks = if has_link_columns {
+        get_hyperlinks(workbook, sheet_name)?
+            .into_iter()
+            .filter(|(_, url)| is_link_url(url))
+            .collect()
+    } else {
+        Vec::new()
+    };
+    Ok(SheetExtras {
+        table_totals_rows: get_table_totals_rows(workbook, excel_bytes, sheet_name)?,
+        hyperlinks,
+    })
+}
+
+/// The summary options imports look for, if any summary row is enabled.
+fn get_import_summary(info: &ExcelInfo) -> Option<&ExcelSummaryOptions> {
+    info.summary
+        .as_ref()
+        .filter(|summary| summary.totals || summary.subtotals)
+}
+
+/// Whether telling the summary rows apart needs the cell formulas, which is
+/// the case unless the summary is precomputed.
+fn needs_summary_formulas(info: &ExcelInfo) -> bool {
+    get_import_summary(info).is_some_and(|summary| !summary.precomputed)
+}
+
+/// Gets the data rows of a sheet ready to read: blanks the totals rows of its
+/// tables and the summary rows, checks the cells, reads booleans and reads hyperlinks. The
+/// blanked rows are returned so flat reads can drop them with
+/// [`remove_range_rows`] while grouped reads keep the merged row indexes.
+fn prepare_import_range(
+    info: &ExcelInfo,
+    workbook: &mut ImportWorkbook,
+    excel_bytes: &[u8],
+    sheet_name: &str,
+    range: &mut calamine::Range<Data>,
+    column_positions: &[ExcelColumnPosition],
+    cell_check: CellCheck,
+) -> Result<PreparedRange, Box<dyn std::error::Error>> {
+    let extras = read_sheet_extras(workbook, excel_bytes, sheet_name, column_positions)?;
+    let formulas = if needs_summary_formulas(info) {
+        Some(workbook.worksheet_formula(sheet_name)?)
+    } else {
+        None
+    };
+    let last_row = range.end().map(|(y, _)| y);
+    let summary_rows = get_summary_rows(info, range, column_positions, formulas.as_ref(), last_row);
+    prepare_range_rows(
+        info,
+        &extras,
+        sheet_name,
+        range,
+        column_positions,
+        summary_rows,
+        cell_check,
+    )
+}
+
+/// Prepares the rows of `range`, which may hold only part of the sheet, as
+/// [`prepare_import_range`] does, skipping `summary_rows`.
+fn prepare_range_rows(
+    info: &ExcelInfo,
+    extras: &SheetExtras,
+    sheet_name: &str,
+    range: &mut calamine::Range<Data>,
+    column_positions: &[ExcelColumnPosition],
+    summary_rows: Vec<u32>,
+    cell_check: CellCheck,
+) -> Result<PreparedRange, Box<dyn std::error::Error>> {
+    let mut skipped_rows = summary_rows;
+    if let Some((first_data_row, range_end_y)) = get_data_row_span(column_positions, range) {
+        skipped_rows.extend(
+            extras
+                .table_totals_rows
+                .iter()
+                .filter(|y| (first_data_row..=range_end_y).contains(*y)),
+        );
+    }
+    blank_range_rows(range, &skipped_rows);
+    match cell_check {
+        CellCheck::Strict => {
+            check_cell_constraints(info, range, column_positions, sheet_name)?;
+            read_boolean_cells(info, range, column_positions, sheet_name)?;
+        }
+        CellCheck::Report => {
+            coerce_boolean_cells(info, range, column_positions)?;
+        }
+    }
+    read_link_cells(&extras.hyperlinks, range, column_positions);
+    let issues = match cell_check {
+        CellCheck::Strict => Vec::new(),
+        CellCheck::Report => get_cell_issues(info, column_positions, range, sheet_name)?,
+    };
+    Ok(PreparedRange {
+        issues,
+        skipped_rows,
+    })
+}
+
+/// Reads the rows of the first sheet after checking the headers match the schema.
+/// With `importBySheetName` set it reads the sheet named `info.sheet_name` instead.
+pub fn import_data_buffer(
+    info: ExcelInfo,
+    excel_bytes: &[u8],
+) -> Result<ExcelData, Box<dyn std::error::Error>> {
+    let mut workbook = open_import_workbook(excel_bytes, info.import_format.as_deref())?;
+    let sheet_name = resolve_import_sheet_name(&workbook, &info)?;
+    let mut range = workbook.worksheet_range(sheet_name.as_str())?;
+    let bound_headers = validate_headers(
+        &info,
+        &range,
+        &get_column_positions(&info),
+        sheet_name.as_str(),
+    )?;
+    let prepared = prepare_import_range(
+        &info,
+        &mut workbook,
+        excel_bytes,
+        &sheet_name,
+        &mut range,
+        &bound_headers.positions,
+        CellCheck::Strict,
+    )?;
+    remove_range_rows(&mut range, &prepared.skipped_rows);
+    let mut rows = get_rows_data(&info, &bound_headers.positions, &range, &sheet_name);
+    apply_import_value_options(&info, get_number_import_mode(&info)?, &mut rows);
+    Ok(ExcelData {
+        rows,
+        column_bindings: bound_headers.bindings,
+    })
+}
+
+/// Reads rows in batches of `batch_size` and hands each batch to `on_batch`.
+/// Returning `false` from the callback stops the import early.
+///
+/// Xlsx sheets are read a block of `batch_size` rows at a time, and each block
+/// is checked as it is read, so a bad cell fails the import after the batches
+/// before it were delivered. Other formats are parsed whole before the first
+/// batch.
+pub async fn import_data_in_batches_buffer<F, Fut>(
+    info: ExcelInfo,
+    excel_bytes: &[u8],
+    batch_size: Option<u32>,
+    mut on_batch: F,
+) -> Result<ExcelImportSummary, Box<dyn std::error::Error>>
+where
+    F: FnMut(Vec<ExcelRowData>, f64) -> Fut,
+    Fut: Future<Output = Result<bool, Box<dyn std::error::Error>>>,
+{
+    let batch_size = batch_size.unwrap_or(DEFAULT_IMPORT_BATCH_SIZE);
+    let number_import_mode = get_number_import_mode(&info)?;
+    if batch_size == 0 {
+        return Err(create_structured_error(
+            "Import batch size must be greater than zero",
+            "IMPORT_BATCH_SIZE_INVALID",
+            &[("batchSize", batch_size.to_string())],
+        )
+        .into());
+    }
+    let mut workbook = open_import_workbook(excel_bytes, info.import_format.as_deref())?;
+    let sheet_name = resolve_import_sheet_name(&workbook, &info)?;
+    let column_positions = get_column_positions(&info);
+    let extras = read_sheet_extras(&mut workbook, excel_bytes, &sheet_name, &column_positions)?;
+    let mut cells = read_sheet_cells(&mut workbook, &sheet_name, needs_summary_formulas(&info))?;
+
+    let header_end_y = column_positions
+        .iter()
+        .map(|position| position.y2)
+        .max()
+        .unwrap_or(0);
+    let mut header_cells = Vec::new();
+    let mut next_cell = cells.next_cell()?;
+    while let Some(cell) = next_cell.take_if(|cell| cell.pos.0 <= header_end_y) {
+        header_cells.push(calamine::Cell::new(cell.pos, cell.value));
+        next_cell = cells.next_cell()?;
+    }
+    let bound_headers = validate_headers(
+        &info,
+        &calamine::Range::from_sparse(header_cells),
+        &column_positions,
+        sheet_name.as_str(),
+    )?;
+
+    let first_data_row = header_end_y + 1;
+    let sheet_rows = (cells.get_end_row() + 1).saturating_sub(first_data_row);
+    let mut summary = ExcelImportSummary {
+        sheet_name: sheet_name.clone(),
+        total_rows: 0,
+        imported_rows: 0,
+        aborted: false,
+        column_bindings: bound_headers.bindings.clone(),
+    };
+    let mut rows = Vec::new();
+    let mut block_start = first_data_row;
+    let mut is_sheet_end = false;
+    while !is_sheet_end {
+        let mut block_cells = Vec::new();
+        let block_end = block_start.saturating_add(batch_size - 1);
+        while let Some(cell) = next_cell.take_if(|cell| cell.pos.0 <= block_end) {
+            block_cells.push(cell);
+            next_cell = cells.next_cell()?;
+        }
+        // The block holding the last cell ends at its row.
+        is_sheet_end = next_cell.is_none();
+        let block_end = match block_cells.last() {
+            Some(cell) if is_sheet_end => cell.pos.0,
+            None if is_sheet_end => block_start - 1,
+            _ => block_end,
+        };
+        if block_end >= block_start {
+            rows.extend(read_row_block(
+                &info,
+                &extras,
+                &sheet_name,
+                &bound_headers.positions,
+                (block_start, block_end),
+                block_cells,
+                is_sheet_end,
+            )?);
+        }
+        block_start = block_end + 1;
+
+        let read_rows = block_start - first_data_row;
+        while rows.len() >= batch_size as usize || (is_sheet_end && !rows.is_empty()) {
+            let mut batch = rows
+                .drain(..rows.len().min(batch_size as usize))
+                .collect::<Vec<ExcelRowData>>();
+            apply_import_value_options(&info, number_import_mode, &mut batch);
+            summary.imported_rows += batch.len() as u32;
+            let is_last_batch = is_sheet_end && rows.is_empty();
+            let progress = if is_last_batch {
+                1.0
+            } else {
+                read_rows as f64 / sheet_rows.max(read_rows) as f64
+            };
+            report_progress(info.progress_callback.as_ref(), progress);
+            if !on_batch(batch, progress).await? {
+                if !is_last_batch {
+                    // The rest of the sheet is not read, so its rows are
+                    // counted from the sheet dimension.
+                    summary.aborted = true;
+                    summary.total_rows = summary.imported_rows
+                        + rows.len() as u32
+                        + sheet_rows.saturating_sub(read_rows);
+                    return Ok(summary);
+                }
+                break;
+            }
+        }
+    }
+    summary.total_rows = summary.imported_rows;
+    Ok(summary)
+}
+
+/// Reads sheet rows `y1` to `y2` from their `cells` and checks them as
+/// [`import_data_buffer`] checks a whole sheet. `is_sheet_end` marks the block
+/// holding the last row, where a precomputed totals row sits.
+fn read_row_block(
+    info: &ExcelInfo,
+    extras: &SheetExtras,
+    sheet_name: &str,
+    column_positions: &[ExcelColumnPosition],
+    (y1, y2): (u32, u32),
+    cells: Vec<SheetCell>,
+    is_sheet_end: bool,
+) -> Result<Vec<ExcelRowData>, Box<dyn std::error::Error>> {
+    let end_x = column_positions
+        .iter()
+        .map(|position| u32::from(position.x2))
+        .max()
+        .unwrap_or(0);
+    let mut range = calamine::Range::new((y1, 0), (y2, end_x));
+    let mut formulas =
+        needs_summary_formulas(info).then(|| calamine::Range::new((y1, 0), (y2, end_x)));
+    for cell in cells
+        .into_iter()
+        .filter(|cell| (y1..=y2).contains(&cell.pos.0) && cell.pos.1 <= end_x)
+    {
+        if let (Some(formulas), Some(formula)) = (formulas.as_mut(), cell.formula) {
+            formulas.set_value(cell.pos, formula);
+        }
+        range.set_value(cell.pos, cell.value);
+    }
+    let summary_rows = get_summary_rows(
+        info,
+        &range,
+        column_positions,
+        formulas.as_ref(),
+        is_sheet_end.then_some(y2),
+    );
+    let prepared = prepare_range_rows(
+        info,
+        extras,
+        sheet_name,
+        &mut range,
+        column_positions,
+        summary_rows,
+        CellCheck::Strict,
+    )?;
+    Ok(iter_rows_data(info, column_positions, &range, sheet_name)
+        .zip(y1..)
+        .filter(|(_, y)| !prepared.skipped_rows.contains(y))
+        .map(|(row, _)| row)
+        .collect())
+}
+
+/// Reads rows and nests merged parent cells with their child rows.
+pub fn import_grouped_data_buffer(
+    info: ExcelInfo,
+    excel_bytes: &[u8],
+) -> Result<ExcelData, Box<dyn std::error::Error>> {
+    let mut workbook = open_import_workbook(excel_bytes, info.import_format.as_deref())?;
+    let sheet_name = resolve_import_sheet_name(&workbook, &info)?;
+    let mut range = workbook.worksheet_range(sheet_name.as_str())?;
+    let merged_row_ends = get_merged_row_ends(&mut workbook, sheet_name.as_str())?;
+    let BoundHeaders {
+        positions: column_positions,
+        bindings,
+        ..
+    } = validate_headers(
+        &info,
+        &range,
+        &get_column_positions(&info),
+        sheet_name.as_str(),
+    )?;
+    let prepared = prepare_import_range(
+        &info,
+        &mut workbook,
+        excel_bytes,
+        &sheet_name,
+        &mut range,
+        &column_positions,
+        CellCheck::Strict,
+    )?;
+    let mut excel_data = ExcelData {
+        rows: Vec::new(),
+        column_bindings: bindings,
+    };
+
+    let header_max_y = column_positions.iter().map(|c| c.y2).max().unwrap_or(0) + 1;
+    let (Some((range_start_y, _)), Some((range_end_y, _))) = (range.start(), range.end()) else {
+        return Ok(excel_data);
+    };
+    let first_data_row = std::cmp::max(header_max_y, range_start_y);
+    if first_data_row > range_end_y {
+        return Ok(excel_data);
+    }
+    let positions_map = column_positions
+        .iter()
+        .map(|position| (position.key.as_str(), position))
+        .collect::<HashMap<&str, &ExcelColumnPosition>>();
+    let grouped_range = GroupedRange {
+        range: &range,
+        merged_row_ends: &merged_row_ends,
+        positions_map: &positions_map,
+        skipped_rows: &prepared.skipped_rows,
+    };
+    excel_data.rows = get_grouped_rows_data(&info, &grouped_range, "", first_data_row, range_end_y);
+    for row in excel_data.rows.iter_mut() {
+        row.sheet_name = Some(sheet_name.clone());
+    }
+    apply_import_value_options(&info, get_number_import_mode(&info)?, &mut excel_data.rows);
+    Ok(excel_data)
+}
+
+struct GroupedRange<'a> {
+    range: &'a calamine::Range<Data>,
+    merged_row_ends: &'a HashMap<(u32, u32), u32>,
+    positions_map: &'a HashMap<&'a str, &'a ExcelColumnPosition>,
+    skipped_rows: &'a [u32],
+}
+
+fn get_grouped_rows_data(
+    info: &ExcelInfo,
+    grouped_range: &GroupedRange,
+    data_group: &str,
+    first_row: u32,
+    last_row: u32,
+) -> Vec<ExcelRowData> {
+    let columns = info
+        .columns
+        .iter()
+        .filter(|column| column.data_group_parent == data_group)
+        .filter_map(|column| {
+            grouped_range
+                .positions_map
+                .get(column.key.as_str())
+                .map(|position| (column, *position))
+        })
+        .filter(|(column, position)| position.is_leaf || !column.data_group.is_empty())
+        .collect::<Vec<(&ExcelColumnInfo, &ExcelColumnPosition)>>();
+
+    let mut rows = Vec::new();
+    let mut y = first_row;
+    while y <= last_row {
+        if grouped_range.skipped_rows.contains(&y) {
+            y += 1;
+            continue;
+        }
+        let row_end = columns
+            .iter()
+            .filter(|(_, position)| position.is_leaf)
+            .filter_map(|(_, position)| {
+                grouped_range
+                    .merged_row_ends
+                    .get(&(y, position.x1 as u32))
+                    .copied()
+            })
+            .max()
+            .unwrap_or(y)
+            .min(last_row);
+        let row_columns = columns
+            .iter()
+            .map(|(column, position)| {
+                let cell = if position.is_leaf {
+                    grouped_range.range.get_value((y, position.x1 as u32))
+                } else {
+                    None
+                };
+                let children = if column.data_group.is_empty() {
+                    Vec::new()
+                } else {
+                    get_grouped_rows_data(info, grouped_range, &column.data_group, y, row_end)
+                };
+                let precision = get_date_precision(Some(column));
+                create_column_data(&column.key, cell, &position.data_type, precision)
+                    .with_children(children)
+            })
+            .collect();
+        rows.push(ExcelRowData::new(row_columns));
+        y = row_end + 1;
+    }
+    rows
+}
+
+/// Reads rows and collects per-cell validation issues instead of failing.
+pub fn import_data_with_report_buffer(
+    info: ExcelInfo,
+    excel_bytes: &[u8],
+) -> Result<ExcelValidationReport, Box<dyn std::error::Error>> {
+    let mut workbook = open_import_workbook(excel_bytes, info.import_format.as_deref())?;
+    let sheet_name = resolve_import_sheet_name(&workbook, &info)?;
+    let mut range = workbook.worksheet_range(sheet_name.as_str())?;
+    let bound_headers = bind_headers(&info, &range, &get_column_positions(&info), &sheet_name)?;
+    let prepared = prepare_import_range(
+        &info,
+        &mut workbook,
+        excel_bytes,
+        &sheet_name,
+        &mut range,
+        &bound_headers.positions,
+        CellCheck::Report,
+    )?;
+    let mut issues = bound_headers
+        .mismatches
+        .into_iter()
+        .map(|mismatch| {
+            ExcelValidationIssue::new(
+                sheet_name.clone(),
+                get_excel_cell_ref(mismatch.position.x1, mismatch.position.y1),
+                mismatch.position.y1 + 1,
+                mismatch.position.key.clone(),
+                mismatch.code.to_string(),
+                mismatch.actual_header,
+            )
         })
-        .collect()
+        .collect::<Vec<ExcelValidationIssue>>();
+    issues.extend(prepared.issues);
+    remove_range_rows(&mut range, &prepared.skipped_rows);
+    let mut data = ExcelData {
+        rows: get_rows_data(&info, &bound_headers.positions, &range, &sheet_name),
+        column_bindings: bound_headers.bindings,
+    };
+    apply_import_value_options(&info, get_number_import_mode(&info)?, &mut data.rows);
+    Ok(ExcelValidationReport { data, issues })
 }
 
-fn import_data_buffer(
+/// Reads every sheet whose headers match the schema and tags rows with the sheet name.
+pub fn import_all_sheets_data_buffer(
     info: ExcelInfo,
     excel_bytes: &[u8],
 ) -> Result<ExcelData, Box<dyn std::error::Error>> {
-    let cursor = Cursor::new(excel_bytes);
-    let mut workbook: Xlsx<_> = open_workbook_from_rs(cursor)?;
-    let mut excel_data = ExcelData { rows: Vec::new() };
-    let sheet_name = resolve_sheet_name(&workbook, None)?;
-    let range = workbook.worksheet_range(sheet_name.as_str())?;
+    let mut workbook = open_import_workbook(excel_bytes, info.import_format.as_deref())?;
+    let mut excel_data = ExcelData::new(Vec::new());
     let column_positions = get_column_positions(&info);
-    validate_headers(&info, &range, &column_positions, sheet_name.as_str())?;
-    excel_data.rows = get_rows_data(&column_positions, &range);
+    let sheet_names = workbook.sheet_names();
+    let mut has_matching_sheet = false;
+    for sheet_name in sheet_names.iter() {
+        let mut range = workbook.worksheet_range(sheet_name.as_str())?;
+        let Ok(bound_headers) =
+            validate_headers(&info, &range, &column_positions, sheet_name.as_str())
+        else {
+            continue;
+        };
+        let prepared = prepare_import_range(
+            &info,
+            &mut workbook,
+            excel_bytes,
+            sheet_name,
+            &mut range,
+            &bound_headers.positions,
+            CellCheck::Strict,
+        )?;
+        remove_range_rows(&mut range, &prepared.skipped_rows);
+        has_matching_sheet = true;
+        excel_data.rows.extend(get_rows_data(
+            &info,
+            &bound_headers.positions,
+            &range,
+            sheet_name,
+        ));
+        excel_data.column_bindings.extend(bound_headers.bindings);
+    }
+    if !has_matching_sheet {
+        return Err(create_structured_error(
+            "No worksheet in the workbook matches the expected headers",
+            "IMPORT_NO_MATCHING_SHEETS",
+            &[("sheetNames", sheet_names.join(", "))],
+        )
+        .into());
+    }
+    apply_import_value_options(&info, get_number_import_mode(&info)?, &mut excel_data.rows);
     Ok(excel_data)
 }
 
-fn import_dynamic_data_buffer(
+/// Reads a sheet without a schema, using the header row as column keys.
+pub fn import_dynamic_data_buffer(
     sheet_name: Option<String>,
     header_row: Option<u32>,
     excel_bytes: &[u8],
+    format: Option<&str>,
 ) -> Result<DynamicExcelData, Box<dyn std::error::Error>> {
-    let cursor = Cursor::new(excel_bytes);
-    let mut workbook: Xlsx<_> = open_workbook_from_rs(cursor)?;
+    let mut workbook = open_import_workbook(excel_bytes, format)?;
     let sheet_name = resolve_sheet_name(&workbook, sheet_name.as_deref())?;
     let range = workbook.worksheet_range(sheet_name.as_str())?;
     let header_row = resolve_dynamic_header_row(&range, header_row)?;
     let headers = get_dynamic_headers(&range, header_row)?;
-    let rows = get_dynamic_rows_data(&range, header_row, &headers);
+    let rows = get_dynamic_rows_data(&range, header_row, &headers, &sheet_name);
 
     Ok(DynamicExcelData {
         sheet_name,
@@ -596,37 +2054,131 @@ fn import_dynamic_data_buffer(
     })
 }
 
-async fn export_data_buffer(
+/// Writes the template headers followed by `data` and returns the xlsx bytes.
+pub async fn export_data_buffer(
     info: &ExcelInfo,
     data: &ExcelData,
 ) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
-    let (mut workbook, column_positions) = create_template_workbook(&info)?;
+    let (mut workbook, column_positions) = create_template_workbook(info)?;
+    let worksheet = workbook.worksheet_from_index(0)?;
+    let total_rows = data.rows.len();
+    write_sheet_data(worksheet, &column_positions, info, data, &|row_index| {
+        report_progress(
+            info.progress_callback.as_ref(),
+            (row_index as f64) / (total_rows as f64),
+        )
+    })
+    .await?;
+    report_progress(info.progress_callback.as_ref(), 1.0);
+
+    Ok(workbook.save_to_buffer()?)
+}
+
+/// Writes one sheet per entry of the workbook and returns the xlsx bytes.
+pub async fn export_workbook_buffer(
+    excel_workbook: &ExcelWorkbook,
+) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let Some((first_info, _)) = excel_workbook.sheets.first() else {
+        return Err(create_structured_error(
+            "Workbook export requires at least one sheet",
+            "WORKBOOK_NO_SHEETS",
+            &[],
+        )
+        .into());
+    };
+    let mut sheet_names = std::collections::HashSet::new();
+    let mut table_names = std::collections::HashSet::new();
+    for (info, _) in excel_workbook.sheets.iter() {
+        let table_name = info.table.as_ref().and_then(|table| table.name.as_deref());
+        if let Some(table_name) = table_name {
+            if !table_names.insert(table_name.to_lowercase()) {
+                return Err(create_structured_error(
+                    format!(
+                        "Workbook export requires unique table names. Duplicate table '{}' found.",
+                        table_name
+                    ),
+                    "WORKBOOK_DUPLICATE_TABLE_NAME",
+                    &[("tableName", table_name.to_string())],
+                )
+                .into());
+            }
+        }
+        if !sheet_names.insert(info.sheet_name.to_lowercase()) {
+            return Err(create_structured_error(
+                format!(
+                    "Workbook export requires unique sheet names. Duplicate sheet '{}' found.",
+                    info.sheet_name
+                ),
+                "WORKBOOK_DUPLICATE_SHEET_NAME",
+                &[("sheetName", info.sheet_name.clone())],
+            )
+            .into());
+        }
+    }
+
+    let mut workbook = Workbook::new();
+    let total_rows = excel_workbook.get_total_rows();
+    let mut written_rows = 0;
+    for (info, data) in excel_workbook.sheets.iter() {
+        let worksheet = workbook.add_worksheet();
+        let column_positions = write_template_sheet(worksheet, info)?;
+        write_sheet_data(worksheet, &column_positions, info, data, &|row_index| {
+            report_progress(
+                excel_workbook.progress_callback.as_ref(),
+                ((written_rows + row_index) as f64) / (total_rows as f64),
+            )
+        })
+        .await?;
+        written_rows += data.rows.len();
+    }
+    report_progress(excel_workbook.progress_callback.as_ref(), 1.0);
+    set_workbook_properties(&mut workbook, first_info)?;
+
+    Ok(workbook.save_to_buffer()?)
+}
+
+fn report_progress(reporter: Option<&Arc<dyn ProgressReporter>>, progress: f64) {
+    if let Some(reporter) = reporter {
+        reporter.report(progress);
+    }
+}
+
+async fn write_sheet_data<F: Fn(usize)>(
+    worksheet: &mut Worksheet,
+    column_positions: &[ExcelColumnPosition],
+    info: &ExcelInfo,
+    data: &ExcelData,
+    on_row: &F,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let formula_columns = get_formula_columns(info, column_positions)?;
     let mut column_positions_map: HashMap<String, (&ExcelColumnPosition, &ExcelColumnInfo)> =
         HashMap::new();
-    for column_position in &column_positions {
+    for column_position in column_positions {
         let column = find_column(info, &column_position.key)?;
+        // Formula columns ignore row values and are written by write_formula_cells.
+        if column.formula.is_some() {
+            continue;
+        }
         column_positions_map.insert(column_position.key.clone(), (column_position, column));
     }
 
     let y_min = column_positions.iter().map(|p| p.y2).max().unwrap_or(0) + 1;
     let mut y = y_min;
-    let mut worksheet = workbook.worksheet_from_name(&info.sheet_name)?;
     let root_group_keys = column_positions_map
         .iter()
         .filter(|(_, (_, info))| info.is_root_group())
         .map(|(key, _)| key)
         .collect::<Vec<&String>>();
 
-    let total_rows = data.rows.len();
+    info.check_sheet_options().map_err(|reason| {
+        create_structured_error(&reason, "INVALID_DEFINITION", &[("reason", reason.clone())])
+    })?;
+    check_summary_labels(info, &data.rows)?;
 
+    let mut data_rows: Vec<(u32, u32)> = Vec::new();
     for (row_index, row) in data.rows.iter().enumerate() {
-        if let Some(callback) = &info.progress_callback {
-            let progress = (row_index as f64) / (total_rows as f64);
-            let _ = callback.call1(
-                &wasm_bindgen::JsValue::NULL,
-                &wasm_bindgen::JsValue::from_f64(progress),
-            );
-        }
+        on_row(row_index);
+        let row_formats = get_row_formats(info, row);
 
         let mut data_with_children = Vec::new();
         let mut data_without_children = Vec::new();
@@ -638,80 +2190,833 @@ async fn export_data_buffer(
             }
         }
 
-        let next_y = write_children_row(
-            worksheet,
-            data_with_children,
-            y,
-            &column_positions_map,
-            &info,
-        )
-        .await?; // Add .await here
-        let y2 = next_y - 1;
-        let has_children = y2 > y;
+        let next_y = write_children_row(
+            worksheet,
+            data_with_children,
+            y,
+            &column_positions_map,
+            &formula_columns,
+            info,
+            &row_formats,
+        )
+        .await?; // Add .await here
+        let y2 = next_y - 1;
+        let has_children = y2 > y;
+        match data_rows.last_mut() {
+            Some((_, last_row)) if *last_row + 1 == y => *last_row = y2,
+            _ => data_rows.push((y, y2)),
+        }
+
+        for column_data in data_without_children.iter() {
+            if let Some((pos, column)) = column_positions_map.get(&column_data.key) {
+                let cell = CellContext {
+                    link_text: get_link_text(row.columns.iter(), column),
+                    row_formats: &row_formats,
+                };
+                if has_children {
+                    write_range_cell(worksheet, pos, y, y2, column_data, column, cell)?;
+                } else {
+                    write_single_cell(worksheet, pos.x1, y2, column_data, column, info, cell)
+                        .await?;
+                    // Add .await here
+                }
+            }
+        }
+        write_formula_cells(worksheet, &formula_columns, "", y, y2, &row_formats)?;
+        if !row_formats.is_empty() {
+            let row_columns = row.columns.iter().collect::<Vec<_>>();
+            write_row_format_blanks(
+                worksheet,
+                &column_positions_map,
+                &row_columns,
+                "",
+                (y, y2),
+                &row_formats,
+            )?;
+        }
+        let has_child_rows = row.columns.iter().any(|column_data| {
+            root_group_keys.contains(&&column_data.key) && !column_data.children.is_empty()
+        });
+        let subtotal_label = info
+            .summary
+            .as_ref()
+            .filter(|summary| summary.subtotals && has_child_rows)
+            .map(|summary| summary.get_subtotal_label());
+        if let Some(label) = subtotal_label {
+            let rows = std::slice::from_ref(row);
+            write_summary_row(
+                worksheet,
+                column_positions,
+                info,
+                label,
+                next_y,
+                (y, y2),
+                rows,
+            )?;
+            y = next_y + 1;
+        } else {
+            y = next_y;
+        }
+    }
+    add_conditional_formats(worksheet, column_positions, info, &data_rows)?;
+    // An export without rows still validates its first data row.
+    if data_rows.is_empty() {
+        data_rows.push((y_min, y_min));
+    }
+    add_data_validations(worksheet, column_positions, info, &data_rows)?;
+    add_table(worksheet, column_positions, info, y_min - 1, y - 1)?;
+    if let Some(summary) = info.summary.as_ref().filter(|summary| summary.totals) {
+        if y > y_min {
+            let label = summary.get_label();
+            write_summary_row(
+                worksheet,
+                column_positions,
+                info,
+                label,
+                y,
+                (y_min, y - 1),
+                &data.rows,
+            )?;
+        }
+    }
+    fit_column_widths(worksheet, column_positions, info, &data.rows)?;
+
+    Ok(())
+}
+
+/// Fails when a value of the label column equals the subtotal label while
+/// subtotals are on. Imports tell a precomputed subtotal row from a data row
+/// only by its label and blank cells, see [`get_summary_rows`].
+fn check_summary_labels(
+    info: &ExcelInfo,
+    rows: &[ExcelRowData],
+) -> Result<(), Box<dyn std::error::Error>> {
+    let leaf_columns = info.get_leaf_columns();
+    let summary = info.summary.as_ref().filter(|summary| summary.subtotals);
+    let (Some(summary), Some(label_column)) = (summary, leaf_columns.first()) else {
+        return Ok(());
+    };
+    match find_summary_label_value(rows, &label_column.key, summary) {
+        Some(value) => Err(create_structured_error(
+            format!(
+                "Value '{}' of column '{}' is reserved for summary rows",
+                value, label_column.key
+            ),
+            "EXPORT_SUMMARY_LABEL_RESERVED",
+            &[
+                ("value", value.to_string()),
+                ("columnKey", label_column.key.clone()),
+            ],
+        )
+        .into()),
+        None => Ok(()),
+    }
+}
+
+fn find_summary_label_value<'a>(
+    rows: &'a [ExcelRowData],
+    key: &str,
+    summary: &ExcelSummaryOptions,
+) -> Option<&'a str> {
+    rows.iter()
+        .flat_map(|row| row.columns.iter())
+        .find_map(|column_data| {
+            if column_data.key == key && summary.is_subtotal_label(column_data.value()) {
+                Some(column_data.value())
+            } else {
+                find_summary_label_value(&column_data.children, key, summary)
+            }
+        })
+}
+
+/// The width of a leaf column before any row is written: fitted to the
+/// header text under `autoWidth`, or the column `width`.
+fn get_header_width(info: &ExcelInfo, column: &ExcelColumnInfo) -> f64 {
+    match info.get_auto_width(column) {
+        Some(auto_width) => auto_width.fit(get_display_width(&column.name)),
+        None => column.width,
+    }
+}
+
+/// Widens the auto-width leaf columns to the widest of their header and
+/// the texts written for `rows`, including child rows.
+fn fit_column_widths(
+    worksheet: &mut Worksheet,
+    column_positions: &[ExcelColumnPosition],
+    info: &ExcelInfo,
+    rows: &[ExcelRowData],
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut fitted_columns = Vec::new();
+    for position in column_positions.iter().filter(|position| position.is_leaf) {
+        let column = find_column(info, &position.key)?;
+        if let Some(auto_width) = info.get_auto_width(column) {
+            fitted_columns.push((position.x1, column, auto_width));
+        }
+    }
+    if fitted_columns.is_empty() {
+        return Ok(());
+    }
+    let mut text_widths = HashMap::new();
+    collect_text_widths(info, rows, &mut text_widths);
+    for (x, column, auto_width) in fitted_columns {
+        let text_width = text_widths.get(column.key.as_str()).copied();
+        let display_width = get_display_width(&column.name).max(text_width.unwrap_or(0));
+        worksheet.set_column_width(x, auto_width.fit(display_width))?;
+    }
+    Ok(())
+}
+
+fn collect_text_widths<'a>(
+    info: &'a ExcelInfo,
+    rows: &[ExcelRowData],
+    text_widths: &mut HashMap<&'a str, usize>,
+) {
+    for row in rows {
+        for column_data in row.columns.iter() {
+            collect_text_widths(info, &column_data.children, text_widths);
+            let Some(column) = info.columns.iter().find(|c| c.key == column_data.key) else {
+                continue;
+            };
+            let link_text = get_link_text(row.columns.iter(), column);
+            let Some(text) = get_cell_text(column_data, column, link_text) else {
+                continue;
+            };
+            let width = text_widths.entry(column.key.as_str()).or_default();
+            *width = (*width).max(get_display_width(&text));
+        }
+    }
+}
+
+/// The text a written cell shows: the link text, the boolean label or the
+/// value. Dates return their Excel number format, which is about as wide as
+/// the dates it shows. Image and formula cells return `None`.
+fn get_cell_text(
+    column_data: &ExcelColumnData,
+    column: &ExcelColumnInfo,
+    link_text: Option<&str>,
+) -> Option<String> {
+    if column.formula.is_some() || column.data_type.eq_ignore_ascii_case("image") {
+        return None;
+    }
+    if let Ok(Some((_, text))) = get_export_link(column_data, column, link_text) {
+        return Some(text);
+    }
+    if let Ok(Some(boolean)) = get_export_boolean(column_data, column) {
+        let text = match column.get_boolean_labels() {
+            Some((true_label, false_label)) => {
+                if boolean {
+                    true_label
+                } else {
+                    false_label
+                }
+            }
+            None if boolean => "TRUE",
+            None => "FALSE",
+        };
+        return Some(text.into());
+    }
+    if let Ok(Some(_)) = get_export_date(column_data, column) {
+        return Some(get_date_cell_pattern(column).unwrap_or_else(|| "yyyy-mm-dd".into()));
+    }
+    Some(column_data.value().to_string())
+}
+
+/// Writes a totals or subtotal row at `y`: the label in the first leaf
+/// column and each aggregate over rows `y1` to `y2`, as a `SUBTOTAL` formula
+/// or, with `precomputed`, as the value computed from `rows`.
+fn write_summary_row(
+    worksheet: &mut Worksheet,
+    column_positions: &[ExcelColumnPosition],
+    info: &ExcelInfo,
+    label: &str,
+    y: u32,
+    (y1, y2): (u32, u32),
+    rows: &[ExcelRowData],
+) -> Result<(), Box<dyn std::error::Error>> {
+    let Some(summary) = info.summary.as_ref() else {
+        return Ok(());
+    };
+    let summary_format = summary.format.as_ref().map(get_cell_format);
+    let row_format = summary_format.as_ref().unwrap_or(&DEFAULT_SUMMARY_FORMAT);
+    let label_column = info
+        .get_leaf_columns()
+        .first()
+        .map(|column| column.key.as_str());
+    for position in column_positions.iter().filter(|position| position.is_leaf) {
+        let x = position.x1;
+        if label_column == Some(position.key.as_str()) {
+            worksheet.write_string_with_format(y, x, label, row_format)?;
+            continue;
+        }
+        let aggregate = summary
+            .aggregates
+            .iter()
+            .find(|aggregate| aggregate.column_key == position.key);
+        let Some(aggregate) = aggregate else {
+            worksheet.write_blank(y, x, row_format)?;
+            continue;
+        };
+        let column = find_column(info, &position.key)?;
+        let aggregate_format = aggregate.format.as_ref().map(get_cell_format);
+        let base_format = aggregate_format.as_ref().unwrap_or(row_format);
+        let number_format =
+            get_number_cell_format(base_format, column).filter(|_| aggregate.function != "count");
+        let f = number_format.as_ref().unwrap_or(base_format);
+        let value = if column.formula.is_some() {
+            None
+        } else {
+            let mut values = Vec::new();
+            collect_column_values(rows, &column.key, &mut values);
+            aggregate.compute(values)
+        };
+        if summary.precomputed {
+            match value {
+                Some(value) => worksheet.write_number_with_format(y, x, value, f)?,
+                None => worksheet.write_blank(y, x, f)?,
+            };
+            continue;
+        }
+        let mut formula = Formula::new(format!(
+            "SUBTOTAL({},{}:{})",
+            aggregate.get_subtotal_number(),
+            get_excel_cell_ref(x, y1),
+            get_excel_cell_ref(x, y2)
+        ));
+        if let Some(value) = value {
+            formula = formula.set_result(value.to_string());
+        }
+        worksheet.write_formula_with_format(y, x, formula, f)?;
+    }
+    Ok(())
+}
+
+/// The values of column `key` in `rows` and their child rows.
+fn collect_column_values<'a>(rows: &'a [ExcelRowData], key: &str, values: &mut Vec<&'a str>) {
+    for row in rows {
+        for column_data in row.columns.iter() {
+            if column_data.key == key {
+                values.push(column_data.value());
+            }
+            collect_column_values(&column_data.children, key, values);
+        }
+    }
+}
+
+/// The row-level inputs shared by the cells of one written row.
+#[derive(Clone, Copy)]
+struct CellContext<'a> {
+    link_text: Option<&'a str>,
+    row_formats: &'a [&'a ExcelRowFormat],
+}
+
+/// The row formats whose rule matches the values of `row`, in schema order.
+fn get_row_formats<'a>(info: &'a ExcelInfo, row: &ExcelRowData) -> Vec<&'a ExcelRowFormat> {
+    info.row_formats
+        .iter()
+        .filter(|row_format| {
+            let value = row
+                .columns
+                .iter()
+                .find(|column_data| column_data.key == row_format.column_key)
+                .map(|column_data| column_data.value());
+            row_format.matches(value.unwrap_or_default())
+        })
+        .collect()
+}
+
+/// Fills the columns a formatted row carries no value for with formatted
+/// blanks, so the format spans the whole row. `data_group` is the group of
+/// `row`; columns of nested groups are only filled when the row has no
+/// child rows for them.
+fn write_row_format_blanks(
+    worksheet: &mut Worksheet,
+    column_positions_map: &HashMap<String, (&ExcelColumnPosition, &ExcelColumnInfo)>,
+    row: &[&ExcelColumnData],
+    data_group: &str,
+    (y1, y2): (u32, u32),
+    row_formats: &[&ExcelRowFormat],
+) -> Result<(), Box<dyn std::error::Error>> {
+    let keys: HashSet<&str> = row
+        .iter()
+        .map(|column_data| column_data.key.as_str())
+        .collect();
+    let filled_groups: HashSet<&str> = row
+        .iter()
+        .filter(|column_data| !column_data.children.is_empty())
+        .map(|column_data| column_data.key.as_str())
+        .collect();
+    // Each group with its parent group and the key of the column holding it.
+    let groups: HashMap<&str, (&str, &str)> = column_positions_map
+        .values()
+        .filter(|(_, column)| !column.data_group.is_empty())
+        .map(|(_, column)| {
+            let parent = column.data_group_parent.as_str();
+            (column.data_group.as_str(), (parent, column.key.as_str()))
+        })
+        .collect();
+    for (pos, column) in column_positions_map.values() {
+        if !pos.is_leaf || !column.data_group.is_empty() {
+            continue;
+        }
+        let mut group = column.data_group_parent.as_str();
+        let is_missing = loop {
+            if group == data_group {
+                break !keys.contains(column.key.as_str());
+            }
+            match groups.get(group) {
+                Some((parent, _)) if *parent != data_group => group = parent,
+                Some((_, group_key)) => break !filled_groups.contains(group_key),
+                None => break false,
+            }
+        };
+        if !is_missing {
+            continue;
+        }
+        if let Some(format) = get_column_value_format("", column, row_formats) {
+            for y in y1..=y2 {
+                worksheet.write_blank(y, pos.x1, &format)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Writes the `table` over the leaf header row and the data rows, or only an
+/// autofilter when `autofilter` is set without a table.
+fn add_table(
+    worksheet: &mut Worksheet,
+    column_positions: &[ExcelColumnPosition],
+    info: &ExcelInfo,
+    header_row: u32,
+    last_row: u32,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut leaf_positions = column_positions
+        .iter()
+        .filter(|p| p.is_leaf)
+        .collect::<Vec<_>>();
+    leaf_positions.sort_by_key(|position| position.x1);
+    let (Some(first), Some(last)) = (leaf_positions.first(), leaf_positions.last()) else {
+        return Ok(());
+    };
+    let (first_col, last_col) = (first.x1, last.x1);
+    let Some(options) = info.table.as_ref() else {
+        if info.autofilter {
+            worksheet.autofilter(header_row, first_col, last_row, last_col)?;
+        }
+        return Ok(());
+    };
+    let has_totals_row = options.has_totals_row();
+    let mut columns = Vec::new();
+    for position in leaf_positions.iter() {
+        let column = find_column(info, &position.key)?;
+        let header_format = column.format.as_ref().map(get_cell_format);
+        let mut table_column = TableColumn::new()
+            .set_header(column.name.as_str())
+            .set_header_format(header_format.unwrap_or_else(|| DEFAULT_HEADER_FORMAT.clone()));
+        if has_totals_row && columns.is_empty() {
+            table_column = table_column.set_total_label(options.get_total_label());
+        }
+        let total = options
+            .totals
+            .iter()
+            .find(|total| total.column_key == column.key);
+        if let Some(total) = total {
+            table_column = table_column.set_total_function(get_table_function(&total.function));
+        }
+        columns.push(table_column);
+    }
+    let mut table = Table::new()
+        .set_columns(&columns)
+        .set_banded_rows(options.banded_rows)
+        .set_banded_columns(options.banded_columns)
+        .set_total_row(has_totals_row);
+    if let Some(name) = options.name.as_deref() {
+        table = table.set_name(name);
+    }
+    if let Some(style) = options.get_style() {
+        table = table.set_style(style);
+    }
+    // Excel tables need a data row, so exports without rows get a blank one.
+    let last_data_row = last_row.max(header_row + 1);
+    let last_row = last_data_row + u32::from(has_totals_row);
+    worksheet.add_table(header_row, first_col, last_row, last_col, &table)?;
+    Ok(())
+}
+
+fn get_table_function(function: &str) -> TableFunction {
+    match function {
+        "average" => TableFunction::Average,
+        "count" => TableFunction::Count,
+        "countNumbers" => TableFunction::CountNumbers,
+        "max" => TableFunction::Max,
+        "min" => TableFunction::Min,
+        "stdDev" => TableFunction::StdDev,
+        "var" => TableFunction::Var,
+        _ => TableFunction::Sum,
+    }
+}
+
+/// Finds the totals and subtotal rows `summary` wrote. A row with the totals
+/// or subtotal label in the first leaf column is a summary row when one of its
+/// aggregate cells holds a `SUBTOTAL` formula in `formulas`. Precomputed rows
+/// have no formula: the totals row is the `last_row` of the sheet, and a
+/// subtotal row holds nothing but its label and aggregates.
+fn get_summary_rows(
+    info: &ExcelInfo,
+    range: &calamine::Range<Data>,
+    column_positions: &[ExcelColumnPosition],
+    formulas: Option<&calamine::Range<String>>,
+    last_row: Option<u32>,
+) -> Vec<u32> {
+    let mut summary_rows = Vec::new();
+    let Some(summary) = get_import_summary(info) else {
+        return summary_rows;
+    };
+    let label_position = info
+        .get_leaf_columns()
+        .first()
+        .and_then(|column| column_positions.iter().find(|p| p.key == column.key));
+    let (Some(label_position), Some((first_data_row, range_end_y))) =
+        (label_position, get_data_row_span(column_positions, range))
+    else {
+        return summary_rows;
+    };
+    let aggregate_xs = column_positions
+        .iter()
+        .filter(|position| {
+            summary
+                .aggregates
+                .iter()
+                .any(|aggregate| aggregate.column_key == position.key)
+        })
+        .map(|position| u32::from(position.x1))
+        .collect::<Vec<u32>>();
+    let other_xs = column_positions
+        .iter()
+        .filter(|position| position.is_leaf && position.x1 != label_position.x1)
+        .map(|position| u32::from(position.x1))
+        .filter(|x| !aggregate_xs.contains(x))
+        .collect::<Vec<u32>>();
+    let has_subtotal_formula = |y: u32| {
+        formulas.is_some_and(|formulas| {
+            aggregate_xs.iter().any(|x| {
+                formulas
+                    .get_value((y, *x))
+                    .is_some_and(|formula| formula.to_uppercase().starts_with("SUBTOTAL("))
+            })
+        })
+    };
+    let is_blank = |y: u32, x: u32| {
+        range
+            .get_value((y, x))
+            .is_none_or(|value| value.to_string().trim().is_empty())
+    };
+    for y in first_data_row..=range_end_y {
+        let Some(label) = range.get_value((y, u32::from(label_position.x1))) else {
+            continue;
+        };
+        let label = label.to_string();
+        let is_summary_row = if summary.is_totals_label(&label) {
+            has_subtotal_formula(y) || (summary.precomputed && Some(y) == last_row)
+        } else if summary.is_subtotal_label(&label) {
+            has_subtotal_formula(y)
+                || (summary.precomputed && other_xs.iter().all(|x| is_blank(y, *x)))
+        } else {
+            false
+        };
+        if is_summary_row {
+            summary_rows.push(y);
+        }
+    }
+    summary_rows
+}
+
+/// Clears every cell of `rows` so the cell checks skip them.
+fn blank_range_rows(range: &mut calamine::Range<Data>, rows: &[u32]) {
+    let (Some((_, start_x)), Some((_, end_x))) = (range.start(), range.end()) else {
+        return;
+    };
+    for y in rows.iter() {
+        for x in start_x..=end_x {
+            range.set_value((*y, x), Data::Empty);
+        }
+    }
+}
+
+/// Removes `rows` from `range`, moving the rows below them up.
+fn remove_range_rows(range: &mut calamine::Range<Data>, rows: &[u32]) {
+    let (Some(start), Some(end)) = (range.start(), range.end()) else {
+        return;
+    };
+    if rows.is_empty() {
+        return;
+    }
+    let mut compacted = calamine::Range::new(start, (end.0 - rows.len() as u32, end.1));
+    for (row, column, cell) in range.used_cells() {
+        let y = start.0 + row as u32;
+        if rows.contains(&y) {
+            continue;
+        }
+        let shift = rows.iter().filter(|removed| **removed < y).count() as u32;
+        compacted.set_value((y - shift, start.1 + column as u32), cell.clone());
+    }
+    *range = compacted;
+}
 
-        for (_, column_data) in data_without_children.iter().enumerate() {
-            if let Some((pos, column)) = column_positions_map.get(&column_data.key) {
-                if has_children {
-                    write_range_cell(
-                        worksheet,
-                        pos.x1,
-                        pos.x2,
-                        y,
-                        y2,
-                        &column_data.value,
-                        &column,
+/// Writes each column's conditional formats over its written data rows.
+/// `data_rows` lists the runs of data rows between the subtotal rows, which
+/// the formats leave out.
+fn add_conditional_formats(
+    worksheet: &mut Worksheet,
+    column_positions: &[ExcelColumnPosition],
+    info: &ExcelInfo,
+    data_rows: &[(u32, u32)],
+) -> Result<(), Box<dyn std::error::Error>> {
+    let (Some((first_row, _)), Some((_, last_row))) = (data_rows.first(), data_rows.last()) else {
+        return Ok(());
+    };
+    let (first_row, last_row) = (*first_row, *last_row);
+    for column_position in column_positions.iter().filter(|p| p.is_leaf) {
+        let column = find_column(info, &column_position.key)?;
+        let x = column_position.x1;
+        let cells = data_rows
+            .iter()
+            .map(|(y1, y2)| {
+                format!(
+                    "{}:{}",
+                    get_excel_cell_ref(x, *y1),
+                    get_excel_cell_ref(x, *y2)
+                )
+            })
+            .collect::<Vec<String>>()
+            .join(" ");
+        for conditional_format in column.conditional_formats.iter() {
+            let format = conditional_format
+                .format
+                .as_ref()
+                .map(get_conditional_cell_format)
+                .unwrap_or_default();
+            let value = conditional_format.value.as_str();
+            let end_value = conditional_format.end_value.as_deref().unwrap_or_default();
+            match conditional_format.rule.as_str() {
+                "contains" => {
+                    let rule = ConditionalFormatTextRule::Contains(value.to_string());
+                    let text = ConditionalFormatText::new()
+                        .set_rule(rule)
+                        .set_multi_range(cells.as_str());
+                    worksheet.add_conditional_format(
+                        first_row,
+                        x,
+                        last_row,
+                        x,
+                        &text.set_format(format),
+                    )?;
+                }
+                "regex" => {
+                    // Excel has no regex rule, so the pattern goes through REGEXTEST,
+                    // which only Excel for Microsoft 365 evaluates.
+                    let formula = format!(
+                        "=_xlfn.REGEXTEST({},\"{}\")",
+                        get_excel_cell_ref(x, first_row),
+                        value.replace('"', "\"\"")
+                    );
+                    let regex = ConditionalFormatFormula::new()
+                        .set_rule(formula.as_str())
+                        .set_multi_range(cells.as_str());
+                    worksheet.add_conditional_format(
+                        first_row,
+                        x,
+                        last_row,
+                        x,
+                        &regex.set_format(format),
+                    )?;
+                }
+                "blank" | "notBlank" => {
+                    let mut blank = ConditionalFormatBlank::new()
+                        .set_format(format)
+                        .set_multi_range(cells.as_str());
+                    if conditional_format.rule == "notBlank" {
+                        blank = blank.invert();
+                    }
+                    worksheet.add_conditional_format(first_row, x, last_row, x, &blank)?;
+                }
+                "colorScale" => match conditional_format.colors.as_slice() {
+                    [min, mid, max] => {
+                        let scale = ConditionalFormat3ColorScale::new()
+                            .set_multi_range(cells.as_str())
+                            .set_minimum_color(min.as_str())
+                            .set_midpoint_color(mid.as_str())
+                            .set_maximum_color(max.as_str());
+                        worksheet.add_conditional_format(first_row, x, last_row, x, &scale)?;
+                    }
+                    [min, max] => {
+                        let scale = ConditionalFormat2ColorScale::new()
+                            .set_multi_range(cells.as_str())
+                            .set_minimum_color(min.as_str())
+                            .set_maximum_color(max.as_str());
+                        worksheet.add_conditional_format(first_row, x, last_row, x, &scale)?;
+                    }
+                    _ => {
+                        let scale =
+                            ConditionalFormat3ColorScale::new().set_multi_range(cells.as_str());
+                        worksheet.add_conditional_format(first_row, x, last_row, x, &scale)?;
+                    }
+                },
+                "dataBar" => {
+                    let mut bar = ConditionalFormatDataBar::new().set_multi_range(cells.as_str());
+                    if let Some(color) = conditional_format.colors.first() {
+                        bar = bar.set_fill_color(color.as_str());
+                    }
+                    worksheet.add_conditional_format(first_row, x, last_row, x, &bar)?;
+                }
+                rule => {
+                    let value = get_conditional_value(value);
+                    let end_value = get_conditional_value(end_value);
+                    let rule = match rule {
+                        "ne" => ConditionalFormatCellRule::NotEqualTo(value),
+                        "gt" => ConditionalFormatCellRule::GreaterThan(value),
+                        "gte" => ConditionalFormatCellRule::GreaterThanOrEqualTo(value),
+                        "lt" => ConditionalFormatCellRule::LessThan(value),
+                        "lte" => ConditionalFormatCellRule::LessThanOrEqualTo(value),
+                        "between" => ConditionalFormatCellRule::Between(value, end_value),
+                        "notBetween" => ConditionalFormatCellRule::NotBetween(value, end_value),
+                        _ => ConditionalFormatCellRule::EqualTo(value),
+                    };
+                    let cell = ConditionalFormatCell::new()
+                        .set_rule(rule)
+                        .set_multi_range(cells.as_str());
+                    worksheet.add_conditional_format(
+                        first_row,
+                        x,
+                        last_row,
+                        x,
+                        &cell.set_format(format),
                     )?;
-                } else {
-                    write_single_cell(worksheet, pos.x1, y2, &column_data.value, &column, &info)
-                        .await?; // Add .await here
                 }
             }
         }
-        y = next_y;
     }
+    Ok(())
+}
 
-    if let Some(callback) = &info.progress_callback {
-        let _ = callback.call1(
-            &wasm_bindgen::JsValue::NULL,
-            &wasm_bindgen::JsValue::from_f64(1.0),
-        );
+/// Compares numbers as numbers and anything else as quoted text.
+fn get_conditional_value(value: &str) -> ConditionalFormatValue {
+    match value.trim().parse::<f64>() {
+        Ok(number) if number.is_finite() => number.into(),
+        _ => value.into(),
     }
+}
 
-    for (_, (pos, info)) in &column_positions_map {
-        if info.allowed_values.is_empty() || !pos.is_leaf {
-            continue;
-        }
-        add_data_validation(&mut worksheet, info, pos.x1, y_min, y)?;
+/// The dropdown entries of a `boolean` column: its labels or TRUE/FALSE.
+fn get_boolean_options(column: &ExcelColumnInfo) -> [&str; 2] {
+    match column.get_boolean_labels() {
+        Some((true_label, false_label)) => [true_label, false_label],
+        None => ["TRUE", "FALSE"],
     }
+}
 
-    Ok(workbook.save_to_buffer()?)
+/// The dropdown entries of a column: the boolean options when
+/// `booleanDropdown` is set, otherwise its `allowedValues`.
+fn get_list_options(column: &ExcelColumnInfo) -> Option<Vec<&str>> {
+    if column.boolean_dropdown && is_boolean_data_type(&column.data_type) {
+        Some(get_boolean_options(column).to_vec())
+    } else if !column.allowed_values.is_empty() {
+        Some(column.allowed_values.iter().map(String::as_str).collect())
+    } else {
+        None
+    }
 }
 
-fn add_data_validation(
-    worksheet: &mut Worksheet,
+/// The input and error messages of a column validation: the `constraints`
+/// messages, or one sentence per rule. Columns without rules return `None`.
+fn get_validation_messages(column: &ExcelColumnInfo) -> Option<(String, String)> {
+    let constraints = column.constraints.as_ref();
+    let mut rules = Vec::new();
+    if column.required {
+        rules.push("Required.".to_string());
+    }
+    rules.extend(
+        constraints
+            .map(ExcelConstraints::describe)
+            .unwrap_or_default(),
+    );
+    let description: String = rules.join(" ").chars().take(255).collect();
+    let input_message = constraints
+        .and_then(|constraints| constraints.input_message.clone())
+        .unwrap_or_else(|| description.clone());
+    let error_message = constraints
+        .and_then(|constraints| constraints.error_message.clone())
+        .unwrap_or(description);
+    if input_message.is_empty() && error_message.is_empty() {
+        return None;
+    }
+    Some((input_message, error_message))
+}
+
+/// Excel's limit for validation titles is 32 characters.
+fn get_validation_title(column: &ExcelColumnInfo) -> String {
+    column.name.chars().take(32).collect()
+}
+
+/// The template validation of a column: its dropdown, or the number range or
+/// text length of its `constraints`. Regex patterns cannot be expressed in
+/// Excel, so they only show up in the messages.
+fn get_data_validation(
     column: &ExcelColumnInfo,
-    column_index: u16,
-    first_row: u32,
-    last_row: u32,
-) -> Result<(), Box<dyn std::error::Error>> {
-    let data_validation = DataValidation::new().allow_list_strings(&column.allowed_values)?;
-    worksheet.add_data_validation(
-        first_row,
-        column_index,
-        last_row,
-        column_index,
-        &data_validation,
-    )?;
-    Ok(())
+) -> Result<Option<DataValidation>, Box<dyn std::error::Error>> {
+    let constraints = column.constraints.as_ref();
+    let mut data_validation = if let Some(options) = get_list_options(column) {
+        DataValidation::new().allow_list_strings(&options)?
+    } else if let Some(constraints) = constraints.filter(|constraints| constraints.has_range()) {
+        let rule = match (constraints.min, constraints.max) {
+            (Some(min), Some(max)) => DataValidationRule::Between(min, max),
+            (Some(min), None) => DataValidationRule::GreaterThanOrEqualTo(min),
+            (None, max) => DataValidationRule::LessThanOrEqualTo(max.unwrap_or_default()),
+        };
+        DataValidation::new().allow_decimal_number(rule)
+    } else if let Some(constraints) = constraints.filter(|constraints| constraints.has_length()) {
+        let rule = match (constraints.min_length, constraints.max_length) {
+            (Some(min), Some(max)) => DataValidationRule::Between(min, max),
+            (Some(min), None) => DataValidationRule::GreaterThanOrEqualTo(min),
+            (None, max) => DataValidationRule::LessThanOrEqualTo(max.unwrap_or_default()),
+        };
+        DataValidation::new().allow_text_length(rule)
+    } else if column.required || constraints.is_some() {
+        DataValidation::new().allow_any_value()
+    } else {
+        return Ok(None);
+    };
+    if let Some((input_message, error_message)) = get_validation_messages(column) {
+        let title = get_validation_title(column);
+        if !input_message.is_empty() {
+            data_validation = data_validation
+                .set_input_title(title.clone())?
+                .set_input_message(input_message)?;
+        }
+        if !error_message.is_empty() {
+            data_validation = data_validation
+                .set_error_title(title)?
+                .set_error_message(error_message)?;
+        }
+    }
+    Ok(Some(data_validation.ignore_blank(!column.required)))
 }
 
-fn create_template_buffer(info: &ExcelInfo) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
-    let (mut workbook, _) = create_template_workbook(&info)?;
+/// Writes an empty template with headers and data validations.
+pub fn create_template_buffer(info: &ExcelInfo) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let (mut workbook, column_positions) = create_template_workbook(info)?;
+    let first_data_row = get_first_data_row(info, &column_positions);
+    add_data_validations(
+        workbook.worksheet_from_index(0)?,
+        &column_positions,
+        info,
+        &[(first_data_row, EXCEL_LAST_ROW)],
+    )?;
     let buffer = workbook.save_to_buffer()?;
     Ok(buffer)
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct ExcelColumnPosition {
     pub x1: u16,
     pub y1: u32,
@@ -731,7 +3036,7 @@ impl ExcelColumnPosition {
 fn validate_image_data(image_data: &[u8], url: &str) -> Result<Image, Box<dyn std::error::Error>> {
     if image_data.is_empty() {
         return Err(create_structured_error(
-            &format!("Image data is empty for URL: {}", url),
+            format!("Image data is empty for URL: {}", url),
             "IMAGE_FETCHER_EMPTY_DATA",
             &[("url", url.to_string())],
         )
@@ -739,7 +3044,7 @@ fn validate_image_data(image_data: &[u8], url: &str) -> Result<Image, Box<dyn st
     }
     let image = Image::new_from_buffer(image_data).map_err(|e| {
         create_structured_error(
-            &format!("Failed to parse image from URL {}: {}", url, e.to_string()),
+            format!("Failed to parse image from URL {}: {}", url, e),
             "IMAGE_PARSE_FAILED",
             &[("url", url.to_string()), ("reason", e.to_string())],
         )
@@ -751,48 +3056,44 @@ async fn write_single_cell(
     worksheet: &mut Worksheet,
     x: u16,
     y: u32,
-    value: &str,
+    column_data: &ExcelColumnData,
     column: &ExcelColumnInfo,
     info: &ExcelInfo,
+    cell: CellContext<'_>,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let data_type = &column.data_type;
+    let value = column_data.value();
     let trimmed_value = value.trim();
     let mut is_date_type = false;
-    if data_type.eq_ignore_ascii_case("image") {
+    let mut is_number_type = matches!(column_data.typed_value(), ExcelValue::Number(_));
+    // Resolved up front so no boxed error is held across the image fetch await,
+    // which would make the export future `!Send`.
+    if let Some((url, text)) = get_export_link(column_data, column, cell.link_text)? {
+        let value_format = get_column_value_format(value, column, cell.row_formats);
+        let f = value_format.as_ref().unwrap_or(&DEFAULT_LINK_FORMAT);
+        return write_link(worksheet, x, y, &url, &text, f);
+    }
+    let boolean = get_export_boolean(column_data, column)?;
+    let date_time = match boolean {
+        Some(_) => None,
+        None => get_export_date(column_data, column)?,
+    };
+    if let Some(boolean) = boolean {
+        write_boolean_value(worksheet, x, y, boolean, column)?;
+    } else if let Some(date_time) = date_time {
+        worksheet.write_number(y, x, date_time_to_excel(&date_time))?;
+        is_date_type = true;
+    } else if column_data.typed_value().is_native() {
+        is_date_type = write_typed_value(worksheet, x, y, column_data.typed_value())?;
+    } else if data_type.eq_ignore_ascii_case("image") {
         if let Some(fetcher) = &info.image_fetcher {
             let values: Vec<&str> = value.split(",").collect();
-            for v in values.iter() {
-                let url_value = JsValue::from_str(v);
-                let result = fetcher.call1(&JsValue::NULL, &url_value).map_err(|e| {
-                    create_structured_error(
-                        &format!("Failed to call image fetcher: {:?}", e),
-                        "IMAGE_FETCHER_CALL_FAILED",
-                        &[("reason", format!("{:?}", e))],
-                    )
-                })?;
-                let promise = js_sys::Promise::resolve(&result);
-                let result = JsFuture::from(promise).await.map_err(|e| {
-                    create_structured_error(
-                        &format!("Failed to wait for image fetcher promise: {:?}", e),
-                        "IMAGE_FETCHER_WAIT_FAILED",
-                        &[("reason", format!("{:?}", e))],
-                    )
-                })?;
+            if let Some(v) = values.first() {
+                let image_data = fetcher.fetch(v).await?;
+                let image = validate_image_data(&image_data, v)?;
 
-                if result.is_object() {
-                    let image_data: Vec<u8> = js_sys::Uint8Array::new(&result).to_vec();
-                    let image = validate_image_data(&image_data, v)?;
-
-                    worksheet.insert_image_fit_to_cell(y, x, &image, true)?;
-                    return Ok(());
-                } else {
-                    return Err(create_structured_error(
-                        &format!("Image fetcher returned invalid data for URL: {}", v),
-                        "IMAGE_FETCHER_INVALID_DATA",
-                        &[("url", v.to_string())],
-                    )
-                    .into());
-                }
+                worksheet.insert_image_fit_to_cell(y, x, &image, true)?;
+                return Ok(());
             }
         } else {
             return Err(create_structured_error(
@@ -806,53 +3107,243 @@ async fn write_single_cell(
         if trimmed_value.is_empty() {
             worksheet.write_string(y, x, "")?;
         } else {
-            let parsed_number = trimmed_value.parse::<f64>().map_err(|error| {
-                create_structured_error(
-                    &format!("Invalid number value '{}'", value),
-                    "EXPORT_NUMBER_VALUE_INVALID",
-                    &[
-                        ("value", value.to_string()),
-                        ("columnKey", column.key.clone()),
-                        ("reason", error.to_string()),
-                    ],
-                )
-            })?;
+            let parsed_number = parse_export_number(value, column)?;
             worksheet.write_number(y, x, parsed_number)?;
-        }
-    } else if data_type.eq_ignore_ascii_case("date") {
-        if trimmed_value.is_empty() {
-            worksheet.write_string(y, x, "")?;
-        } else {
-            let date_time = ExcelDateTime::parse_from_str(trimmed_value).map_err(|error| {
-                create_structured_error(
-                    &format!("Invalid date value '{}'", value),
-                    "EXPORT_DATE_VALUE_INVALID",
-                    &[
-                        ("value", value.to_string()),
-                        ("columnKey", column.key.clone()),
-                        ("reason", error.to_string()),
-                    ],
-                )
-            })?;
-            worksheet.write_datetime(y, x, date_time)?;
-            is_date_type = true;
+            is_number_type = true;
         }
     } else {
         worksheet.write_string(y, x, value)?;
     }
-    if let Some(f) = get_column_value_format(value, &column) {
-        worksheet.set_cell_format(y, x, &f)?;
+    let value_format = get_column_value_format(value, column, cell.row_formats);
+    let f = value_format.as_ref().unwrap_or(if is_date_type {
+        &DEFAULT_DATE_FORMAT
     } else {
-        worksheet.set_cell_format(
-            y,
-            x,
-            if is_date_type {
-                &DEFAULT_DATE_FORMAT
-            } else {
-                &DEFAULT_FORMAT
-            },
-        )?;
+        &DEFAULT_FORMAT
+    });
+    let cell_format = if is_date_type {
+        get_date_cell_format(f, column)
+    } else {
+        get_number_cell_format(f, column).filter(|_| is_number_type)
+    };
+    match cell_format {
+        Some(cell_format) => worksheet.set_cell_format(y, x, &cell_format)?,
+        None => worksheet.set_cell_format(y, x, f)?,
+    };
+    Ok(())
+}
+
+/// `format` with the column `numberFormat` applied, for cells holding numbers.
+fn get_number_cell_format(format: &Format, column: &ExcelColumnInfo) -> Option<Format> {
+    let number_format = column.number_format.as_ref()?;
+    Some(format.clone().set_num_format(number_format.get_pattern()))
+}
+
+fn parse_export_number(
+    value: &str,
+    column: &ExcelColumnInfo,
+) -> Result<f64, Box<dyn std::error::Error>> {
+    value.trim().parse::<f64>().map_err(|error| {
+        create_structured_error(
+            format!("Invalid number value '{}'", value),
+            "EXPORT_NUMBER_VALUE_INVALID",
+            &[
+                ("value", value.to_string()),
+                ("columnKey", column.key.clone()),
+                ("reason", error.to_string()),
+            ],
+        )
+        .into()
+    })
+}
+
+fn parse_export_date(
+    value: &str,
+    column: &ExcelColumnInfo,
+) -> Result<chrono::NaiveDateTime, Box<dyn std::error::Error>> {
+    parse_date_value(value, column).map_err(|reason| {
+        create_structured_error(
+            format!("Invalid date value '{}'", value),
+            "EXPORT_DATE_VALUE_INVALID",
+            &[
+                ("value", value.to_string()),
+                ("columnKey", column.key.clone()),
+                ("reason", reason),
+            ],
+        )
+        .into()
+    })
+}
+
+/// Returns the wall-clock time written to a date column cell, parsing text
+/// values and moving typed dates to the column timezone. Other columns,
+/// empty values and typed numbers return `None`.
+fn get_export_date(
+    column_data: &ExcelColumnData,
+    column: &ExcelColumnInfo,
+) -> Result<Option<chrono::NaiveDateTime>, Box<dyn std::error::Error>> {
+    let Some(kind) = get_date_kind(&column.data_type) else {
+        return Ok(None);
+    };
+    let date_time = match column_data.typed_value() {
+        ExcelValue::DateTime(date_time) => match get_column_offset(column) {
+            Some(offset) => date_time.and_utc().with_timezone(&offset).naive_local(),
+            None => *date_time,
+        },
+        ExcelValue::Text(_) if !column_data.value().trim().is_empty() => {
+            parse_export_date(column_data.value(), column)?
+        }
+        _ => return Ok(None),
+    };
+    Ok(Some(truncate_to_date_kind(date_time, kind)))
+}
+
+/// The Excel number format of a date column: the `excelPattern` of its
+/// `dateOptions`, or `hh:mm:ss` for clock times.
+fn get_date_cell_pattern(column: &ExcelColumnInfo) -> Option<String> {
+    let kind = get_date_kind(&column.data_type)?;
+    let excel_pattern = column
+        .date_options
+        .as_ref()
+        .and_then(|date_options| date_options.excel_pattern.clone());
+    match excel_pattern {
+        Some(excel_pattern) => Some(excel_pattern),
+        None if kind == DateKind::Time => Some("hh:mm:ss".into()),
+        None => None,
+    }
+}
+
+/// `format` with the column date pattern applied, for cells holding dates.
+fn get_date_cell_format(format: &Format, column: &ExcelColumnInfo) -> Option<Format> {
+    get_date_cell_pattern(column).map(|pattern| format.clone().set_num_format(pattern))
+}
+
+/// Returns the value of a `boolean` column cell, parsing text values.
+/// Other columns and empty values return `None`.
+fn get_export_boolean(
+    column_data: &ExcelColumnData,
+    column: &ExcelColumnInfo,
+) -> Result<Option<bool>, Box<dyn std::error::Error>> {
+    if !is_boolean_data_type(&column.data_type) {
+        return Ok(None);
+    }
+    match column_data.typed_value() {
+        ExcelValue::Bool(boolean) => Ok(Some(*boolean)),
+        ExcelValue::Number(_) | ExcelValue::DateTime(_) => Ok(None),
+        _ if column_data.value().trim().is_empty() => Ok(None),
+        _ => parse_export_boolean(column_data.value(), column).map(Some),
+    }
+}
+
+fn parse_export_boolean(
+    value: &str,
+    column: &ExcelColumnInfo,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    parse_boolean_text(value, column).ok_or_else(|| {
+        create_structured_error(
+            format!("Invalid boolean value '{}'", value),
+            "EXPORT_BOOLEAN_VALUE_INVALID",
+            &[
+                ("value", value.to_string()),
+                ("columnKey", column.key.clone()),
+            ],
+        )
+        .into()
+    })
+}
+
+fn write_boolean_value(
+    worksheet: &mut Worksheet,
+    x: u16,
+    y: u32,
+    boolean: bool,
+    column: &ExcelColumnInfo,
+) -> Result<(), Box<dyn std::error::Error>> {
+    match column.get_boolean_labels() {
+        Some((true_label, false_label)) => {
+            worksheet.write_string(y, x, if boolean { true_label } else { false_label })?;
+        }
+        None => {
+            worksheet.write_boolean(y, x, boolean)?;
+        }
+    }
+    Ok(())
+}
+
+fn is_link_data_type(data_type: &str) -> bool {
+    data_type.eq_ignore_ascii_case("link")
+}
+
+fn is_link_url(url: &str) -> bool {
+    LINK_SCHEMES.iter().any(|scheme| url.starts_with(scheme))
+}
+
+/// The display text a `link` column takes from its `linkTextKey` in the same row.
+fn get_link_text<'a>(
+    columns: impl IntoIterator<Item = &'a ExcelColumnData>,
+    column: &ExcelColumnInfo,
+) -> Option<&'a str> {
+    let key = column.link_text_key.as_deref()?;
+    columns
+        .into_iter()
+        .find(|column_data| column_data.key == key)
+        .map(|column_data| column_data.value())
+        .filter(|text| !text.trim().is_empty())
+}
+
+/// The target and display text of a cell that should be written as a hyperlink.
+fn get_export_link(
+    column_data: &ExcelColumnData,
+    column: &ExcelColumnInfo,
+    link_text: Option<&str>,
+) -> Result<Option<(String, String)>, Box<dyn std::error::Error>> {
+    let link = match column_data.typed_value() {
+        ExcelValue::Link { .. } => column_data.typed_value().clone(),
+        // Plain text without a target, such as an imported `N/A`, is written as is.
+        ExcelValue::Text(value)
+            if is_link_data_type(&column.data_type)
+                && (value.contains('|') || is_link_url(value.trim())) =>
+        {
+            ExcelValue::parse_link(value)
+        }
+        _ => return Ok(None),
+    };
+    let ExcelValue::Link { url, text } = link else {
+        return Ok(None);
+    };
+    if !is_link_url(&url) {
+        return Err(create_structured_error(
+            format!(
+                "Invalid link '{}' for column '{}', expected one of {}",
+                url,
+                column.key,
+                LINK_SCHEMES.join(", ")
+            ),
+            "EXPORT_LINK_VALUE_INVALID",
+            &[("url", url.clone()), ("columnKey", column.key.clone())],
+        )
+        .into());
     }
+    let text = link_text.map(str::to_string).unwrap_or(text);
+    Ok(Some((url, text)))
+}
+
+fn write_link(
+    worksheet: &mut Worksheet,
+    x: u16,
+    y: u32,
+    url: &str,
+    text: &str,
+    format: &Format,
+) -> Result<(), Box<dyn std::error::Error>> {
+    worksheet
+        .write_url_with_format(y, x, Url::new(url).set_text(text), format)
+        .map_err(|e| {
+            create_structured_error(
+                format!("Invalid link '{}': {}", url, e),
+                "EXPORT_LINK_VALUE_INVALID",
+                &[("url", url.to_string()), ("reason", e.to_string())],
+            )
+        })?;
     Ok(())
 }
 
@@ -861,22 +3352,47 @@ fn write_children_row<'a>(
     row: Vec<&'a ExcelColumnData>,
     y: u32,
     column_positions_map: &'a HashMap<String, (&'a ExcelColumnPosition, &'a ExcelColumnInfo)>,
+    formula_columns: &'a [FormulaColumn<'a>],
     info: &'a ExcelInfo,
-) -> Pin<Box<dyn Future<Output = Result<u32, Box<dyn std::error::Error>>> + 'a>> {
+    row_formats: &'a [&'a ExcelRowFormat],
+) -> WriteRowFuture<'a> {
     Box::pin(async move {
         let mut current_y = y;
         let mut t_y = y;
-        for (_, column_data) in row.iter().enumerate() {
+        for column_data in row.iter() {
             if !column_data.children.is_empty() {
                 for d in column_data.children.iter() {
+                    let child_y = t_y;
                     t_y = write_children_row(
                         worksheet,
                         d.columns.iter().collect(),
                         t_y,
                         column_positions_map,
-                        &info,
+                        formula_columns,
+                        info,
+                        row_formats,
                     )
                     .await?;
+                    if let Some((_, group)) = column_positions_map.get(&column_data.key) {
+                        write_formula_cells(
+                            worksheet,
+                            formula_columns,
+                            &group.data_group,
+                            child_y,
+                            t_y - 1,
+                            row_formats,
+                        )?;
+                        if !row_formats.is_empty() {
+                            write_row_format_blanks(
+                                worksheet,
+                                column_positions_map,
+                                &d.columns.iter().collect::<Vec<_>>(),
+                                &group.data_group,
+                                (child_y, t_y - 1),
+                                row_formats,
+                            )?;
+                        }
+                    }
                 }
                 let last_row = t_y - 1;
 
@@ -885,25 +3401,30 @@ fn write_children_row<'a>(
                 }
                 if let Some((pos, column)) = column_positions_map.get(&column_data.key) {
                     if !column.is_root_group() {
+                        let cell = CellContext {
+                            link_text: get_link_text(row.iter().copied(), column),
+                            row_formats,
+                        };
                         if y == last_row {
                             write_single_cell(
                                 worksheet,
                                 pos.x1,
                                 y,
-                                &column_data.value,
-                                &column,
+                                column_data,
+                                column,
                                 info,
+                                cell,
                             )
                             .await?;
                         } else {
                             write_range_cell(
                                 worksheet,
-                                pos.x1,
-                                pos.x2,
+                                pos,
                                 y,
                                 last_row,
-                                &column_data.value,
-                                &column,
+                                column_data,
+                                column,
+                                cell,
                             )?;
                         }
                     }
@@ -912,30 +3433,189 @@ fn write_children_row<'a>(
                 continue;
             }
             if let Some((pos, column)) = column_positions_map.get(&column_data.key) {
-                write_single_cell(worksheet, pos.x1, y, &column_data.value, &column, &info).await?;
+                let cell = CellContext {
+                    link_text: get_link_text(row.iter().copied(), column),
+                    row_formats,
+                };
+                write_single_cell(worksheet, pos.x1, y, column_data, column, info, cell).await?;
             }
         }
         Ok(current_y + 1)
     })
 }
 
+/// A formula column with its `{key}` placeholders resolved to column letters.
+struct FormulaColumn<'a> {
+    position: &'a ExcelColumnPosition,
+    column: &'a ExcelColumnInfo,
+    parts: Vec<FormulaPart<'a>>,
+}
+
+enum FormulaPart<'a> {
+    Text(&'a str),
+    Cell { x: u16, data_group_parent: &'a str },
+}
+
+impl FormulaColumn<'_> {
+    /// Resolves the template for rows `y1..=y2` written at the `data_group` level.
+    /// Columns of nested groups become the range over every row of the block.
+    fn get_formula(&self, data_group: &str, y1: u32, y2: u32) -> String {
+        let mut formula = String::new();
+        for part in self.parts.iter() {
+            match part {
+                FormulaPart::Text(text) => formula.push_str(text),
+                FormulaPart::Cell {
+                    x,
+                    data_group_parent,
+                } if y1 == y2 || *data_group_parent == data_group => {
+                    formula.push_str(&get_excel_cell_ref(*x, y1));
+                }
+                FormulaPart::Cell { x, .. } => {
+                    formula.push_str(&get_excel_cell_ref(*x, y1));
+                    formula.push(':');
+                    formula.push_str(&get_excel_cell_ref(*x, y2));
+                }
+            }
+        }
+        formula
+    }
+}
+
+fn get_formula_columns<'a>(
+    info: &'a ExcelInfo,
+    column_positions: &'a [ExcelColumnPosition],
+) -> Result<Vec<FormulaColumn<'a>>, Box<dyn std::error::Error>> {
+    let mut formula_columns = Vec::new();
+    for position in column_positions.iter().filter(|position| position.is_leaf) {
+        let column = find_column(info, &position.key)?;
+        let Some(formula) = column.formula.as_deref() else {
+            continue;
+        };
+        let mut parts = Vec::new();
+        for token in split_formula_template(formula) {
+            parts.push(match token {
+                FormulaToken::Text(text) => FormulaPart::Text(text),
+                FormulaToken::Key(key) => {
+                    let referenced = find_column(info, key)?;
+                    let x = column_positions
+                        .iter()
+                        .find(|position| position.key == key)
+                        .map_or(0, |position| position.x1);
+                    FormulaPart::Cell {
+                        x,
+                        data_group_parent: &referenced.data_group_parent,
+                    }
+                }
+            });
+        }
+        formula_columns.push(FormulaColumn {
+            position,
+            column,
+            parts,
+        });
+    }
+    Ok(formula_columns)
+}
+
+/// Writes the formula columns of the `data_group` level for rows `y1..=y2`,
+/// merging the cell when the block spans several rows.
+fn write_formula_cells(
+    worksheet: &mut Worksheet,
+    formula_columns: &[FormulaColumn],
+    data_group: &str,
+    y1: u32,
+    y2: u32,
+    row_formats: &[&ExcelRowFormat],
+) -> Result<(), Box<dyn std::error::Error>> {
+    for formula_column in formula_columns
+        .iter()
+        .filter(|formula_column| formula_column.column.data_group_parent == data_group)
+    {
+        let position = formula_column.position;
+        let value_format = get_column_value_format("", formula_column.column, row_formats);
+        let base_format = value_format.as_ref().unwrap_or(&DEFAULT_FORMAT);
+        let number_format = get_number_cell_format(base_format, formula_column.column);
+        let f = number_format.as_ref().unwrap_or(base_format);
+        if y2 > y1 {
+            worksheet.merge_range(y1, position.x1, y2, position.x1, "", f)?;
+        }
+        let formula = Formula::new(formula_column.get_formula(data_group, y1, y2));
+        worksheet.write_formula_with_format(y1, position.x1, formula, f)?;
+    }
+    Ok(())
+}
+
 fn write_range_cell(
     worksheet: &mut Worksheet,
-    x1: u16,
-    x2: u16,
+    pos: &ExcelColumnPosition,
     y1: u32,
     y2: u32,
-    value: &str,
+    column_data: &ExcelColumnData,
     column: &ExcelColumnInfo,
+    cell: CellContext<'_>,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    if let Some(f) = get_column_value_format(value, column) {
-        worksheet.merge_range(y1, x1, y2, x2, value, &f)?;
+    let (x1, x2) = (pos.x1, pos.x2);
+    let value = column_data.value();
+    let value_format = get_column_value_format(value, column, cell.row_formats);
+    if let Some((url, text)) = get_export_link(column_data, column, cell.link_text)? {
+        let f = value_format.as_ref().unwrap_or(&DEFAULT_LINK_FORMAT);
+        worksheet.merge_range(y1, x1, y2, x2, "", f)?;
+        return write_link(worksheet, x1, y1, &url, &text, f);
+    }
+    if let Some(date_time) = get_export_date(column_data, column)? {
+        let f = value_format.as_ref().unwrap_or(&DEFAULT_DATE_FORMAT);
+        worksheet.merge_range(y1, x1, y2, x2, "", f)?;
+        worksheet.write_number(y1, x1, date_time_to_excel(&date_time))?;
+        let date_format = get_date_cell_format(f, column);
+        worksheet.set_cell_format(y1, x1, date_format.as_ref().unwrap_or(f))?;
+        return Ok(());
+    }
+    let f = value_format.as_ref().unwrap_or(&DEFAULT_FORMAT);
+    if column_data.typed_value().is_native() {
+        worksheet.merge_range(y1, x1, y2, x2, "", f)?;
+        let is_date_type = write_typed_value(worksheet, x1, y1, column_data.typed_value())?;
+        let number_format = get_number_cell_format(f, column)
+            .filter(|_| matches!(column_data.typed_value(), ExcelValue::Number(_)));
+        if let Some(number_format) = number_format {
+            worksheet.set_cell_format(y1, x1, &number_format)?;
+        } else if is_date_type && value_format.is_none() {
+            worksheet.set_cell_format(y1, x1, &DEFAULT_DATE_FORMAT)?;
+        } else {
+            worksheet.set_cell_format(y1, x1, f)?;
+        }
     } else {
-        worksheet.merge_range(y1, x1, y2, x2, value, &DEFAULT_FORMAT)?;
+        worksheet.merge_range(y1, x1, y2, x2, value, f)?;
     }
     Ok(())
 }
 
+fn write_typed_value(
+    worksheet: &mut Worksheet,
+    x: u16,
+    y: u32,
+    typed_value: &ExcelValue,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    match typed_value {
+        ExcelValue::Number(number) => {
+            worksheet.write_number(y, x, *number)?;
+        }
+        ExcelValue::Bool(boolean) => {
+            worksheet.write_boolean(y, x, *boolean)?;
+        }
+        ExcelValue::DateTime(date_time) => {
+            worksheet.write_datetime(y, x, ExcelValue::to_excel_date_time(date_time)?)?;
+            return Ok(true);
+        }
+        ExcelValue::Link { url, text } => {
+            write_link(worksheet, x, y, url, text, &DEFAULT_LINK_FORMAT)?;
+        }
+        ExcelValue::Empty | ExcelValue::Text(_) | ExcelValue::Error(_) => {
+            worksheet.write_string(y, x, typed_value.to_display_string())?;
+        }
+    }
+    Ok(false)
+}
+
 fn get_cell_format(value_format: &ExcelCellFormat) -> Format {
     let mut result = Format::new();
     result = result.set_font_size(value_format.font_size);
@@ -982,8 +3662,44 @@ fn get_cell_format(value_format: &ExcelCellFormat) -> Format {
     result
 }
 
-fn get_column_value_format(value: &str, column: &ExcelColumnInfo) -> Option<Format> {
-    column.get_value_format(value).map(get_cell_format)
+/// The parts of a cell format Excel applies in conditional formats: font
+/// color and style, fill and border. Font size and alignment are ignored.
+fn get_conditional_cell_format(value_format: &ExcelCellFormat) -> Format {
+    let mut result = Format::new()
+        .set_background_color(Color::from(value_format.background_color.as_str()))
+        .set_font_color(Color::from(value_format.color.as_str()));
+    if value_format.bold {
+        result = result.set_bold();
+    }
+    if value_format.italic {
+        result = result.set_italic();
+    }
+    if value_format.strikethrough {
+        result = result.set_font_strikethrough();
+    }
+    if value_format.underline {
+        result = result.set_underline(FormatUnderline::Single);
+    }
+    if let Some(border_color) = &value_format.border_color {
+        result = result
+            .set_border(FormatBorder::Thin)
+            .set_border_color(Color::from(border_color.as_str()));
+    }
+    result
+}
+
+/// The first matching row format for the column, else its `valueFormat`.
+fn get_column_value_format(
+    value: &str,
+    column: &ExcelColumnInfo,
+    row_formats: &[&ExcelRowFormat],
+) -> Option<Format> {
+    row_formats
+        .iter()
+        .find(|row_format| row_format.applies_to(&column.key))
+        .and_then(|row_format| row_format.format.as_ref())
+        .or_else(|| column.get_value_format(value))
+        .map(get_cell_format)
 }
 
 fn get_parent_times(
@@ -1001,6 +3717,41 @@ fn get_parent_times(
     parent_times
 }
 
+/// Flattens grouped rows into one map per leaf row, repeating the parent values.
+fn flatten_row(row: &ExcelRowData) -> Vec<HashMap<&str, &ExcelColumnData>> {
+    let mut base_line = HashMap::new();
+    let mut children_lines = Vec::new();
+    for column_data in row.columns.iter() {
+        base_line.insert(column_data.key.as_str(), column_data);
+        if !column_data.children.is_empty() {
+            children_lines.push(
+                column_data
+                    .children
+                    .iter()
+                    .flat_map(flatten_row)
+                    .collect::<Vec<HashMap<&str, &ExcelColumnData>>>(),
+            );
+        }
+    }
+    let line_count = children_lines
+        .iter()
+        .map(|lines| lines.len())
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    (0..line_count)
+        .map(|line_index| {
+            let mut line = base_line.clone();
+            for lines in children_lines.iter() {
+                if let Some(child_line) = lines.get(line_index) {
+                    line.extend(child_line.iter().map(|(key, value)| (*key, *value)));
+                }
+            }
+            line
+        })
+        .collect()
+}
+
 fn get_column_positions(info: &ExcelInfo) -> Vec<ExcelColumnPosition> {
     let parent_map = info.get_parent_map();
     let leaf_columns = info.get_leaf_columns();
@@ -1070,7 +3821,7 @@ fn find_column<'a>(
         .find(|column| column.key == key)
         .ok_or_else(|| {
             create_structured_error(
-                &format!("Column key missing: {}", key),
+                format!("Column key missing: {}", key),
                 "COLUMN_KEY_MISSING",
                 &[("columnKey", key.to_string())],
             )
@@ -0,0 +1,373 @@
+use crate::workbook_reader::{
+    get_list_validations, get_merged_regions, open_import_workbook, parse_range_ref,
+    ImportWorkbook, ListValidation,
+};
+use crate::{
+    create_structured_error, format_header_value, get_excel_cell_ref, resolve_dynamic_header_row,
+    resolve_sheet_name, ExcelColumnInfo, ExcelInfo,
+};
+use calamine::{Data, Dimensions, Range, Reader};
+use std::collections::HashSet;
+
+const TYPE_SAMPLE_SIZE: usize = 100;
+
+struct HeaderLayout<'a> {
+    range: &'a Range<Data>,
+    merged_regions: &'a [Dimensions],
+    top: u32,
+    depth: u32,
+}
+
+impl HeaderLayout<'_> {
+    fn bottom(&self) -> u32 {
+        self.top + self.depth - 1
+    }
+
+    fn get_merged_region(&self, y: u32, x: u32) -> Option<&Dimensions> {
+        self.merged_regions
+            .iter()
+            .find(|region| region.start == (y, x))
+    }
+
+    fn is_covered(&self, y: u32, x: u32) -> bool {
+        self.merged_regions
+            .iter()
+            .any(|region| region.start != (y, x) && region.contains(y, x))
+    }
+
+    fn is_row_blank(&self, y: u32, x1: u32, x2: u32) -> bool {
+        (x1..=x2).all(|x| {
+            format_header_value(self.range.get_value((y, x))).is_empty() && !self.is_covered(y, x)
+        })
+    }
+}
+
+/// Builds an `ExcelInfo` from an existing sheet. The header block is read
+/// from merged cells, data types are guessed from sampled rows and `list`
+/// data validations become `allowed_values`.
+pub fn infer_excel_info_buffer(
+    sheet_name: Option<String>,
+    header_row: Option<u32>,
+    excel_bytes: &[u8],
+    format: Option<&str>,
+) -> Result<ExcelInfo, Box<dyn std::error::Error>> {
+    let mut workbook = open_import_workbook(excel_bytes, format)?;
+    let sheet_name = resolve_sheet_name(&workbook, sheet_name.as_deref())?;
+    let range = workbook.worksheet_range(sheet_name.as_str())?;
+    let merged_regions = get_merged_regions(&mut workbook, &sheet_name)?;
+    let list_validations = get_list_validations(&workbook, excel_bytes, &sheet_name)?;
+
+    let mut top = resolve_dynamic_header_row(&range, header_row)?;
+    let title = match header_row {
+        Some(_) => None,
+        None => get_title(&range, &merged_regions, top),
+    };
+    let title = title.map(|(title, header_top)| {
+        top = header_top;
+        title
+    });
+    let Some((x1, x2)) = get_header_columns(&range, &merged_regions, top) else {
+        return Err(create_structured_error(
+            "Schema inference could not find a non-empty header row in the worksheet",
+            "DYNAMIC_HEADER_ROW_NOT_FOUND",
+            &[],
+        )
+        .into());
+    };
+    let layout = HeaderLayout {
+        range: &range,
+        merged_regions: &merged_regions,
+        top,
+        depth: get_header_depth(&range, &merged_regions, top, x1, x2),
+    };
+
+    let mut columns = Vec::new();
+    let mut keys = HashSet::new();
+    collect_columns(&layout, 0, x1, x2, "", &mut keys, &mut columns)?;
+
+    let first_data_row = layout.bottom() + 1;
+    let mut column_infos = Vec::with_capacity(columns.len());
+    for (column, x) in columns {
+        let column = match x {
+            Some(x) => {
+                let allowed_values = get_allowed_values(
+                    &mut workbook,
+                    &sheet_name,
+                    &list_validations,
+                    first_data_row,
+                    x,
+                );
+                column
+                    .with_data_type(guess_data_type(&range, first_data_row, x))
+                    .with_allowed_values(allowed_values)
+            }
+            None => column,
+        };
+        column_infos.push(column);
+    }
+
+    let info = ExcelInfo::new(
+        sheet_name.clone(),
+        sheet_name,
+        column_infos,
+        "".into(),
+        "".into(),
+    )
+    .map_err(|reason| {
+        create_structured_error(&reason, "INVALID_DEFINITION", &[("reason", reason.clone())])
+    })?;
+    let dy = if title.is_some() { top - 1 } else { top };
+    let info = info.with_offset(x1 as u16, dy);
+    Ok(match title {
+        Some(title) => info.with_title(title),
+        None => info,
+    })
+}
+
+/// A title is a single merged cell spanning several columns above a header
+/// row with more than one label. Returns the title and that header row.
+fn get_title(
+    range: &Range<Data>,
+    merged_regions: &[Dimensions],
+    row: u32,
+) -> Option<(String, u32)> {
+    let (_, start_x) = range.start()?;
+    let (end_y, end_x) = range.end()?;
+    let cells: Vec<u32> = (start_x..=end_x)
+        .filter(|x| !format_header_value(range.get_value((row, *x))).is_empty())
+        .collect();
+    let [x] = cells[..] else {
+        return None;
+    };
+    merged_regions
+        .iter()
+        .find(|region| region.start == (row, x) && region.end.0 == row && region.end.1 > x)?;
+    let (header_row, labels) = (row + 1..=end_y).find_map(|y| {
+        let count = (start_x..=end_x)
+            .filter(|x| !format_header_value(range.get_value((y, *x))).is_empty())
+            .count();
+        (count > 0).then_some((y, count))
+    })?;
+    (labels > 1).then(|| (format_header_value(range.get_value((row, x))), header_row))
+}
+
+fn get_header_columns(
+    range: &Range<Data>,
+    merged_regions: &[Dimensions],
+    top: u32,
+) -> Option<(u32, u32)> {
+    let (_, start_x) = range.start()?;
+    let (_, end_x) = range.end()?;
+    let has_header = |x: u32| {
+        !format_header_value(range.get_value((top, x))).is_empty()
+            || merged_regions.iter().any(|region| region.contains(top, x))
+    };
+    let x1 = (start_x..=end_x).find(|x| has_header(*x))?;
+    let x2 = (x1..=end_x).rev().find(|x| has_header(*x))?;
+    Some((x1, x2))
+}
+
+/// Counts header rows: vertical merges extend the block to their last row,
+/// and a horizontal merge on the last row is a parent with labels below it.
+fn get_header_depth(
+    range: &Range<Data>,
+    merged_regions: &[Dimensions],
+    top: u32,
+    x1: u32,
+    x2: u32,
+) -> u32 {
+    let end_y = range.end().map(|(y, _)| y).unwrap_or(top);
+    let mut depth = 1;
+    loop {
+        let bottom = top + depth - 1;
+        let mut next_depth = depth;
+        for region in merged_regions.iter().filter(|region| {
+            (top..=bottom).contains(&region.start.0) && (x1..=x2).contains(&region.start.1)
+        }) {
+            next_depth = next_depth.max(region.end.0 - top + 1);
+            if region.start.0 == bottom
+                && region.end.0 == bottom
+                && region.end.1 > region.start.1
+                && bottom < end_y
+            {
+                next_depth = next_depth.max(depth + 1);
+            }
+        }
+        if next_depth == depth {
+            return depth;
+        }
+        depth = next_depth;
+    }
+}
+
+fn collect_columns(
+    layout: &HeaderLayout,
+    level: u32,
+    x1: u32,
+    x2: u32,
+    parent: &str,
+    keys: &mut HashSet<String>,
+    columns: &mut Vec<(ExcelColumnInfo, Option<u32>)>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let y = layout.top + level;
+    let mut x = x1;
+    while x <= x2 {
+        let name = format_header_value(layout.range.get_value((y, x)));
+        let (span_y2, span_x2) = layout
+            .get_merged_region(y, x)
+            .map(|region| region.end)
+            .unwrap_or((y, x));
+        let span_x2 = span_x2.min(x2);
+
+        if name.is_empty() {
+            if y >= layout.bottom() {
+                let cell_ref = get_excel_cell_ref(x as u16, y);
+                return Err(create_structured_error(
+                    format!(
+                        "Schema inference requires non-empty header names. Found an empty header at {}.",
+                        cell_ref
+                    ),
+                    "DYNAMIC_HEADER_EMPTY",
+                    &[("cell", cell_ref)],
+                )
+                .into());
+            }
+            collect_columns(layout, level + 1, x, x, parent, keys, columns)?;
+            x += 1;
+            continue;
+        }
+
+        let key = get_unique_key(&name, parent, keys).ok_or_else(|| {
+            let cell_ref = get_excel_cell_ref(x as u16, y);
+            create_structured_error(
+                format!(
+                    "Schema inference requires unique header names. Duplicate header '{}' found at {}.",
+                    name, cell_ref
+                ),
+                "DYNAMIC_HEADER_DUPLICATE",
+                &[("header", name.clone()), ("cell", cell_ref)],
+            )
+        })?;
+        let column = ExcelColumnInfo::new(key.clone(), name).with_parent(parent);
+        let is_leaf = span_y2 >= layout.bottom() || layout.is_row_blank(y + 1, x, span_x2);
+        if is_leaf {
+            columns.push((column, Some(x)));
+        } else {
+            columns.push((column, None));
+            collect_columns(layout, level + 1, x, span_x2, &key, keys, columns)?;
+        }
+        x = span_x2 + 1;
+    }
+    Ok(())
+}
+
+/// Uses the header name as key, prefixed with the parent key when the name
+/// is already taken by another group.
+fn get_unique_key(name: &str, parent: &str, keys: &mut HashSet<String>) -> Option<String> {
+    let candidates = [name.to_string(), format!("{}.{}", parent, name)];
+    let key = candidates
+        .into_iter()
+        .take(if parent.is_empty() { 1 } else { 2 })
+        .find(|candidate| !keys.contains(candidate))?;
+    keys.insert(key.clone());
+    Some(key)
+}
+
+fn guess_data_type(range: &Range<Data>, first_data_row: u32, x: u32) -> &'static str {
+    let Some((end_y, _)) = range.end() else {
+        return "text";
+    };
+    let mut samples = (first_data_row..=end_y)
+        .filter_map(|y| range.get_value((y, x)))
+        .filter(|data| match data {
+            Data::Empty => false,
+            Data::String(value) => !value.trim().is_empty(),
+            _ => true,
+        })
+        .take(TYPE_SAMPLE_SIZE)
+        .peekable();
+    if samples.peek().is_none() {
+        return "text";
+    }
+    let mut data_type = None;
+    for data in samples {
+        let sample_type = match data {
+            Data::Int(_) | Data::Float(_) => "number",
+            Data::DateTime(_) | Data::DateTimeIso(_) => "date",
+            Data::Bool(_) => "boolean",
+            _ => return "text",
+        };
+        if data_type.is_some_and(|data_type| data_type != sample_type) {
+            return "text";
+        }
+        data_type = Some(sample_type);
+    }
+    data_type.unwrap_or("text")
+}
+
+fn get_allowed_values(
+    workbook: &mut ImportWorkbook,
+    sheet_name: &str,
+    list_validations: &[ListValidation],
+    first_data_row: u32,
+    x: u32,
+) -> Vec<String> {
+    list_validations
+        .iter()
+        .find(|validation| {
+            validation.ranges.iter().any(|range| {
+                range.start.1 <= x && x <= range.end.1 && range.end.0 >= first_data_row
+            })
+        })
+        .map(|validation| resolve_list_formula(workbook, sheet_name, &validation.formula))
+        .unwrap_or_default()
+}
+
+/// Resolves an inline `"a,b,c"` list, a cell range or a defined name that
+/// points at a cell range. Ranges without a sheet refer to `sheet_name`.
+fn resolve_list_formula(
+    workbook: &mut ImportWorkbook,
+    sheet_name: &str,
+    formula: &str,
+) -> Vec<String> {
+    let formula = formula.trim().trim_start_matches('=');
+    if let Some(list) = formula
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+    {
+        return list
+            .replace("\"\"", "\"")
+            .split(',')
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+            .collect();
+    }
+    let formula = workbook
+        .defined_names()
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(formula))
+        .map(|(_, reference)| reference.trim_start_matches('=').to_string())
+        .unwrap_or_else(|| formula.to_string());
+    let (sheet_name, reference) = match formula.rsplit_once('!') {
+        Some((sheet_name, reference)) => {
+            (sheet_name.trim_matches('\'').replace("''", "'"), reference)
+        }
+        None => (sheet_name.to_string(), formula.as_str()),
+    };
+    let (Some(reference), Ok(range)) = (
+        parse_range_ref(reference),
+        workbook.worksheet_range(&sheet_name),
+    ) else {
+        return Vec::new();
+    };
+    let mut values = Vec::new();
+    for y in reference.start.0..=reference.end.0 {
+        for x in reference.start.1..=reference.end.1 {
+            let value = format_header_value(range.get_value((y, x)));
+            if !value.is_empty() {
+                values.push(value);
+            }
+        }
+    }
+    values
+}
@@ -0,0 +1,948 @@
+#[cfg(feature = "wasm")]
+use crate::wasm::error_to_js_value;
+use crate::{
+    create_structured_error, date_time_to_excel, find_column, get_boolean_options, get_cell_text,
+    get_column_positions, get_date_cell_pattern, get_display_width, get_excel_cell_ref,
+    get_export_date, get_export_link, get_formula_columns, get_header_width, get_link_text,
+    get_list_options, get_validation_messages, get_validation_title, is_boolean_data_type,
+    is_date_data_type, parse_date_time_text, parse_export_boolean, parse_export_number,
+    ExcelColumnData, ExcelColumnInfo, ExcelColumnPosition, ExcelData, ExcelInfo, ExcelRowData,
+    ExcelValue, FormulaColumn,
+};
+use rust_xlsxwriter::Worksheet;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write as _;
+use std::io::{Cursor, Write};
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+const MAX_ROWS: u32 = 1_048_576;
+const STYLE_HEADER: u32 = 1;
+const STYLE_DATE: u32 = 2;
+const STYLE_DEFAULT: u32 = 3;
+const STYLE_LINK: u32 = 4;
+
+const CONTENT_TYPES_XML: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types"><Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/><Default Extension="xml" ContentType="application/xml"/><Override PartName="/xl/workbook.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml"/><Override PartName="/xl/worksheets/sheet1.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/><Override PartName="/xl/styles.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.styles+xml"/><Override PartName="/docProps/core.xml" ContentType="application/vnd.openxmlformats-package.core-properties+xml"/></Types>"#;
+
+const ROOT_RELS_XML: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="xl/workbook.xml"/><Relationship Id="rId2" Type="http://schemas.openxmlformats.org/package/2006/relationships/metadata/core-properties" Target="docProps/core.xml"/></Relationships>"#;
+
+const WORKBOOK_RELS_XML: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/><Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/styles" Target="styles.xml"/></Relationships>"#;
+
+// Style ids match the STYLE_* constants: default, header, date, data and link cells.
+// Column number and date formats get numFmt ids from NUMBER_FORMAT_ID and the styles after STYLE_LINK.
+const CELL_XFS_XML: &str = r#"<xf numFmtId="0" fontId="0" fillId="0" borderId="0" xfId="0"/><xf numFmtId="0" fontId="1" fillId="0" borderId="0" xfId="0" applyFont="1" applyAlignment="1"><alignment horizontal="center" vertical="center" wrapText="1"/></xf><xf numFmtId="164" fontId="0" fillId="0" borderId="0" xfId="0" applyNumberFormat="1" applyAlignment="1"><alignment vertical="center" wrapText="1"/></xf><xf numFmtId="0" fontId="0" fillId="0" borderId="0" xfId="0" applyAlignment="1"><alignment vertical="center" wrapText="1"/></xf><xf numFmtId="0" fontId="2" fillId="0" borderId="0" xfId="0" applyFont="1" applyAlignment="1"><alignment vertical="center" wrapText="1"/></xf>"#;
+const NUMBER_FORMAT_ID: u32 = 165;
+
+/// Writes a single-sheet workbook row by row without keeping the rows in memory.
+/// The sheet xml is rendered here rather than through rust_xlsxwriter, and only
+/// the compressed output, the header layout, merged ranges and hyperlinks are
+/// retained between batches. With auto-width columns the sheet head waits for
+/// the first batch, so the widths are fitted to the header and that batch only.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub struct ExcelStreamWriter {
+    info: ExcelInfo,
+    column_positions: Vec<ExcelColumnPosition>,
+    zip: Option<ZipWriter<Cursor<Vec<u8>>>>,
+    // Header rows held back until the first batch fits the auto-width columns.
+    pending_header_rows: Option<String>,
+    // Widest text of the first batch in each auto-width leaf column.
+    text_widths: HashMap<String, usize>,
+    merged_ranges: Vec<String>,
+    // Cell reference and target of every hyperlink written so far.
+    hyperlinks: Vec<(String, String)>,
+    column_styles: HashMap<String, u32>,
+    first_data_row: u32,
+    next_row: u32,
+}
+
+impl ExcelStreamWriter {
+    pub fn new(info: ExcelInfo) -> Result<Self, Box<dyn std::error::Error>> {
+        Worksheet::new().set_name(info.sheet_name.as_str())?;
+        info.check_sheet_options().map_err(|reason| {
+            create_structured_error(&reason, "INVALID_DEFINITION", &[("reason", reason.clone())])
+        })?;
+        check_stream_options(&info)?;
+        let column_positions = get_column_positions(&info);
+        for position in column_positions.iter() {
+            find_column(&info, &position.key)?;
+        }
+        let first_data_row = column_positions
+            .iter()
+            .map(|position| position.y2 + 1)
+            .max()
+            .unwrap_or(info.dy + u32::from(info.title.is_some()));
+
+        let (styles_xml, column_styles) = get_styles_xml(&info);
+
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+        for (path, content) in [
+            ("[Content_Types].xml", CONTENT_TYPES_XML.to_string()),
+            ("_rels/.rels", ROOT_RELS_XML.to_string()),
+            ("docProps/core.xml", get_core_xml(&info)),
+            ("xl/workbook.xml", get_workbook_xml(&info)),
+            ("xl/_rels/workbook.xml.rels", WORKBOOK_RELS_XML.to_string()),
+            ("xl/styles.xml", styles_xml),
+        ] {
+            zip.start_file(path, options)?;
+            zip.write_all(content.as_bytes())?;
+        }
+        zip.start_file("xl/worksheets/sheet1.xml", options)?;
+
+        let mut text_widths = HashMap::new();
+        for position in column_positions.iter().filter(|position| position.is_leaf) {
+            let column = find_column(&info, &position.key)?;
+            if column.formula.is_none() && info.get_auto_width(column).is_some() {
+                text_widths.insert(position.key.clone(), 0);
+            }
+        }
+        let mut writer = ExcelStreamWriter {
+            info,
+            column_positions,
+            zip: Some(zip),
+            pending_header_rows: None,
+            text_widths,
+            merged_ranges: Vec::new(),
+            hyperlinks: Vec::new(),
+            column_styles,
+            first_data_row,
+            next_row: first_data_row,
+        };
+        let header_rows = writer.get_header_rows();
+        if writer.text_widths.is_empty() {
+            let sheet_head = writer.get_sheet_head();
+            writer.write_xml(&sheet_head)?;
+            writer.write_xml(&header_rows)?;
+        } else {
+            writer.pending_header_rows = Some(header_rows);
+        }
+        Ok(writer)
+    }
+
+    pub fn write_rows(&mut self, rows: &[ExcelRowData]) -> Result<(), Box<dyn std::error::Error>> {
+        let mut leaf_columns = HashMap::new();
+        for position in self.column_positions.iter().filter(|p| p.is_leaf) {
+            let column = find_column(&self.info, &position.key)?;
+            if column.formula.is_none() {
+                leaf_columns.insert(position.key.as_str(), (position, column));
+            }
+        }
+        let layout = RowLayout {
+            leaf_columns,
+            formula_columns: get_formula_columns(&self.info, &self.column_positions)?,
+            data_groups: self
+                .info
+                .columns
+                .iter()
+                .filter(|column| !column.data_group.is_empty())
+                .map(|column| (column.key.as_str(), column.data_group.as_str()))
+                .collect(),
+        };
+
+        // Rows are only committed once the whole batch rendered, so a failed
+        // batch leaves the writer ready for the next one.
+        let mut rows_xml = RowsXml::default();
+        let mut text_widths: HashMap<&str, usize> = HashMap::new();
+        let mut y = self.next_row;
+        for row in rows.iter() {
+            let mut cells = Vec::new();
+            let last_row = layout.lay_out_row(row, "", y, &mut cells);
+            if last_row >= MAX_ROWS {
+                return Err(create_structured_error(
+                    format!("Worksheet row limit of {} exceeded", MAX_ROWS),
+                    "STREAM_ROW_LIMIT_EXCEEDED",
+                    &[("maxRows", MAX_ROWS.to_string())],
+                )
+                .into());
+            }
+            cells.sort_by_key(|cell| (cell.y1, cell.position.x1));
+            let mut cells = cells.into_iter().peekable();
+            for row_y in y..=last_row {
+                write!(rows_xml.xml, r#"<row r="{}">"#, row_y + 1)?;
+                while let Some(cell) = cells.next_if(|cell| cell.y1 == row_y) {
+                    let x = cell.position.x1;
+                    let column_style = self.column_styles.get(&cell.position.key).copied();
+                    match cell.value {
+                        RowCellValue::Data {
+                            column_data,
+                            column,
+                            link_text,
+                        } => {
+                            write_data_cell(
+                                &mut rows_xml,
+                                x,
+                                row_y,
+                                column_data,
+                                column,
+                                link_text,
+                                column_style,
+                            )?;
+                            if self.pending_header_rows.is_some()
+                                && self.text_widths.contains_key(&column.key)
+                            {
+                                let text = get_cell_text(column_data, column, link_text);
+                                let width = text_widths.entry(column.key.as_str()).or_default();
+                                *width = (*width).max(get_display_width(&text.unwrap_or_default()));
+                            }
+                        }
+                        RowCellValue::Formula(formula) => {
+                            let style = column_style.unwrap_or(STYLE_DEFAULT);
+                            write_formula_cell(&mut rows_xml.xml, x, row_y, &formula, style);
+                        }
+                    }
+                    if cell.y2 > cell.y1 {
+                        rows_xml.merged_ranges.push(format!(
+                            "{}:{}",
+                            get_excel_cell_ref(x, cell.y1),
+                            get_excel_cell_ref(x, cell.y2)
+                        ));
+                    }
+                }
+                rows_xml.xml.push_str("</row>");
+            }
+            y = last_row + 1;
+        }
+        for (key, text_width) in text_widths {
+            if let Some(width) = self.text_widths.get_mut(key) {
+                *width = (*width).max(text_width);
+            }
+        }
+        self.write_pending_header_rows()?;
+        self.write_xml(&rows_xml.xml)?;
+        self.hyperlinks.extend(rows_xml.hyperlinks);
+        self.merged_ranges.extend(rows_xml.merged_ranges);
+        self.next_row = y;
+        Ok(())
+    }
+
+    pub fn finish(&mut self) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        self.write_pending_header_rows()?;
+        let sheet_tail = self.get_sheet_tail()?;
+        self.write_xml(&sheet_tail)?;
+        let sheet_rels = self.get_sheet_rels()?;
+        let mut zip = self.zip.take().ok_or_else(get_finished_error)?;
+        if let Some(sheet_rels) = sheet_rels {
+            let options =
+                SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+            zip.start_file("xl/worksheets/_rels/sheet1.xml.rels", options)?;
+            zip.write_all(sheet_rels.as_bytes())?;
+        }
+        Ok(zip.finish()?.into_inner())
+    }
+
+    fn write_xml(&mut self, xml: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let zip = self.zip.as_mut().ok_or_else(get_finished_error)?;
+        zip.write_all(xml.as_bytes())?;
+        Ok(())
+    }
+
+    /// Writes the sheet head, with the fitted auto-width columns, and the
+    /// header rows once the first batch has been measured.
+    fn write_pending_header_rows(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(header_rows) = self.pending_header_rows.take() {
+            let sheet_head = self.get_sheet_head();
+            self.write_xml(&sheet_head)?;
+            self.write_xml(&header_rows)?;
+        }
+        Ok(())
+    }
+
+    fn get_sheet_head(&self) -> String {
+        let info = &self.info;
+        let mut xml = String::from(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships">"#,
+        );
+        xml.push_str(r#"<sheetViews><sheetView tabSelected="1" workbookViewId="0">"#);
+        if info.is_header_freeze && self.first_data_row > 0 {
+            let _ = write!(
+                xml,
+                r#"<pane ySplit="{}" topLeftCell="A{}" activePane="bottomLeft" state="frozen"/><selection pane="bottomLeft"/>"#,
+                self.first_data_row,
+                self.first_data_row + 1
+            );
+        }
+        xml.push_str("</sheetView></sheetViews>");
+        match info.default_row_height {
+            Some(row_height) => {
+                let _ = write!(
+                    xml,
+                    r#"<sheetFormatPr defaultRowHeight="{}" customHeight="1"/>"#,
+                    row_height
+                );
+            }
+            None => xml.push_str(r#"<sheetFormatPr defaultRowHeight="15"/>"#),
+        }
+
+        let mut column_widths = Vec::new();
+        for position in self.column_positions.iter().filter(|p| p.is_leaf) {
+            if let Ok(column) = find_column(info, &position.key) {
+                let width = match self.text_widths.get(&position.key) {
+                    Some(text_width) => info
+                        .get_auto_width(column)
+                        .unwrap_or_default()
+                        .fit(get_display_width(&column.name).max(*text_width)),
+                    None => get_header_width(info, column),
+                };
+                column_widths.push((position.x1, width));
+            }
+        }
+        if !column_widths.is_empty() {
+            xml.push_str("<cols>");
+            for (x, width) in column_widths {
+                let _ = write!(
+                    xml,
+                    r#"<col min="{0}" max="{0}" width="{1}" customWidth="1"/>"#,
+                    x + 1,
+                    get_column_width(width)
+                );
+            }
+            xml.push_str("</cols>");
+        }
+        xml
+    }
+
+    fn get_header_rows(&mut self) -> String {
+        let info = &self.info;
+        let mut xml = String::new();
+        let mut header_rows: BTreeMap<u32, Vec<(u16, String)>> = BTreeMap::new();
+        let max_column_x = self.column_positions.iter().map(|p| p.x2).max();
+        if let (Some(title), Some(max_column_x)) = (info.title.as_ref(), max_column_x) {
+            header_rows
+                .entry(info.dy)
+                .or_default()
+                .push((info.dx, title.clone()));
+            if max_column_x > info.dx {
+                self.merged_ranges.push(format!(
+                    "{}:{}",
+                    get_excel_cell_ref(info.dx, info.dy),
+                    get_excel_cell_ref(max_column_x, info.dy)
+                ));
+            }
+        }
+        for position in self.column_positions.iter() {
+            let Ok(column) = find_column(info, &position.key) else {
+                continue;
+            };
+            header_rows
+                .entry(position.y1)
+                .or_default()
+                .push((position.x1, column.name.clone()));
+            if !position.is_single_cell() {
+                self.merged_ranges.push(format!(
+                    "{}:{}",
+                    get_excel_cell_ref(position.x1, position.y1),
+                    get_excel_cell_ref(position.x2, position.y2)
+                ));
+            }
+        }
+
+        xml.push_str("<sheetData>");
+        for (y, cells) in header_rows.iter_mut() {
+            cells.sort_by_key(|(x, _)| *x);
+            let row_height = if info.title.is_some() && *y == info.dy {
+                info.title_height
+            } else {
+                info.header_row_height
+            };
+            match row_height {
+                Some(row_height) => {
+                    let _ = write!(
+                        xml,
+                        r#"<row r="{}" ht="{}" customHeight="1">"#,
+                        y + 1,
+                        row_height
+                    );
+                }
+                None => {
+                    let _ = write!(xml, r#"<row r="{}">"#, y + 1);
+                }
+            }
+            for (x, text) in cells.iter() {
+                write_string_cell(&mut xml, *x, *y, text, STYLE_HEADER);
+            }
+            xml.push_str("</row>");
+        }
+        xml
+    }
+
+    fn get_sheet_tail(&self) -> Result<String, Box<dyn std::error::Error>> {
+        let mut xml = String::from("</sheetData>");
+        let leaf_xs = self
+            .column_positions
+            .iter()
+            .filter(|p| p.is_leaf)
+            .map(|p| p.x1);
+        if let (true, Some(first_x), Some(last_x)) =
+            (self.info.autofilter, leaf_xs.clone().min(), leaf_xs.max())
+        {
+            let header_row = self.first_data_row.saturating_sub(1);
+            write!(
+                xml,
+                r#"<autoFilter ref="{}:{}"/>"#,
+                get_excel_cell_ref(first_x, header_row),
+                get_excel_cell_ref(last_x, self.next_row.saturating_sub(1).max(header_row))
+            )?;
+        }
+        if !self.merged_ranges.is_empty() {
+            write!(xml, r#"<mergeCells count="{}">"#, self.merged_ranges.len())?;
+            for merged_range in self.merged_ranges.iter() {
+                write!(xml, r#"<mergeCell ref="{}"/>"#, merged_range)?;
+            }
+            xml.push_str("</mergeCells>");
+        }
+
+        let mut validations = Vec::new();
+        for position in self.column_positions.iter().filter(|p| p.is_leaf) {
+            let column = find_column(&self.info, &position.key)?;
+            let sqref = format!(
+                "{}:{}",
+                get_excel_cell_ref(position.x1, self.first_data_row),
+                get_excel_cell_ref(position.x1, self.next_row)
+            );
+            if let Some(validation) = get_data_validation_xml(column, &sqref) {
+                validations.push(validation);
+            }
+        }
+        if !validations.is_empty() {
+            write!(
+                xml,
+                r#"<dataValidations count="{}">{}</dataValidations>"#,
+                validations.len(),
+                validations.join("")
+            )?;
+        }
+        if !self.hyperlinks.is_empty() {
+            xml.push_str("<hyperlinks>");
+            let mut relationship_id = 0;
+            for (cell_ref, url) in self.hyperlinks.iter() {
+                match url.strip_prefix("internal:") {
+                    Some(location) => write!(
+                        xml,
+                        r#"<hyperlink ref="{}" location="{}"/>"#,
+                        cell_ref,
+                        escape_xml(location)
+                    )?,
+                    None => {
+                        relationship_id += 1;
+                        write!(
+                            xml,
+                            r#"<hyperlink ref="{}" r:id="rId{}"/>"#,
+                            cell_ref, relationship_id
+                        )?;
+                    }
+                }
+            }
+            xml.push_str("</hyperlinks>");
+        }
+        xml.push_str(
+            r#"<pageMargins left="0.7" right="0.7" top="0.75" bottom="0.75" header="0.3" footer="0.3"/></worksheet>"#,
+        );
+        Ok(xml)
+    }
+
+    /// Relationships of the external hyperlinks, numbered like `get_sheet_tail`.
+    fn get_sheet_rels(&self) -> Result<Option<String>, Box<dyn std::error::Error>> {
+        let mut targets = self
+            .hyperlinks
+            .iter()
+            .filter(|(_, url)| !url.starts_with("internal:"))
+            .peekable();
+        if targets.peek().is_none() {
+            return Ok(None);
+        }
+        let mut xml = String::from(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">"#,
+        );
+        for (index, (_, url)) in targets.enumerate() {
+            write!(
+                xml,
+                r#"<Relationship Id="rId{}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/hyperlink" Target="{}" TargetMode="External"/>"#,
+                index + 1,
+                escape_xml(url)
+            )?;
+        }
+        xml.push_str("</Relationships>");
+        Ok(Some(xml))
+    }
+}
+
+#[cfg(feature = "wasm")]
+#[wasm_bindgen]
+impl ExcelStreamWriter {
+    #[wasm_bindgen(constructor)]
+    pub fn bind_new(info: ExcelInfo) -> Result<ExcelStreamWriter, JsValue> {
+        ExcelStreamWriter::new(info).map_err(|e| error_to_js_value(e.as_ref()))
+    }
+
+    #[wasm_bindgen(js_name = writeRows)]
+    pub fn bind_write_rows(&mut self, rows: Vec<ExcelRowData>) -> Result<(), JsValue> {
+        self.write_rows(&rows)
+            .map_err(|e| error_to_js_value(e.as_ref()))
+    }
+
+    #[wasm_bindgen(js_name = finish)]
+    pub fn bind_finish(&mut self) -> Result<Vec<u8>, JsValue> {
+        self.finish().map_err(|e| error_to_js_value(e.as_ref()))
+    }
+}
+
+/// Exports `data` through an [`ExcelStreamWriter`] in a single call.
+pub fn export_data_streaming_buffer(
+    info: ExcelInfo,
+    data: &ExcelData,
+) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let mut writer = ExcelStreamWriter::new(info)?;
+    writer.write_rows(&data.rows)?;
+    writer.finish()
+}
+
+/// Sheet xml, hyperlinks and merged ranges rendered for one batch of rows.
+#[derive(Default)]
+struct RowsXml {
+    xml: String,
+    hyperlinks: Vec<(String, String)>,
+    merged_ranges: Vec<String>,
+}
+
+/// The columns a batch of rows is laid out with. `data_groups` maps each
+/// group column to the `dataGroup` of its child rows.
+struct RowLayout<'a> {
+    leaf_columns: HashMap<&'a str, (&'a ExcelColumnPosition, &'a ExcelColumnInfo)>,
+    formula_columns: Vec<FormulaColumn<'a>>,
+    data_groups: HashMap<&'a str, &'a str>,
+}
+
+/// A cell of an exported row, merged over rows `y1..=y2` when they differ.
+struct RowCell<'a> {
+    position: &'a ExcelColumnPosition,
+    value: RowCellValue<'a>,
+    y1: u32,
+    y2: u32,
+}
+
+enum RowCellValue<'a> {
+    Data {
+        column_data: &'a ExcelColumnData,
+        column: &'a ExcelColumnInfo,
+        link_text: Option<&'a str>,
+    },
+    Formula(String),
+}
+
+impl<'a> RowLayout<'a> {
+    /// Lays out `row` of the `data_group` level from row `y` like the regular
+    /// export: child rows stack below each other, root values and formula
+    /// cells merge over the rows of their block. Returns the last row used.
+    fn lay_out_row(
+        &self,
+        row: &'a ExcelRowData,
+        data_group: &str,
+        y: u32,
+        cells: &mut Vec<RowCell<'a>>,
+    ) -> u32 {
+        let mut last_row = y;
+        for column_data in row.columns.iter() {
+            let Some(child_group) = self.data_groups.get(column_data.key.as_str()) else {
+                continue;
+            };
+            let mut child_y = y;
+            for child in column_data.children.iter() {
+                let child_last_row = self.lay_out_row(child, child_group, child_y, cells);
+                self.push_formula_cells(child_group, child_y, child_last_row, cells);
+                child_y = child_last_row + 1;
+            }
+            last_row = last_row.max(child_y.saturating_sub(1));
+        }
+        for column_data in row.columns.iter() {
+            let Some(&(position, column)) = self.leaf_columns.get(column_data.key.as_str()) else {
+                continue;
+            };
+            // Values of nested rows stay in their first row, as in regular exports.
+            let y2 = if data_group.is_empty() || !column_data.children.is_empty() {
+                last_row
+            } else {
+                y
+            };
+            cells.push(RowCell {
+                position,
+                value: RowCellValue::Data {
+                    column_data,
+                    column,
+                    link_text: get_link_text(row.columns.iter(), column),
+                },
+                y1: y,
+                y2,
+            });
+        }
+        if data_group.is_empty() {
+            self.push_formula_cells("", y, last_row, cells);
+        }
+        last_row
+    }
+
+    fn push_formula_cells(&self, data_group: &str, y1: u32, y2: u32, cells: &mut Vec<RowCell<'a>>) {
+        for formula_column in self
+            .formula_columns
+            .iter()
+            .filter(|formula_column| formula_column.column.data_group_parent == data_group)
+        {
+            cells.push(RowCell {
+                position: formula_column.position,
+                value: RowCellValue::Formula(formula_column.get_formula(data_group, y1, y2)),
+                y1,
+                y2,
+            });
+        }
+    }
+}
+
+/// Fails on the options only regular exports write, so a streamed file never
+/// drops them silently.
+fn check_stream_options(info: &ExcelInfo) -> Result<(), Box<dyn std::error::Error>> {
+    if info.title_format.is_some() {
+        return Err(get_unsupported_option_error("titleFormat", None));
+    }
+    if !info.row_formats.is_empty() {
+        return Err(get_unsupported_option_error("rowFormats", None));
+    }
+    if info.table.is_some() {
+        return Err(get_unsupported_option_error("table", None));
+    }
+    if info.summary.is_some() {
+        return Err(get_unsupported_option_error("summary", None));
+    }
+    for column in info.columns.iter() {
+        let option = if column.format.is_some() {
+            "format"
+        } else if !column.value_format.is_empty() {
+            "valueFormat"
+        } else if column.note.is_some() {
+            "note"
+        } else if column.data_type.eq_ignore_ascii_case("image") {
+            "image"
+        } else if !column.conditional_formats.is_empty() {
+            "conditionalFormats"
+        } else {
+            continue;
+        };
+        return Err(get_unsupported_option_error(option, Some(&column.key)));
+    }
+    Ok(())
+}
+
+fn get_unsupported_option_error(
+    option: &str,
+    column_key: Option<&str>,
+) -> Box<dyn std::error::Error> {
+    let mut params = vec![("option", option.to_string())];
+    let message = match column_key {
+        Some(column_key) => {
+            params.push(("columnKey", column_key.to_string()));
+            format!(
+                "Streaming export does not support '{}' on column '{}'",
+                option, column_key
+            )
+        }
+        None => format!("Streaming export does not support '{}'", option),
+    };
+    create_structured_error(message, "STREAM_OPTION_UNSUPPORTED", &params).into()
+}
+
+fn write_data_cell(
+    rows_xml: &mut RowsXml,
+    x: u16,
+    y: u32,
+    column_data: &ExcelColumnData,
+    column: &ExcelColumnInfo,
+    link_text: Option<&str>,
+    column_style: Option<u32>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let xml = &mut rows_xml.xml;
+    if let Some((url, text)) = get_export_link(column_data, column, link_text)? {
+        write_string_cell(xml, x, y, &text, STYLE_LINK);
+        rows_xml.hyperlinks.push((get_excel_cell_ref(x, y), url));
+        return Ok(());
+    }
+    if let Some(date_time) = get_export_date(column_data, column)? {
+        let style = column_style.unwrap_or(STYLE_DATE);
+        write_number_cell(xml, x, y, date_time_to_excel(&date_time), style);
+        return Ok(());
+    }
+    let number_style = column_style.unwrap_or(STYLE_DEFAULT);
+    match column_data.typed_value() {
+        // Links and dates of date columns were written above.
+        ExcelValue::Empty | ExcelValue::Link { .. } => {}
+        ExcelValue::Number(number) => write_number_cell(xml, x, y, *number, number_style),
+        ExcelValue::Bool(boolean) => write_boolean_cell(xml, x, y, *boolean, column),
+        ExcelValue::DateTime(date_time) => {
+            write_number_cell(xml, x, y, date_time_to_excel(date_time), STYLE_DATE)
+        }
+        ExcelValue::Text(value) | ExcelValue::Error(value) => {
+            if value.trim().is_empty() {
+                write_string_cell(xml, x, y, value, STYLE_DEFAULT);
+            } else if column.data_type.eq_ignore_ascii_case("number") {
+                let number = parse_export_number(value, column)?;
+                write_number_cell(xml, x, y, number, number_style);
+            } else if is_boolean_data_type(&column.data_type) {
+                let boolean = parse_export_boolean(value, column)?;
+                write_boolean_cell(xml, x, y, boolean, column);
+            } else {
+                write_string_cell(xml, x, y, value, STYLE_DEFAULT);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Renders the same validation `get_data_validation` adds to regular exports.
+fn get_data_validation_xml(column: &ExcelColumnInfo, sqref: &str) -> Option<String> {
+    let constraints = column.constraints.as_ref();
+    let (kind, operator, formulas) = if let Some(options) = get_list_options(column) {
+        ("list", "", vec![format!("\"{}\"", options.join(","))])
+    } else if let Some(constraints) = constraints.filter(|constraints| constraints.has_range()) {
+        let (operator, formulas) = get_validation_rule(
+            constraints.min.map(|min| min.to_string()),
+            constraints.max.map(|max| max.to_string()),
+        );
+        ("decimal", operator, formulas)
+    } else if let Some(constraints) = constraints.filter(|constraints| constraints.has_length()) {
+        let (operator, formulas) = get_validation_rule(
+            constraints.min_length.map(|min| min.to_string()),
+            constraints.max_length.map(|max| max.to_string()),
+        );
+        ("textLength", operator, formulas)
+    } else if column.required || constraints.is_some() {
+        ("", "", Vec::new())
+    } else {
+        return None;
+    };
+    let mut xml = String::from("<dataValidation");
+    if !kind.is_empty() {
+        let _ = write!(xml, r#" type="{}""#, kind);
+    }
+    if !operator.is_empty() {
+        let _ = write!(xml, r#" operator="{}""#, operator);
+    }
+    if !column.required {
+        xml.push_str(r#" allowBlank="1""#);
+    }
+    xml.push_str(r#" showInputMessage="1" showErrorMessage="1""#);
+    if let Some((input_message, error_message)) = get_validation_messages(column) {
+        let title = escape_xml(&get_validation_title(column));
+        if !error_message.is_empty() {
+            let _ = write!(
+                xml,
+                r#" errorTitle="{}" error="{}""#,
+                title,
+                escape_xml(&error_message)
+            );
+        }
+        if !input_message.is_empty() {
+            let _ = write!(
+                xml,
+                r#" promptTitle="{}" prompt="{}""#,
+                title,
+                escape_xml(&input_message)
+            );
+        }
+    }
+    let _ = write!(xml, r#" sqref="{}">"#, sqref);
+    for (index, formula) in formulas.iter().enumerate() {
+        let _ = write!(
+            xml,
+            "<formula{0}>{1}</formula{0}>",
+            index + 1,
+            escape_xml(formula)
+        );
+    }
+    xml.push_str("</dataValidation>");
+    Some(xml)
+}
+
+fn get_validation_rule(min: Option<String>, max: Option<String>) -> (&'static str, Vec<String>) {
+    match (min, max) {
+        (Some(min), Some(max)) => ("between", vec![min, max]),
+        (Some(min), None) => ("greaterThanOrEqual", vec![min]),
+        (None, max) => ("lessThanOrEqual", max.into_iter().collect()),
+    }
+}
+
+fn write_boolean_cell(xml: &mut String, x: u16, y: u32, boolean: bool, column: &ExcelColumnInfo) {
+    if is_boolean_data_type(&column.data_type) && column.get_boolean_labels().is_some() {
+        let label = get_boolean_options(column)[usize::from(!boolean)];
+        write_string_cell(xml, x, y, label, STYLE_DEFAULT);
+        return;
+    }
+    let _ = write!(
+        xml,
+        r#"<c r="{}" s="{}" t="b"><v>{}</v></c>"#,
+        get_excel_cell_ref(x, y),
+        STYLE_DEFAULT,
+        u8::from(boolean)
+    );
+}
+
+fn write_formula_cell(xml: &mut String, x: u16, y: u32, formula: &str, style: u32) {
+    let _ = write!(
+        xml,
+        r#"<c r="{}" s="{}"><f>{}</f></c>"#,
+        get_excel_cell_ref(x, y),
+        style,
+        escape_xml(formula.strip_prefix('=').unwrap_or(formula))
+    );
+}
+
+fn write_number_cell(xml: &mut String, x: u16, y: u32, number: f64, style: u32) {
+    if !number.is_finite() {
+        write_string_cell(xml, x, y, &number.to_string(), style);
+        return;
+    }
+    let _ = write!(
+        xml,
+        r#"<c r="{}" s="{}"><v>{}</v></c>"#,
+        get_excel_cell_ref(x, y),
+        style,
+        number
+    );
+}
+
+fn write_string_cell(xml: &mut String, x: u16, y: u32, text: &str, style: u32) {
+    let space = if text.trim() != text {
+        r#" xml:space="preserve""#
+    } else {
+        ""
+    };
+    let _ = write!(
+        xml,
+        r#"<c r="{}" s="{}" t="inlineStr"><is><t{}>{}</t></is></c>"#,
+        get_excel_cell_ref(x, y),
+        style,
+        space,
+        escape_xml(text)
+    );
+}
+
+/// Builds the stylesheet and the style of every column with a `numberFormat`
+/// or a date pattern.
+fn get_styles_xml(info: &ExcelInfo) -> (String, HashMap<String, u32>) {
+    let mut patterns: Vec<String> = Vec::new();
+    let mut column_styles = HashMap::new();
+    for column in info.columns.iter() {
+        let pattern = match get_date_cell_pattern(column) {
+            Some(pattern) => pattern,
+            None if is_date_data_type(&column.data_type) => continue,
+            None => match column.number_format.as_ref() {
+                Some(number_format) => number_format.get_pattern(),
+                None => continue,
+            },
+        };
+        if pattern == "General" {
+            continue;
+        }
+        let index = match patterns.iter().position(|existing| *existing == pattern) {
+            Some(index) => index,
+            None => {
+                patterns.push(pattern);
+                patterns.len() - 1
+            }
+        };
+        column_styles.insert(column.key.clone(), STYLE_LINK + 1 + index as u32);
+    }
+
+    let mut num_fmts = String::from(r#"<numFmt numFmtId="164" formatCode="yyyy\-mm\-dd"/>"#);
+    let mut cell_xfs = String::from(CELL_XFS_XML);
+    for (index, pattern) in patterns.iter().enumerate() {
+        let num_fmt_id = NUMBER_FORMAT_ID + index as u32;
+        let _ = write!(
+            num_fmts,
+            r#"<numFmt numFmtId="{}" formatCode="{}"/>"#,
+            num_fmt_id,
+            escape_xml(pattern)
+        );
+        let _ = write!(
+            cell_xfs,
+            r#"<xf numFmtId="{}" fontId="0" fillId="0" borderId="0" xfId="0" applyNumberFormat="1" applyAlignment="1"><alignment vertical="center" wrapText="1"/></xf>"#,
+            num_fmt_id
+        );
+    }
+    let styles_xml = format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<styleSheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><numFmts count="{}">{}</numFmts><fonts count="3"><font><sz val="11"/><color theme="1"/><name val="Calibri"/><family val="2"/><scheme val="minor"/></font><font><b/><sz val="11"/><color theme="1"/><name val="Calibri"/><family val="2"/><scheme val="minor"/></font><font><u/><sz val="11"/><color theme="10"/><name val="Calibri"/><family val="2"/><scheme val="minor"/></font></fonts><fills count="2"><fill><patternFill patternType="none"/></fill><fill><patternFill patternType="gray125"/></fill></fills><borders count="1"><border><left/><right/><top/><bottom/><diagonal/></border></borders><cellStyleXfs count="1"><xf numFmtId="0" fontId="0" fillId="0" borderId="0"/></cellStyleXfs><cellXfs count="{}">{}</cellXfs><cellStyles count="1"><cellStyle name="Normal" xfId="0" builtinId="0"/></cellStyles></styleSheet>"#,
+        patterns.len() + 1,
+        num_fmts,
+        STYLE_LINK as usize + 1 + patterns.len(),
+        cell_xfs
+    );
+    (styles_xml, column_styles)
+}
+
+fn get_core_xml(info: &ExcelInfo) -> String {
+    let created = parse_date_time_text(&info.create_time)
+        .map(|date_time| {
+            format!(
+                r#"<dcterms:created xsi:type="dcterms:W3CDTF">{}</dcterms:created>"#,
+                date_time.format("%Y-%m-%dT%H:%M:%SZ")
+            )
+        })
+        .unwrap_or_default();
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<cp:coreProperties xmlns:cp="http://schemas.openxmlformats.org/package/2006/metadata/core-properties" xmlns:dc="http://purl.org/dc/elements/1.1/" xmlns:dcterms="http://purl.org/dc/terms/" xmlns:dcmitype="http://purl.org/dc/dcmitype/" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance"><dc:title>{}</dc:title><dc:creator>{}</dc:creator>{}</cp:coreProperties>"#,
+        escape_xml(&info.name),
+        escape_xml(&info.author),
+        created
+    )
+}
+
+fn get_workbook_xml(info: &ExcelInfo) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><bookViews><workbookView/></bookViews><sheets><sheet name="{}" sheetId="1" r:id="rId1"/></sheets><calcPr fullCalcOnLoad="1"/></workbook>"#,
+        escape_xml(&info.sheet_name)
+    )
+}
+
+// Mirrors rust_xlsxwriter, which stores widths in character units plus cell padding.
+fn get_column_width(width: f64) -> f64 {
+    if width <= 0.0 {
+        return 0.0;
+    }
+    let pixels = if width < 1.0 {
+        (width * 12.0 + 0.5).trunc()
+    } else {
+        (width * 7.0 + 0.5).trunc() + 5.0
+    };
+    (pixels / 7.0 * 256.0).trunc() / 256.0
+}
+
+fn escape_xml(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for character in value.chars() {
+        match character {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\t' | '\n' | '\r' => escaped.push(character),
+            character if character.is_control() => {}
+            character => escaped.push(character),
+        }
+    }
+    escaped
+}
+
+fn get_finished_error() -> Box<dyn std::error::Error> {
+    create_structured_error(
+        "Stream writer has already been finished",
+        "STREAM_WRITER_FINISHED",
+        &[],
+    )
+    .into()
+}
@@ -1,14 +1,35 @@
 #[cfg(test)]
+#[allow(clippy::module_inception)]
 mod tests {
+    #[cfg(feature = "cli")]
+    use crate::cli;
+    use crate::csv_data::{export_csv_buffer, import_csv_buffer, import_dynamic_csv_buffer};
+    use crate::stream_writer::{export_data_streaming_buffer, ExcelStreamWriter};
     use crate::{
-        create_template_buffer, excel_structs::*, export_data_buffer, import_data_buffer,
-        import_dynamic_data_buffer, validate_image_data,
+        create_structured_error, create_template_buffer, excel_structs::*, export_data_buffer,
+        export_workbook_buffer, import_all_sheets_data_buffer, import_data_buffer,
+        import_data_in_batches_buffer, import_data_with_report_buffer, import_dynamic_data_buffer,
+        import_grouped_data_buffer, infer_excel_info_buffer, validate_image_data, ImageFetchFuture,
+        ImageFetcher, ImportExportError,
     };
+    use calamine::Reader;
+    use std::sync::{Arc, Mutex};
 
+    use csv_options::*;
+    use excel_auto_width::*;
     use excel_column_data::*;
+    use excel_conditional_format::*;
+    use excel_constraints::*;
     use excel_data::*;
+    use excel_date_options::*;
     use excel_info::*;
+    use excel_number_format::*;
     use excel_row_data::*;
+    use excel_row_format::*;
+    use excel_summary_options::*;
+    use excel_table_options::*;
+    use excel_value::*;
+    use excel_workbook::*;
     use insta::{assert_binary_snapshot, assert_snapshot};
 
     fn create_excel_info() -> ExcelInfo {
@@ -133,6 +154,197 @@ mod tests {
         assert!(result.rows.is_empty());
     }
 
+    #[test]
+    fn import_pokemon_by_sheet_name_reads_named_sheet() {
+        // Arrange
+        let (mut workbook, _) = crate::create_template_workbook(&create_excel_info()).unwrap();
+        let first_sheet = workbook.worksheet_from_index(0).unwrap();
+        first_sheet.write_string(1, 0, "#004").unwrap();
+        let second_sheet = workbook.add_worksheet();
+        second_sheet.set_name("Johto Pokédex").unwrap();
+        for (index, column) in create_excel_info().columns.iter().enumerate() {
+            second_sheet
+                .write_string(0, index as u16, &column.name)
+                .unwrap();
+        }
+        second_sheet.write_string(1, 0, "#152").unwrap();
+        let excel_bytes = workbook.save_to_buffer().unwrap();
+        let mut import_info = create_excel_info().with_import_by_sheet_name(true);
+        import_info.sheet_name = "Johto Pokédex".into();
+
+        // Act
+        let result = import_data_buffer(import_info, &excel_bytes);
+
+        // Assert
+        assert!(result.is_ok());
+        let result = result.unwrap();
+        assert_eq!(result.rows.len(), 1);
+        assert_eq!(result.rows[0].columns[0].value(), "#152");
+        assert_eq!(result.rows[0].sheet_name.as_deref(), Some("Johto Pokédex"));
+    }
+
+    #[test]
+    fn import_pokemon_by_sheet_name_missing_sheet_fails() {
+        // Arrange
+        let excel_bytes = create_template_buffer(&create_excel_info()).unwrap();
+        let mut import_info = create_excel_info().with_import_by_sheet_name(true);
+        import_info.sheet_name = "Kanto Pokédex".into();
+
+        // Act
+        let result = import_data_buffer(import_info, &excel_bytes);
+
+        // Assert
+        assert!(result.is_err());
+        assert!(result
+            .err()
+            .unwrap()
+            .to_string()
+            .contains("Worksheet 'Kanto Pokédex' not found"));
+    }
+
+    #[tokio::test]
+    async fn import_pokemon_all_sheets_tags_rows_with_sheet_name() {
+        // Arrange
+        let create_sheet = |sheet_name: &str, number: &str| {
+            let mut info = create_excel_info();
+            info.sheet_name = sheet_name.into();
+            let data = ExcelData::new(vec![ExcelRowData::new(vec![
+                ExcelColumnData::new("number", number),
+                ExcelColumnData::new("name", "Pokémon"),
+            ])]);
+            (info, data)
+        };
+        let (kanto_info, kanto_data) = create_sheet("Kanto", "#001");
+        let (johto_info, johto_data) = create_sheet("Johto", "#152");
+        let notes_info = ExcelInfo::new(
+            "Notes",
+            "Notes",
+            vec![ExcelColumnInfo::new("note", "Note")],
+            "senlinz",
+            "2024-11-01T08:00:00",
+        )
+        .expect("test schema should be valid");
+        let notes_data = ExcelData::new(vec![ExcelRowData::new(vec![ExcelColumnData::new(
+            "note", "skipped",
+        )])]);
+        let workbook = ExcelWorkbook::new()
+            .with_sheet(kanto_info, kanto_data)
+            .with_sheet(notes_info, notes_data)
+            .with_sheet(johto_info, johto_data);
+        let excel_bytes = export_workbook_buffer(&workbook).await.unwrap();
+
+        // Act
+        let result = import_all_sheets_data_buffer(create_excel_info(), &excel_bytes);
+
+        // Assert
+        assert!(result.is_ok());
+        let result = result.unwrap();
+        assert_eq!(result.rows.len(), 2);
+        assert_eq!(result.rows[0].columns[0].value(), "#001");
+        assert_eq!(result.rows[0].sheet_name.as_deref(), Some("Kanto"));
+        assert_eq!(result.rows[1].columns[0].value(), "#152");
+        assert_eq!(result.rows[1].sheet_name.as_deref(), Some("Johto"));
+    }
+
+    #[test]
+    fn import_pokemon_all_sheets_without_match_fails() {
+        // Arrange
+        let excel_bytes = create_template_buffer(&create_excel_info()).unwrap();
+        let mut import_info = create_excel_info();
+        import_info.columns[0].name = "No.".into();
+
+        // Act
+        let result = import_all_sheets_data_buffer(import_info, &excel_bytes);
+
+        // Assert
+        assert!(result.is_err());
+        assert!(result
+            .err()
+            .unwrap()
+            .to_string()
+            .contains("No worksheet in the workbook matches"));
+    }
+
+    fn create_trainer_excel_info() -> ExcelInfo {
+        ExcelInfo::new(
+            "Trainers",
+            "sheet1",
+            vec![
+                ExcelColumnInfo::new("name", "Name").with_required(true),
+                ExcelColumnInfo::new("age", "Age").with_data_type("number"),
+                ExcelColumnInfo::new("joined_on", "Joined On").with_data_type("date"),
+                ExcelColumnInfo::new("category", "Category")
+                    .with_allowed_values(vec!["Cat".into(), "Mouse".into()]),
+            ],
+            "senlinz",
+            "2024-11-01T08:00:00",
+        )
+        .expect("test schema should be valid")
+    }
+
+    #[test]
+    fn import_with_report_collects_every_invalid_cell() {
+        // Arrange
+        let (mut workbook, _) =
+            crate::create_template_workbook(&create_trainer_excel_info()).unwrap();
+        let worksheet = workbook.worksheet_from_index(0).unwrap();
+        worksheet.write_string(1, 0, "Tom").unwrap();
+        worksheet.write_number(1, 1, 12).unwrap();
+        worksheet.write_string(1, 2, "2024-11-01").unwrap();
+        worksheet.write_string(1, 3, "Cat").unwrap();
+        worksheet.write_string(2, 1, "twelve").unwrap();
+        worksheet.write_string(2, 2, "someday").unwrap();
+        worksheet.write_string(2, 3, "Dog").unwrap();
+        worksheet.write_string(4, 0, "Jerry").unwrap();
+        let excel_bytes = workbook.save_to_buffer().unwrap();
+
+        // Act
+        let result = import_data_with_report_buffer(create_trainer_excel_info(), &excel_bytes);
+
+        // Assert
+        assert!(result.is_ok());
+        let result = result.unwrap();
+        assert!(!result.is_valid());
+        assert_eq!(result.data.rows.len(), 4);
+        let issues = result
+            .issues
+            .iter()
+            .map(|issue| format!("{:?}", issue))
+            .collect::<Vec<String>>();
+        assert_eq!(
+            issues,
+            vec![
+                "sheet1!A3 [name] CELL_REQUIRED: ",
+                "sheet1!B3 [age] CELL_NUMBER_INVALID: twelve",
+                "sheet1!C3 [joined_on] CELL_DATE_INVALID: someday",
+                "sheet1!D3 [category] CELL_VALUE_NOT_ALLOWED: Dog",
+            ]
+        );
+        assert_eq!(result.issues[0].row, 3);
+    }
+
+    #[test]
+    fn import_with_report_collects_every_header_mismatch() {
+        // Arrange
+        let excel_bytes = create_template_buffer(&create_trainer_excel_info()).unwrap();
+        let mut import_info = create_trainer_excel_info();
+        import_info.columns[0].name = "Trainer".into();
+        import_info.columns[3].name = "Kind".into();
+
+        // Act
+        let result = import_data_with_report_buffer(import_info, &excel_bytes);
+
+        // Assert
+        assert!(result.is_ok());
+        let result = result.unwrap();
+        assert_eq!(result.issues.len(), 2);
+        assert_eq!(result.issues[0].code, "HEADER_MISMATCH");
+        assert_eq!(result.issues[0].cell, "A1");
+        assert_eq!(result.issues[0].value, "Name");
+        assert_eq!(result.issues[1].cell, "D1");
+        assert_eq!(result.issues[1].column_key, "category");
+    }
+
     #[test]
     fn import_pokemon_missing_header_fails() {
         // Arrange
@@ -172,7 +384,7 @@ mod tests {
         ])]);
         let excel_bytes = export_data_buffer(&info, &data).await.unwrap();
 
-        let result = import_dynamic_data_buffer(None, None, &excel_bytes);
+        let result = import_dynamic_data_buffer(None, None, &excel_bytes, None);
 
         assert!(result.is_ok());
         let result = result.unwrap();
@@ -196,9 +408,9 @@ mod tests {
         );
         assert_eq!(result.rows.len(), 1);
         assert_eq!(result.rows[0].columns[0].key, "Number");
-        assert_eq!(result.rows[0].columns[0].value, "#001");
+        assert_eq!(result.rows[0].columns[0].value(), "#001");
         assert_eq!(result.rows[0].columns[1].key, "Name");
-        assert_eq!(result.rows[0].columns[1].value, "Bulbasaur");
+        assert_eq!(result.rows[0].columns[1].value(), "Bulbasaur");
     }
 
     #[tokio::test]
@@ -220,12 +432,12 @@ mod tests {
         ])]);
         let excel_bytes = export_data_buffer(&info, &data).await.unwrap();
 
-        let result = import_dynamic_data_buffer(None, Some(3), &excel_bytes);
+        let result = import_dynamic_data_buffer(None, Some(3), &excel_bytes, None);
 
         assert!(result.is_ok());
         let result = result.unwrap();
         assert_eq!(result.headers[0], "Number");
-        assert_eq!(result.rows[0].columns[0].value, "#001");
+        assert_eq!(result.rows[0].columns[0].value(), "#001");
     }
 
     #[test]
@@ -281,6 +493,100 @@ mod tests {
             .contains("unsupported dataType 'string'"));
     }
 
+    #[test]
+    fn excel_info_from_json_reads_camel_case_schema() {
+        // Arrange
+        let json = r##"{
+            "name": "Pokemon",
+            "columns": [
+                { "key": "name", "name": "Name", "note": "Species name" },
+                {
+                    "key": "hp",
+                    "name": "HP",
+                    "dataType": "number",
+                    "width": 6,
+                    "valueFormat": { "rule": "eq", "value": "100", "bold": true, "fontSize": 14 }
+                },
+                { "key": "type", "name": "Type", "allowedValues": ["Fire", "Water"], "required": true }
+            ],
+            "titleFormat": { "backgroundColor": "#FF0000", "alignVertical": "center" },
+            "isHeaderFreeze": true,
+            "dy": 2
+        }"##;
+
+        // Act
+        let result = ExcelInfo::from_json(json);
+
+        // Assert
+        let info = result.unwrap();
+        assert_eq!(info.sheet_name, "sheet1");
+        assert_eq!(info.dy, 2);
+        assert!(info.is_header_freeze);
+        let title_format = info.title_format.as_ref().unwrap();
+        assert_eq!(title_format.background_color, "#FF0000");
+        assert_eq!(title_format.align_vertical, "center");
+        assert_eq!(title_format.color, "black");
+        assert_eq!(info.columns[0].note.as_deref(), Some("Species name"));
+        assert_eq!(info.columns[0].data_type, "text");
+        assert_eq!(info.columns[0].width, 10.0);
+        assert_eq!(info.columns[1].width, 6.0);
+        assert_eq!(info.columns[1].value_format.len(), 1);
+        assert!(info.columns[1].value_format[0].bold);
+        assert_eq!(info.columns[1].value_format[0].font_size, 14.0);
+        assert_eq!(info.columns[2].allowed_values, vec!["Fire", "Water"]);
+        assert!(info.columns[2].required);
+    }
+
+    #[test]
+    fn excel_info_json_round_trip_keeps_template_output() {
+        // Arrange
+        let json = create_excel_info().to_json().unwrap();
+
+        // Act
+        let info = ExcelInfo::from_json(&json).unwrap();
+        let result = create_template_buffer(&info);
+
+        // Assert
+        assert_eq!(
+            result.unwrap(),
+            create_template_buffer(&create_excel_info()).unwrap()
+        );
+    }
+
+    #[test]
+    fn excel_info_from_json_validates_columns() {
+        // Arrange
+        let json = r#"{
+            "name": "Broken",
+            "columns": [
+                { "key": "name", "name": "Name" },
+                { "key": "name", "name": "Alias" }
+            ]
+        }"#;
+
+        // Act
+        let result = ExcelInfo::from_json(json);
+
+        // Assert
+        assert!(result.is_err());
+        assert!(result
+            .err()
+            .unwrap()
+            .contains("duplicate column key 'name'"));
+    }
+
+    #[test]
+    fn excel_info_from_json_with_missing_columns_fails() {
+        // Act
+        let result = ExcelInfo::from_json(r#"{ "name": "Broken" }"#);
+
+        // Assert
+        assert!(result.is_err());
+        let error = result.err().unwrap();
+        assert!(error.starts_with("Invalid definition:"));
+        assert!(error.contains("missing field `columns`"));
+    }
+
     #[test]
     fn create_pokemon_template_success() {
         // Arrange
@@ -326,6 +632,7 @@ mod tests {
                 ExcelColumnData::new("speed", "45"),
                 ExcelColumnData::new("total", "318"),
             ])],
+            column_bindings: Vec::new(),
         };
 
         // Act
@@ -337,6 +644,98 @@ mod tests {
         assert_binary_snapshot!("export_pokemon_success.xlsx", result);
     }
 
+    #[tokio::test]
+    async fn export_and_import_typed_values_success() {
+        // Arrange
+        let info = ExcelInfo::new(
+            "Trainers",
+            "sheet1",
+            vec![
+                ExcelColumnInfo::new("name", "Name"),
+                ExcelColumnInfo::new("age", "Age").with_data_type("number"),
+                ExcelColumnInfo::new("is_active", "Active"),
+                ExcelColumnInfo::new("joined_on", "Joined On").with_data_type("date"),
+                ExcelColumnInfo::new("note", "Note"),
+            ],
+            "senlinz",
+            "2024-11-01T08:00:00",
+        )
+        .expect("test schema should be valid");
+        let joined_on = chrono::NaiveDate::from_ymd_opt(2024, 11, 1)
+            .unwrap()
+            .and_hms_opt(8, 30, 0)
+            .unwrap();
+        let data = ExcelData::new(vec![ExcelRowData::new(vec![
+            ExcelColumnData::new("name", "Tom"),
+            ExcelColumnData::with_typed_value("age", ExcelValue::Number(12.5)),
+            ExcelColumnData::with_typed_value("is_active", ExcelValue::Bool(true)),
+            ExcelColumnData::with_typed_value("joined_on", ExcelValue::DateTime(joined_on)),
+            ExcelColumnData::new("note", ""),
+        ])]);
+        let excel_bytes = export_data_buffer(&info, &data).await.unwrap();
+
+        // Act
+        let result = import_data_buffer(info, &excel_bytes);
+
+        // Assert
+        assert!(result.is_ok());
+        let columns = &result.unwrap().rows[0].columns;
+        assert_eq!(columns[0].typed_value(), &ExcelValue::Text("Tom".into()));
+        assert_eq!(columns[1].typed_value(), &ExcelValue::Number(12.5));
+        assert_eq!(columns[1].value(), "12.5");
+        assert_eq!(columns[2].typed_value(), &ExcelValue::Bool(true));
+        assert_eq!(columns[2].value(), "true");
+        assert_eq!(columns[3].typed_value(), &ExcelValue::DateTime(joined_on));
+        assert_eq!(columns[3].value(), "2024-11-01 08:30:00");
+        assert_eq!(columns[4].typed_value(), &ExcelValue::Empty);
+        assert_eq!(columns[4].value_type(), "empty");
+    }
+
+    #[tokio::test]
+    async fn export_edited_imported_number_writes_new_value() {
+        // Arrange
+        let create_info = || {
+            ExcelInfo::new(
+                "Trainers",
+                "sheet1",
+                vec![ExcelColumnInfo::new("age", "Age").with_data_type("number")],
+                "senlinz",
+                "2024-11-01T08:00:00",
+            )
+            .expect("test schema should be valid")
+        };
+        let data = ExcelData::new(vec![ExcelRowData::new(vec![
+            ExcelColumnData::with_typed_value("age", ExcelValue::Number(12.5)),
+        ])]);
+        let excel_bytes = export_data_buffer(&create_info(), &data).await.unwrap();
+        let mut imported = import_data_buffer(create_info(), &excel_bytes).unwrap();
+        imported.rows[0].columns[0].set_value("13");
+
+        // Act
+        let excel_bytes = export_data_buffer(&create_info(), &imported).await.unwrap();
+
+        // Assert
+        let result = import_data_buffer(create_info(), &excel_bytes).unwrap();
+        assert_eq!(
+            result.rows[0].columns[0].typed_value(),
+            &ExcelValue::Number(13.0)
+        );
+        assert_eq!(result.rows[0].columns[0].value(), "13");
+    }
+
+    #[test]
+    fn set_typed_value_updates_text_value() {
+        // Arrange
+        let mut column_data = ExcelColumnData::new("age", "twelve");
+
+        // Act
+        column_data.set_typed_value(ExcelValue::Number(12.5));
+
+        // Assert
+        assert_eq!(column_data.typed_value(), &ExcelValue::Number(12.5));
+        assert_eq!(column_data.value(), "12.5");
+    }
+
     #[tokio::test]
     async fn export_number_column_with_invalid_value_fails() {
         let info = ExcelInfo::new(
@@ -405,6 +804,7 @@ mod tests {
                 ExcelColumnData::new("speed", "45"),
                 ExcelColumnData::new("total", "318"),
             ])],
+            column_bindings: Vec::new(),
         };
         let excel_bytes = export_data_buffer(&info, &data).await.unwrap();
 
@@ -417,15 +817,13 @@ mod tests {
         assert_eq!(result.rows.len(), 1);
         assert_eq!(result.rows[0].columns.len(), 12);
         assert_eq!(result.rows[0].columns[0].key, "number");
-        assert_eq!(result.rows[0].columns[0].value, "#001");
+        assert_eq!(result.rows[0].columns[0].value(), "#001");
         assert_eq!(result.rows[0].columns[1].key, "name");
-        assert_eq!(result.rows[0].columns[1].value, "Bulbasaur");
+        assert_eq!(result.rows[0].columns[1].value(), "Bulbasaur");
     }
 
-    #[tokio::test]
-    async fn export_pokemon_skill_success() {
-        // Arrange
-        let info = ExcelInfo::new(
+    fn create_skill_excel_info() -> ExcelInfo {
+        ExcelInfo::new(
             "Pokemon",
             "FireRed Pokédex",
             vec![
@@ -460,9 +858,11 @@ mod tests {
             "senlinz",
             "2024-11-01T08:00:00",
         )
-        .expect("test schema should be valid");
+        .expect("test schema should be valid")
+    }
 
-        let data = ExcelData::new(vec![ExcelRowData::new(vec![
+    fn create_skill_excel_data() -> ExcelData {
+        ExcelData::new(vec![ExcelRowData::new(vec![
             ExcelColumnData::new("number", "#001"),
             ExcelColumnData::new("name", "Bulbasaur"),
             ExcelColumnData::new_root_group(
@@ -535,7 +935,14 @@ mod tests {
                     )]),
                 ],
             ),
-        ])]);
+        ])])
+    }
+
+    #[tokio::test]
+    async fn export_pokemon_skill_success() {
+        // Arrange
+        let info = create_skill_excel_info();
+        let data = create_skill_excel_data();
 
         // Act
         let result = export_data_buffer(&info, &data).await;
@@ -546,6 +953,3260 @@ mod tests {
         assert_binary_snapshot!("export_pokemon_skill_success.xlsx", result);
     }
 
+    #[tokio::test]
+    async fn import_pokemon_skill_grouped_round_trip_success() {
+        // Arrange
+        let info = create_skill_excel_info();
+        let excel_bytes = export_data_buffer(&info, &create_skill_excel_data())
+            .await
+            .unwrap();
+
+        // Act
+        let result = import_grouped_data_buffer(create_skill_excel_info(), &excel_bytes);
+
+        // Assert
+        assert!(result.is_ok());
+        let result = result.unwrap();
+        assert_eq!(result.rows.len(), 1);
+        let row = &result.rows[0];
+        assert_eq!(row.sheet_name.as_deref(), Some("FireRed Pokédex"));
+        assert_eq!(row.columns.len(), 3);
+        assert_eq!(row.columns[0].value(), "#001");
+        assert_eq!(row.columns[1].value(), "Bulbasaur");
+        let moves = &row.columns[2];
+        assert_eq!(moves.key, "moves");
+        assert_eq!(moves.children.len(), 2);
+        let level_moves = &moves.children[0].columns[0];
+        assert_eq!(level_moves.key, "moves_by");
+        assert_eq!(level_moves.value(), "Level");
+        assert_eq!(level_moves.children.len(), 4);
+        assert_eq!(level_moves.children[3].columns[0].value(), "10");
+        assert_eq!(level_moves.children[3].columns[1].value(), "Vine Whip");
+        let egg_moves = &moves.children[1].columns[0];
+        assert_eq!(egg_moves.value(), "Egg");
+        assert_eq!(egg_moves.children.len(), 3);
+        assert_eq!(egg_moves.children[0].columns[0].value(), "");
+        assert_eq!(egg_moves.children[2].columns[1].value(), "Nature Power");
+    }
+
+    #[tokio::test]
+    async fn import_pokemon_grouped_without_groups_matches_flat_import() {
+        // Arrange
+        let info = create_excel_info();
+        let data = ExcelData::new(vec![ExcelRowData::new(vec![
+            ExcelColumnData::new("number", "#001"),
+            ExcelColumnData::new("name", "Bulbasaur"),
+        ])]);
+        let excel_bytes = export_data_buffer(&info, &data).await.unwrap();
+
+        // Act
+        let grouped = import_grouped_data_buffer(create_excel_info(), &excel_bytes).unwrap();
+        let flat = import_data_buffer(create_excel_info(), &excel_bytes).unwrap();
+
+        // Assert
+        assert_eq!(format!("{:?}", grouped), format!("{:?}", flat));
+    }
+
+    #[tokio::test]
+    async fn export_workbook_with_multiple_sheets_success() {
+        let orders_info = ExcelInfo::new(
+            "Report",
+            "Orders",
+            vec![
+                ExcelColumnInfo::new("order_no", "Order No."),
+                ExcelColumnInfo::new("amount", "Amount").with_data_type("number"),
+            ],
+            "senlinz",
+            "2024-11-01T08:00:00",
+        )
+        .expect("test schema should be valid");
+        let orders_data = ExcelData::new(vec![
+            ExcelRowData::new(vec![
+                ExcelColumnData::new("order_no", "SO-001"),
+                ExcelColumnData::new("amount", "120.5"),
+            ]),
+            ExcelRowData::new(vec![
+                ExcelColumnData::new("order_no", "SO-002"),
+                ExcelColumnData::new("amount", "80"),
+            ]),
+        ]);
+        let summary_info = ExcelInfo::new(
+            "Report",
+            "Summary",
+            vec![ExcelColumnInfo::new("total", "Total").with_data_type("number")],
+            "senlinz",
+            "2024-11-01T08:00:00",
+        )
+        .expect("test schema should be valid");
+        let summary_data = ExcelData::new(vec![ExcelRowData::new(vec![ExcelColumnData::new(
+            "total", "200.5",
+        )])]);
+        let workbook = ExcelWorkbook::new()
+            .with_sheet(orders_info, orders_data)
+            .with_sheet(summary_info, summary_data);
+
+        let excel_bytes = export_workbook_buffer(&workbook).await.unwrap();
+
+        let orders =
+            import_dynamic_data_buffer(Some("Orders".into()), None, &excel_bytes, None).unwrap();
+        assert_eq!(orders.headers, vec!["Order No.", "Amount"]);
+        assert_eq!(orders.rows.len(), 2);
+        assert_eq!(orders.rows[1].columns[0].value(), "SO-002");
+        let summary =
+            import_dynamic_data_buffer(Some("Summary".into()), None, &excel_bytes, None).unwrap();
+        assert_eq!(summary.sheet_name, "Summary");
+        assert_eq!(summary.headers, vec!["Total"]);
+        assert_eq!(summary.rows[0].columns[0].value(), "200.5");
+    }
+
+    #[tokio::test]
+    async fn export_workbook_duplicate_sheet_name_fails() {
+        let workbook = ExcelWorkbook::new()
+            .with_sheet(create_excel_info(), ExcelData::new(vec![]))
+            .with_sheet(create_excel_info(), ExcelData::new(vec![]));
+
+        let result = export_workbook_buffer(&workbook).await;
+
+        assert!(result.is_err());
+        assert!(result
+            .err()
+            .unwrap()
+            .to_string()
+            .contains("Duplicate sheet 'FireRed Pokédex'"));
+    }
+
+    #[tokio::test]
+    async fn export_workbook_without_sheets_fails() {
+        let result = export_workbook_buffer(&ExcelWorkbook::new()).await;
+
+        assert!(result.is_err());
+        assert!(result
+            .err()
+            .unwrap()
+            .to_string()
+            .contains("at least one sheet"));
+    }
+
+    #[test]
+    fn export_and_import_csv_round_trip_success() {
+        // Arrange
+        let info = create_trainer_excel_info();
+        let data = ExcelData::new(vec![
+            ExcelRowData::new(vec![
+                ExcelColumnData::new("name", "Tom, the cat"),
+                ExcelColumnData::new("age", "12"),
+                ExcelColumnData::new("joined_on", "2024-11-01"),
+                ExcelColumnData::new("category", "Cat"),
+            ]),
+            ExcelRowData::new(vec![
+                ExcelColumnData::new("name", "Jerry"),
+                ExcelColumnData::new_number("age".into(), 3.0),
+            ]),
+        ]);
+
+        // Act
+        let csv_bytes = export_csv_buffer(&info, &data, &CsvOptions::new()).unwrap();
+        let result = import_csv_buffer(create_trainer_excel_info(), &csv_bytes, &CsvOptions::new());
+
+        // Assert
+        assert_eq!(
+            String::from_utf8(csv_bytes).unwrap(),
+            "Name,Age,Joined On,Category\n\"Tom, the cat\",12,2024-11-01,Cat\nJerry,3,,\n"
+        );
+        assert!(result.is_ok());
+        let result = result.unwrap();
+        assert_eq!(result.rows.len(), 2);
+        assert_eq!(result.rows[0].columns[0].value(), "Tom, the cat");
+        assert_eq!(
+            result.rows[0].columns[1].typed_value(),
+            &ExcelValue::Number(12.0)
+        );
+        assert_eq!(result.rows[0].columns[2].value(), "2024-11-01 00:00:00");
+        assert_eq!(result.rows[1].columns[3].typed_value(), &ExcelValue::Empty);
+        assert_eq!(result.rows[1].sheet_name.as_deref(), Some("sheet1"));
+    }
+
+    #[test]
+    fn export_and_import_tsv_with_gbk_encoding_success() {
+        // Arrange
+        let info = ExcelInfo::new(
+            "Pokemon",
+            "sheet1",
+            vec![
+                ExcelColumnInfo::new("name", "名称"),
+                ExcelColumnInfo::new("type", "属性"),
+            ],
+            "senlinz",
+            "2024-11-01T08:00:00",
+        )
+        .expect("test schema should be valid");
+        let data = ExcelData::new(vec![ExcelRowData::new(vec![
+            ExcelColumnData::new("name", "妙蛙种子"),
+            ExcelColumnData::new("type", "草"),
+        ])]);
+        let options = CsvOptions::tsv().with_encoding("gbk");
+
+        // Act
+        let csv_bytes = export_csv_buffer(&info, &data, &options).unwrap();
+        let result = import_csv_buffer(info, &csv_bytes, &options);
+
+        // Assert
+        assert!(std::str::from_utf8(&csv_bytes).is_err());
+        assert!(result.is_ok());
+        let result = result.unwrap();
+        assert_eq!(result.rows[0].columns[0].value(), "妙蛙种子");
+        assert_eq!(result.rows[0].columns[1].value(), "草");
+    }
+
+    #[test]
+    fn import_csv_with_utf8_bom_success() {
+        // Arrange
+        let info = create_trainer_excel_info();
+        let data = ExcelData::new(vec![ExcelRowData::new(vec![ExcelColumnData::new(
+            "name", "Tom",
+        )])]);
+        let csv_bytes = export_csv_buffer(&info, &data, &CsvOptions::new().with_bom(true)).unwrap();
+
+        // Act
+        let result = import_csv_buffer(create_trainer_excel_info(), &csv_bytes, &CsvOptions::new());
+
+        // Assert
+        assert!(csv_bytes.starts_with(&[0xEF, 0xBB, 0xBF]));
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap().rows[0].columns[0].value(), "Tom");
+    }
+
+    #[test]
+    fn import_csv_header_mismatch_fails() {
+        // Arrange
+        let csv_bytes = "Trainer,Age,Joined On,Category\nTom,12,,Cat\n".as_bytes();
+
+        // Act
+        let result = import_csv_buffer(create_trainer_excel_info(), csv_bytes, &CsvOptions::new());
+
+        // Assert
+        assert!(result.is_err());
+        let error = result.err().unwrap().to_string();
+        assert!(error.contains("Header mismatch"));
+        assert!(error.contains("expected 'Name'"));
+    }
+
+    #[test]
+    fn export_csv_flattens_grouped_rows() {
+        // Arrange
+        let info = create_skill_excel_info();
+        let data = create_skill_excel_data();
+
+        // Act
+        let csv_bytes = export_csv_buffer(&info, &data, &CsvOptions::new()).unwrap();
+        let result = import_dynamic_csv_buffer(None, &csv_bytes, &CsvOptions::new());
+
+        // Assert
+        assert!(result.is_ok());
+        let result = result.unwrap();
+        assert_eq!(result.rows.len(), 7);
+        assert_eq!(result.rows[0].columns[0].value(), "#001");
+        assert_eq!(result.rows[6].columns[0].value(), "#001");
+        assert_eq!(result.rows[6].columns[2].value(), "Egg");
+    }
+
+    #[test]
+    fn import_dynamic_csv_with_explicit_header_row_success() {
+        // Arrange
+        let csv_bytes = "Pokemon\nNumber;Name\n#001;Bulbasaur\n".as_bytes();
+
+        // Act
+        let result =
+            import_dynamic_csv_buffer(Some(2), csv_bytes, &CsvOptions::new().with_delimiter(";"));
+
+        // Assert
+        assert!(result.is_ok());
+        let result = result.unwrap();
+        assert_eq!(result.headers, vec!["Number", "Name"]);
+        assert_eq!(result.rows[0].columns[1].value(), "Bulbasaur");
+    }
+
+    #[test]
+    fn import_csv_with_invalid_delimiter_fails() {
+        // Act
+        let result = import_dynamic_csv_buffer(
+            None,
+            "Name\n".as_bytes(),
+            &CsvOptions::new().with_delimiter("::"),
+        );
+
+        // Assert
+        assert!(result.is_err());
+        assert!(result
+            .err()
+            .unwrap()
+            .to_string()
+            .contains("'delimiter' must be a single ASCII character"));
+    }
+
+    #[test]
+    fn import_ods_detected_from_magic_bytes_success() {
+        // Arrange
+        let ods_bytes: &[u8] = include_bytes!("./fixtures/trainers.ods");
+
+        // Act
+        let result = import_data_buffer(create_trainer_excel_info(), ods_bytes);
+
+        // Assert
+        assert!(result.is_ok());
+        let result = result.unwrap();
+        assert_eq!(result.rows.len(), 2);
+        assert_eq!(result.rows[0].columns[0].value(), "Tom");
+        assert_eq!(
+            result.rows[0].columns[1].typed_value(),
+            &ExcelValue::Number(12.0)
+        );
+        assert_eq!(result.rows[0].columns[2].value_type(), "date");
+        assert_eq!(result.rows[1].columns[3].value(), "Mouse");
+        assert_eq!(result.rows[1].sheet_name.as_deref(), Some("Trainers"));
+    }
+
+    #[test]
+    fn import_dynamic_ods_with_explicit_format_success() {
+        // Arrange
+        let ods_bytes: &[u8] = include_bytes!("./fixtures/trainers.ods");
+
+        // Act
+        let result = import_dynamic_data_buffer(None, None, ods_bytes, Some("ods"));
+
+        // Assert
+        assert!(result.is_ok());
+        let result = result.unwrap();
+        assert_eq!(result.sheet_name, "Trainers");
+        assert_eq!(result.headers, vec!["Name", "Age", "Joined On", "Category"]);
+        assert_eq!(result.rows[1].columns[0].value(), "Jerry");
+    }
+
+    #[test]
+    fn import_unknown_workbook_format_fails() {
+        // Act
+        let result = import_data_buffer(create_trainer_excel_info(), "Name,Age\n".as_bytes());
+
+        // Assert
+        assert!(result.is_err());
+        assert!(result
+            .err()
+            .unwrap()
+            .to_string()
+            .contains("Unsupported workbook format"));
+    }
+
+    #[test]
+    fn import_with_unsupported_explicit_format_fails() {
+        // Arrange
+        let excel_bytes = create_template_buffer(&create_trainer_excel_info()).unwrap();
+        let info = create_trainer_excel_info().with_import_format("numbers");
+
+        // Act
+        let result = import_data_buffer(info, &excel_bytes);
+
+        // Assert
+        assert!(result.is_err());
+        assert!(result
+            .err()
+            .unwrap()
+            .to_string()
+            .contains("Unsupported workbook format 'numbers'"));
+    }
+
+    #[test]
+    fn stream_writer_writes_batches_in_order() {
+        // Arrange
+        let mut writer = ExcelStreamWriter::new(create_trainer_excel_info()).unwrap();
+        let first_batch = vec![ExcelRowData::new(vec![
+            ExcelColumnData::new("name", "Tom"),
+            ExcelColumnData::new("age", "12"),
+            ExcelColumnData::new("joined_on", "2024-11-01"),
+            ExcelColumnData::new("category", "Cat"),
+        ])];
+        let second_batch = vec![ExcelRowData::new(vec![
+            ExcelColumnData::new("name", "Jerry & <friends>"),
+            ExcelColumnData::new_bool("category".into(), true),
+        ])];
+
+        // Act
+        writer.write_rows(&first_batch).unwrap();
+        writer.write_rows(&second_batch).unwrap();
+        let excel_bytes = writer.finish().unwrap();
+        let result = import_data_buffer(create_trainer_excel_info(), &excel_bytes);
+
+        // Assert
+        assert!(result.is_ok());
+        let result = result.unwrap();
+        assert_eq!(result.rows.len(), 2);
+        assert_eq!(
+            result.rows[0].columns[1].typed_value(),
+            &ExcelValue::Number(12.0)
+        );
+        assert_eq!(result.rows[0].columns[2].value(), "2024-11-01 00:00:00");
+        assert_eq!(result.rows[1].columns[0].value(), "Jerry & <friends>");
+        assert_eq!(
+            result.rows[1].columns[3].typed_value(),
+            &ExcelValue::Bool(true)
+        );
+        assert_eq!(result.rows[1].sheet_name.as_deref(), Some("sheet1"));
+    }
+
+    #[tokio::test]
+    async fn stream_writer_keeps_title_offset_and_grouped_rows() {
+        // Arrange
+        let create_info = || {
+            create_skill_excel_info()
+                .with_offset(1, 2)
+                .with_title("Skills")
+        };
+        let data = create_skill_excel_data();
+
+        // Act
+        let excel_bytes = export_data_streaming_buffer(create_info(), &data).unwrap();
+        let regular_bytes = export_data_buffer(&create_info(), &data).await.unwrap();
+        let result = import_grouped_data_buffer(create_info(), &excel_bytes);
+
+        // Assert
+        let result = result.unwrap();
+        assert_eq!(result.rows.len(), 1);
+        assert_eq!(result.rows[0].columns[0].value(), "#001");
+        let moves_by = &result.rows[0].columns[2].children;
+        assert_eq!(moves_by[0].columns[0].value(), "Level");
+        assert_eq!(moves_by[0].columns[0].children.len(), 4);
+        let read_sheet = |bytes: Vec<u8>| {
+            let mut workbook: calamine::Xlsx<_> =
+                calamine::open_workbook_from_rs(std::io::Cursor::new(bytes)).unwrap();
+            let mut merged_cells = workbook
+                .merge_cells_by_sheet_name("FireRed Pokédex")
+                .unwrap()
+                .into_iter()
+                .map(|dimensions| (dimensions.start, dimensions.end))
+                .collect::<Vec<_>>();
+            merged_cells.sort();
+            let range = workbook.worksheet_range("FireRed Pokédex").unwrap();
+            let cells = range
+                .used_cells()
+                .filter(|(_, _, cell)| !cell.to_string().is_empty())
+                .map(|(y, x, cell)| (y, x, cell.to_string()))
+                .collect::<Vec<_>>();
+            (merged_cells, cells)
+        };
+        assert_eq!(read_sheet(excel_bytes), read_sheet(regular_bytes));
+    }
+
+    #[test]
+    fn stream_writer_rejects_invalid_batch_and_continues() {
+        // Arrange
+        let mut writer = ExcelStreamWriter::new(create_trainer_excel_info()).unwrap();
+        let invalid_batch = vec![ExcelRowData::new(vec![ExcelColumnData::new(
+            "age", "twelve",
+        )])];
+        let valid_batch = vec![ExcelRowData::new(vec![ExcelColumnData::new("name", "Tom")])];
+
+        // Act
+        let invalid_result = writer.write_rows(&invalid_batch);
+        writer.write_rows(&valid_batch).unwrap();
+        let excel_bytes = writer.finish().unwrap();
+        let finish_again = writer.finish();
+
+        // Assert
+        assert!(invalid_result
+            .err()
+            .unwrap()
+            .to_string()
+            .contains("Invalid number value 'twelve'"));
+        let result = import_data_buffer(create_trainer_excel_info(), &excel_bytes).unwrap();
+        assert_eq!(result.rows.len(), 1);
+        assert_eq!(result.rows[0].columns[0].value(), "Tom");
+        assert!(finish_again
+            .err()
+            .unwrap()
+            .to_string()
+            .contains("already been finished"));
+    }
+
+    #[test]
+    fn stream_writer_rejects_options_it_cannot_write() {
+        // Arrange
+        let create_info = |column: ExcelColumnInfo| {
+            ExcelInfo::new(
+                "Trainers",
+                "Trainers",
+                vec![ExcelColumnInfo::new("id", "Id"), column],
+                "senlinz",
+                "2024-11-01T08:00:00",
+            )
+            .unwrap()
+        };
+        let bold = ExcelCellFormat::new().with_bold(true);
+        let infos = vec![
+            create_info(ExcelColumnInfo::new("name", "Name")).with_title_format(bold.clone()),
+            create_info(ExcelColumnInfo::new("name", "Name").with_format(bold.clone())),
+            create_info(ExcelColumnInfo::new("name", "Name").with_value_format(vec![bold])),
+            create_info(ExcelColumnInfo::new("name", "Name").with_note("Full name".into())),
+            create_info(ExcelColumnInfo::new("photo", "Photo").with_data_type("image")),
+        ];
+        let invalid_auto_width = create_info(ExcelColumnInfo::new("name", "Name")).with_auto_width(
+            ExcelAutoWidth::new()
+                .with_min_width(40.0)
+                .with_max_width(20.0),
+        );
+
+        // Act
+        let errors = infos
+            .into_iter()
+            .map(|info| ExcelStreamWriter::new(info).err().unwrap())
+            .collect::<Vec<_>>();
+        let invalid_auto_width = ExcelStreamWriter::new(invalid_auto_width).err().unwrap();
+
+        // Assert
+        let options = errors
+            .iter()
+            .map(|error| {
+                let error = error.downcast_ref::<ImportExportError>().unwrap();
+                assert_eq!(error.code(), "STREAM_OPTION_UNSUPPORTED");
+                error
+                    .params()
+                    .iter()
+                    .find(|(key, _)| key == "option")
+                    .map(|(_, value)| value.clone())
+                    .unwrap()
+            })
+            .collect::<Vec<String>>();
+        assert_eq!(
+            options,
+            vec!["titleFormat", "format", "valueFormat", "note", "image"]
+        );
+        let invalid_auto_width = invalid_auto_width
+            .downcast_ref::<ImportExportError>()
+            .unwrap();
+        assert_eq!(invalid_auto_width.code(), "INVALID_DEFINITION");
+    }
+
+    #[test]
+    fn stream_writer_rejects_conditional_formats() {
+        // Arrange
+        let mut info = create_stats_excel_info();
+        for column in info.columns.iter_mut().filter(|column| column.key == "hp") {
+            column.conditional_formats = vec![ExcelConditionalFormat::new("gt").with_value("50")];
+        }
+
+        // Act
+        let result = ExcelStreamWriter::new(info);
+
+        // Assert
+        let error = result.err().unwrap();
+        let error = error.downcast_ref::<ImportExportError>().unwrap();
+        assert_eq!(error.code(), "STREAM_OPTION_UNSUPPORTED");
+        assert!(error
+            .params()
+            .contains(&("option".to_string(), "conditionalFormats".to_string())));
+        assert!(error
+            .params()
+            .contains(&("columnKey".to_string(), "hp".to_string())));
+    }
+
+    #[test]
+    fn stream_writer_rejects_row_formats() {
+        // Arrange
+        let info =
+            create_stats_excel_info().with_row_formats(vec![ExcelRowFormat::new("hp", "gt")
+                .with_value("40")
+                .with_format(ExcelCellFormat::new().with_bold(true))]);
+
+        // Act
+        let result = ExcelStreamWriter::new(info);
+
+        // Assert
+        let error = result.err().unwrap();
+        let error = error.downcast_ref::<ImportExportError>().unwrap();
+        assert_eq!(error.code(), "STREAM_OPTION_UNSUPPORTED");
+        assert!(error
+            .params()
+            .contains(&("option".to_string(), "rowFormats".to_string())));
+    }
+
+    #[test]
+    fn stream_writer_rejects_table() {
+        // Arrange
+        let info = create_stats_excel_info().with_table(ExcelTableOptions::new());
+
+        // Act
+        let result = ExcelStreamWriter::new(info);
+
+        // Assert
+        let error = result.err().unwrap();
+        let error = error.downcast_ref::<ImportExportError>().unwrap();
+        assert_eq!(error.code(), "STREAM_OPTION_UNSUPPORTED");
+        assert!(error
+            .params()
+            .contains(&("option".to_string(), "table".to_string())));
+    }
+
+    #[test]
+    fn stream_writer_rejects_summary() {
+        // Arrange
+        let info = create_stats_excel_info().with_summary(ExcelSummaryOptions::new(vec![
+            ExcelAggregate::new("hp", "sum"),
+        ]));
+
+        // Act
+        let result = ExcelStreamWriter::new(info);
+
+        // Assert
+        let error = result.err().unwrap();
+        let error = error.downcast_ref::<ImportExportError>().unwrap();
+        assert_eq!(error.code(), "STREAM_OPTION_UNSUPPORTED");
+        assert!(error
+            .params()
+            .contains(&("option".to_string(), "summary".to_string())));
+    }
+
+    #[tokio::test]
+    async fn stream_writer_fits_auto_width_columns_to_written_values() {
+        // Arrange
+        let create_info = || {
+            ExcelInfo::new(
+                "Products",
+                "Products",
+                vec![
+                    ExcelColumnInfo::new("name", "Name"),
+                    ExcelColumnInfo::new("sku", "SKU").with_auto_width(false),
+                    ExcelColumnInfo::new("released", "Released")
+                        .with_data_type("dateOnly")
+                        .with_date_options(
+                            ExcelDateOptions::new().with_excel_pattern("yyyy/mm/dd hh:mm:ss"),
+                        ),
+                ],
+                "senlinz",
+                "2024-11-01T08:00:00",
+            )
+            .unwrap()
+            .with_auto_width(ExcelAutoWidth::new().with_max_width(30.0))
+        };
+        let rows = vec![
+            ExcelRowData::new(vec![
+                ExcelColumnData::new("name", "皮卡丘电光一闪"),
+                ExcelColumnData::new("sku", "SKU-000000000001"),
+                ExcelColumnData::new("released", "2024-11-01"),
+            ]),
+            ExcelRowData::new(vec![
+                ExcelColumnData::new("name", "A very long name that keeps going past the limit"),
+                ExcelColumnData::new("sku", "SKU-2"),
+                ExcelColumnData::new("released", ""),
+            ]),
+        ];
+        let get_cols = |bytes: &[u8]| {
+            let sheet_xml = read_zip_entry(bytes, "xl/worksheets/sheet1.xml");
+            let start = sheet_xml.find("<cols>").unwrap();
+            let end = sheet_xml.find("</cols>").unwrap();
+            sheet_xml[start..end].to_string()
+        };
+
+        // Act
+        let mut writer = ExcelStreamWriter::new(create_info()).unwrap();
+        writer.write_rows(&rows).unwrap();
+        let streamed = writer.finish().unwrap();
+        let mut batched_writer = ExcelStreamWriter::new(create_info()).unwrap();
+        batched_writer.write_rows(&rows[..1]).unwrap();
+        batched_writer.write_rows(&rows[1..]).unwrap();
+        let batched = batched_writer.finish().unwrap();
+        let exported = export_data_buffer(&create_info(), &ExcelData::new(rows))
+            .await
+            .unwrap();
+
+        // Assert
+        let cols = get_cols(&streamed);
+        assert_eq!(cols, get_cols(&exported));
+        assert!(cols.contains(r#"<col min="1" max="1" width="30.7109375" customWidth="1"/>"#));
+        assert!(cols.contains(r#"<col min="2" max="2" width="10.7109375" customWidth="1"/>"#));
+        assert!(cols.contains(r#"<col min="3" max="3" width="21.7109375" customWidth="1"/>"#));
+        // Later batches are written after the column widths and do not widen them.
+        let batched_cols = get_cols(&batched);
+        assert!(
+            batched_cols.contains(r#"<col min="1" max="1" width="16.7109375" customWidth="1"/>"#)
+        );
+        let workbook_bytes = std::io::Cursor::new(batched);
+        let mut workbook = calamine::open_workbook_auto_from_rs(workbook_bytes).unwrap();
+        let range = workbook.worksheet_range("Products").unwrap();
+        assert_eq!(range.get_size(), (3, 3));
+    }
+
+    fn create_trainer_excel_bytes(row_count: u32) -> Vec<u8> {
+        let (mut workbook, _) =
+            crate::create_template_workbook(&create_trainer_excel_info()).unwrap();
+        let worksheet = workbook.worksheet_from_index(0).unwrap();
+        for row_index in 1..=row_count {
+            worksheet
+                .write_string(row_index, 0, format!("Trainer {}", row_index))
+                .unwrap();
+            worksheet.write_number(row_index, 1, row_index).unwrap();
+        }
+        workbook.save_to_buffer().unwrap()
+    }
+
+    #[tokio::test]
+    async fn import_in_batches_delivers_every_row_with_progress() {
+        // Arrange
+        let excel_bytes = create_trainer_excel_bytes(5);
+        let mut batches = Vec::new();
+
+        // Act
+        let result = import_data_in_batches_buffer(
+            create_trainer_excel_info(),
+            &excel_bytes,
+            Some(2),
+            |rows, progress| {
+                batches.push((rows, progress));
+                std::future::ready(Ok(true))
+            },
+        )
+        .await;
+
+        // Assert
+        assert!(result.is_ok());
+        assert_eq!(format!("{:?}", result.unwrap()), "sheet1: 5/5 rows");
+        let batch_sizes = batches
+            .iter()
+            .map(|(rows, progress)| (rows.len(), *progress))
+            .collect::<Vec<(usize, f64)>>();
+        assert_eq!(batch_sizes, vec![(2, 0.4), (2, 0.8), (1, 1.0)]);
+        assert_eq!(batches[2].0[0].columns[0].value(), "Trainer 5");
+    }
+
+    #[tokio::test]
+    async fn import_in_batches_stops_when_callback_returns_false() {
+        // Arrange
+        let excel_bytes = create_trainer_excel_bytes(5);
+        let mut batch_count = 0;
+
+        // Act
+        let result = import_data_in_batches_buffer(
+            create_trainer_excel_info(),
+            &excel_bytes,
+            Some(2),
+            |_, _| {
+                batch_count += 1;
+                std::future::ready(Ok(false))
+            },
+        )
+        .await;
+
+        // Assert
+        assert!(result.is_ok());
+        let result = result.unwrap();
+        assert!(result.aborted);
+        assert_eq!(result.imported_rows, 2);
+        assert_eq!(result.total_rows, 5);
+        assert_eq!(batch_count, 1);
+    }
+
+    #[tokio::test]
+    async fn import_in_batches_checks_each_block_as_it_is_read() {
+        // Arrange
+        let (mut workbook, _) =
+            crate::create_template_workbook(&create_trainer_excel_info()).unwrap();
+        let worksheet = workbook.worksheet_from_index(0).unwrap();
+        for row_index in 1..=5 {
+            if row_index != 4 {
+                worksheet
+                    .write_string(row_index, 0, format!("Trainer {}", row_index))
+                    .unwrap();
+            }
+            worksheet.write_number(row_index, 1, row_index).unwrap();
+        }
+        let excel_bytes = workbook.save_to_buffer().unwrap();
+        let mut delivered = Vec::new();
+
+        // Act
+        let result = import_data_in_batches_buffer(
+            create_trainer_excel_info(),
+            &excel_bytes,
+            Some(2),
+            |rows, _| {
+                delivered.extend(rows);
+                std::future::ready(Ok(true))
+            },
+        )
+        .await;
+
+        // Assert
+        let error = result.err().unwrap();
+        let error = error.downcast_ref::<ImportExportError>().unwrap();
+        assert_eq!(error.code(), "IMPORT_CONSTRAINT_VIOLATED");
+        assert!(error
+            .params()
+            .contains(&("cell".to_string(), "A5".to_string())));
+        // The first block was read and delivered before the bad cell.
+        assert_eq!(delivered.len(), 2);
+        assert_eq!(delivered[1].columns[0].value(), "Trainer 2");
+    }
+
+    #[tokio::test]
+    async fn import_in_batches_reads_what_import_data_reads() {
+        // Arrange
+        let create_info = |precomputed: bool| {
+            create_move_power_excel_info().with_summary(
+                ExcelSummaryOptions::new(vec![ExcelAggregate::new("power", "sum")])
+                    .with_subtotals(true)
+                    .with_precomputed(precomputed),
+            )
+        };
+        let create_move = |name: &str, power: &str| {
+            ExcelRowData::new(vec![
+                ExcelColumnData::new("move", name),
+                ExcelColumnData::new("power", power),
+            ])
+        };
+        let data = ExcelData::new(vec![
+            ExcelRowData::new(vec![
+                ExcelColumnData::new("name", "Bulbasaur"),
+                ExcelColumnData::new_root_group(
+                    "moves".into(),
+                    vec![create_move("Tackle", "40"), create_move("Vine Whip", "35")],
+                ),
+            ]),
+            ExcelRowData::new(vec![
+                ExcelColumnData::new("name", "Charmander"),
+                ExcelColumnData::new_root_group("moves".into(), vec![create_move("Ember", "40")]),
+            ]),
+            ExcelRowData::new(vec![ExcelColumnData::new("name", "Pikachu")]),
+        ]);
+        let get_rows = |rows: &[ExcelRowData]| {
+            rows.iter()
+                .map(|row| {
+                    row.columns
+                        .iter()
+                        .map(|column| column.value().to_string())
+                        .collect::<Vec<String>>()
+                })
+                .collect::<Vec<Vec<String>>>()
+        };
+
+        for precomputed in [false, true] {
+            let excel_bytes = export_data_buffer(&create_info(precomputed), &data)
+                .await
+                .unwrap();
+            let mut batches = Vec::new();
+
+            // Act
+            let imported = import_data_buffer(create_info(precomputed), &excel_bytes).unwrap();
+            let summary = import_data_in_batches_buffer(
+                create_info(precomputed),
+                &excel_bytes,
+                Some(2),
+                |rows, _| {
+                    batches.push(rows);
+                    std::future::ready(Ok(true))
+                },
+            )
+            .await
+            .unwrap();
+
+            // Assert
+            assert_eq!(summary.total_rows, 4);
+            assert_eq!(
+                batches.iter().map(Vec::len).collect::<Vec<usize>>(),
+                vec![2, 2]
+            );
+            assert_eq!(get_rows(&batches.concat()), get_rows(&imported.rows));
+        }
+    }
+
+    #[tokio::test]
+    async fn import_in_batches_with_zero_batch_size_fails() {
+        // Arrange
+        let excel_bytes = create_trainer_excel_bytes(1);
+
+        // Act
+        let result = import_data_in_batches_buffer(
+            create_trainer_excel_info(),
+            &excel_bytes,
+            Some(0),
+            |_, _| std::future::ready(Ok(true)),
+        )
+        .await;
+
+        // Assert
+        assert!(result.is_err());
+        assert!(result
+            .err()
+            .unwrap()
+            .to_string()
+            .contains("batch size must be greater than zero"));
+    }
+
+    struct FixtureImageFetcher {
+        requested_urls: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl ImageFetcher for FixtureImageFetcher {
+        fn fetch<'a>(&'a self, url: &'a str) -> ImageFetchFuture<'a> {
+            self.requested_urls.lock().unwrap().push(url.to_string());
+            Box::pin(async move {
+                if url.ends_with(".png") {
+                    Ok(include_bytes!("./fixtures/pikachu.png").to_vec())
+                } else {
+                    Err(create_structured_error(
+                        format!("Image not found: {}", url),
+                        "IMAGE_NOT_FOUND",
+                        &[("url", url.to_string())],
+                    )
+                    .into())
+                }
+            })
+        }
+    }
+
+    fn create_avatar_excel_info(requested_urls: Arc<Mutex<Vec<String>>>) -> ExcelInfo {
+        let columns = vec![
+            ExcelColumnInfo::new("name", "Name"),
+            ExcelColumnInfo::new("avatar", "Avatar").with_data_type("image"),
+        ];
+        ExcelInfo::new(
+            "Avatars",
+            "Avatars",
+            columns,
+            "senlinz",
+            "2024-11-01T08:00:00",
+        )
+        .unwrap()
+        .with_image_fetcher(FixtureImageFetcher { requested_urls })
+    }
+
+    #[tokio::test]
+    async fn export_with_native_progress_reporter_and_image_fetcher_success() {
+        // Arrange
+        let requested_urls = Arc::new(Mutex::new(Vec::new()));
+        let progress = Arc::new(Mutex::new(Vec::new()));
+        let progress_values = progress.clone();
+        let info = create_avatar_excel_info(requested_urls.clone())
+            .with_progress_callback(move |value: f64| progress_values.lock().unwrap().push(value));
+        let data = ExcelData::new(vec![ExcelRowData::new(vec![
+            ExcelColumnData::new("name", "Pikachu"),
+            ExcelColumnData::new("avatar", "https://example.com/pikachu.png"),
+        ])]);
+
+        // Act
+        let result = export_data_buffer(&info, &data).await;
+
+        // Assert
+        assert!(result.is_ok());
+        let workbook: calamine::Xlsx<_> =
+            calamine::open_workbook_from_rs(std::io::Cursor::new(result.unwrap())).unwrap();
+        assert_eq!(workbook.pictures().map(|pictures| pictures.len()), Some(1));
+        assert_eq!(
+            *requested_urls.lock().unwrap(),
+            vec!["https://example.com/pikachu.png".to_string()]
+        );
+        assert_eq!(progress.lock().unwrap().last(), Some(&1.0));
+    }
+
+    #[tokio::test]
+    async fn export_with_failing_native_image_fetcher_keeps_error_code() {
+        // Arrange
+        let info = create_avatar_excel_info(Arc::new(Mutex::new(Vec::new())));
+        let data = ExcelData::new(vec![ExcelRowData::new(vec![
+            ExcelColumnData::new("name", "Missingno"),
+            ExcelColumnData::new("avatar", "https://example.com/missingno"),
+        ])]);
+
+        // Act
+        let result = export_data_buffer(&info, &data).await;
+
+        // Assert
+        let error = result.err().unwrap();
+        let error = error.downcast_ref::<ImportExportError>().unwrap();
+        assert_eq!(error.code(), "IMAGE_NOT_FOUND");
+        assert_eq!(
+            error.params(),
+            &[(
+                "url".to_string(),
+                "https://example.com/missingno".to_string()
+            )]
+        );
+    }
+
+    fn assert_send<T: Send>(_: T) {}
+
+    #[test]
+    fn native_export_and_batch_import_futures_are_send() {
+        let info = create_avatar_excel_info(Arc::new(Mutex::new(Vec::new())))
+            .with_progress_callback(|_: f64| {});
+        let data = ExcelData::new(vec![]);
+        let workbook = ExcelWorkbook::new();
+
+        assert_send(export_data_buffer(&info, &data));
+        assert_send(export_workbook_buffer(&workbook));
+        assert_send(import_data_in_batches_buffer(
+            create_excel_info(),
+            &[],
+            None,
+            |_, _| async { Ok(true) },
+        ));
+    }
+
+    #[cfg(feature = "cli")]
+    fn create_cli_temp_dir(name: &str) -> std::path::PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("imexport-cli-{}-{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[cfg(feature = "cli")]
+    fn run_cli(args: &[&str]) -> Result<(), Box<dyn std::error::Error>> {
+        let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        cli::run(&args)
+    }
+
+    #[cfg(feature = "cli")]
+    #[test]
+    fn cli_export_then_import_round_trip_success() {
+        // Arrange
+        let dir = create_cli_temp_dir("round-trip");
+        let schema = dir.join("schema.json");
+        let rows = dir.join("rows.json");
+        let workbook = dir.join("trainers.xlsx");
+        let imported = dir.join("imported.json");
+        std::fs::write(
+            &schema,
+            r#"{
+                "name": "Trainers",
+                "sheetName": "Trainers",
+                "author": "senlinz",
+                "createTime": "2024-11-01T08:00:00",
+                "columns": [
+                    { "key": "name", "name": "Name" },
+                    { "key": "age", "name": "Age", "dataType": "number" },
+                    { "key": "category", "name": "Category", "allowedValues": ["Cat", "Mouse"] }
+                ]
+            }"#,
+        )
+        .unwrap();
+        std::fs::write(
+            &rows,
+            r#"[
+                { "name": "Tom", "age": 12, "category": "Cat" },
+                { "name": "Jerry", "age": 3.5, "category": null }
+            ]"#,
+        )
+        .unwrap();
+
+        // Act
+        let export_result = run_cli(&[
+            "export",
+            "--schema",
+            schema.to_str().unwrap(),
+            "--data",
+            rows.to_str().unwrap(),
+            "--output",
+            workbook.to_str().unwrap(),
+        ]);
+        let import_result = run_cli(&[
+            "import",
+            "--schema",
+            schema.to_str().unwrap(),
+            "--input",
+            workbook.to_str().unwrap(),
+            "--output",
+            imported.to_str().unwrap(),
+        ]);
+
+        // Assert
+        assert!(export_result.is_ok());
+        assert!(import_result.is_ok());
+        let imported: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(&imported).unwrap()).unwrap();
+        assert_eq!(
+            imported,
+            serde_json::json!([
+                { "name": "Tom", "age": 12, "category": "Cat" },
+                { "name": "Jerry", "age": 3.5, "category": null }
+            ])
+        );
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[cfg(feature = "cli")]
+    #[test]
+    fn cli_imported_links_export_again_as_links() {
+        // Arrange
+        let dir = create_cli_temp_dir("link-round-trip");
+        let schema = dir.join("schema.json");
+        let rows = dir.join("rows.json");
+        let workbook = dir.join("trainers.xlsx");
+        let imported = dir.join("imported.json");
+        let exported_again = dir.join("trainers-again.xlsx");
+        let imported_again = dir.join("imported-again.json");
+        std::fs::write(
+            &schema,
+            r#"{
+                "name": "Trainers",
+                "sheetName": "Trainers",
+                "columns": [
+                    { "key": "name", "name": "Name" },
+                    { "key": "site", "name": "Site", "dataType": "link" }
+                ]
+            }"#,
+        )
+        .unwrap();
+        std::fs::write(
+            &rows,
+            r#"[{ "name": "Ash", "site": "Pallet|https://example.com/pallet" }]"#,
+        )
+        .unwrap();
+        let schema = schema.to_str().unwrap();
+
+        // Act
+        let results = [
+            run_cli(&[
+                "export",
+                "--schema",
+                schema,
+                "--data",
+                rows.to_str().unwrap(),
+                "--output",
+                workbook.to_str().unwrap(),
+            ]),
+            run_cli(&[
+                "import",
+                "--schema",
+                schema,
+                "--input",
+                workbook.to_str().unwrap(),
+                "--output",
+                imported.to_str().unwrap(),
+            ]),
+            run_cli(&[
+                "export",
+                "--schema",
+                schema,
+                "--data",
+                imported.to_str().unwrap(),
+                "--output",
+                exported_again.to_str().unwrap(),
+            ]),
+            run_cli(&[
+                "import",
+                "--schema",
+                schema,
+                "--input",
+                exported_again.to_str().unwrap(),
+                "--output",
+                imported_again.to_str().unwrap(),
+            ]),
+        ];
+
+        // Assert
+        assert!(results.iter().all(Result::is_ok));
+        let expected = serde_json::json!([
+            { "name": "Ash", "site": { "url": "https://example.com/pallet", "text": "Pallet" } }
+        ]);
+        for path in [&imported, &imported_again] {
+            let rows: serde_json::Value =
+                serde_json::from_slice(&std::fs::read(path).unwrap()).unwrap();
+            assert_eq!(rows, expected);
+        }
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[cfg(feature = "cli")]
+    #[test]
+    fn cli_import_dynamic_writes_headers_and_rows() {
+        // Arrange
+        let dir = create_cli_temp_dir("import-dynamic");
+        let output = dir.join("trainers.json");
+
+        // Act
+        let result = run_cli(&[
+            "import-dynamic",
+            "--input",
+            "src/tests/fixtures/trainers.ods",
+            "--sheet",
+            "Trainers",
+            "--output",
+            output.to_str().unwrap(),
+        ]);
+
+        // Assert
+        assert!(result.is_ok());
+        let output: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(&output).unwrap()).unwrap();
+        assert_eq!(output["sheetName"], "Trainers");
+        assert_eq!(
+            output["headers"],
+            serde_json::json!(["Name", "Age", "Joined On", "Category"])
+        );
+        assert_eq!(output["rows"][1]["Name"], "Jerry");
+        assert_eq!(output["rows"][1]["Age"], 3);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[cfg(feature = "cli")]
+    #[test]
+    fn cli_unknown_command_returns_usage_error_code() {
+        // Act
+        let result = run_cli(&["convert", "--input", "trainers.xlsx"]);
+
+        // Assert
+        let error = result.err().unwrap();
+        assert_eq!(cli::get_exit_code(error.as_ref()), 2);
+        let error: serde_json::Value =
+            serde_json::from_str(&cli::error_to_json(error.as_ref())).unwrap();
+        assert_eq!(error["code"], "CLI_USAGE_INVALID");
+        assert_eq!(error["message"], "Unknown command 'convert'");
+    }
+
+    #[cfg(feature = "cli")]
+    #[test]
+    fn cli_invalid_schema_returns_definition_error_code() {
+        // Arrange
+        let dir = create_cli_temp_dir("invalid-schema");
+        let schema = dir.join("schema.json");
+        std::fs::write(
+            &schema,
+            r#"{ "name": "Trainers", "columns": [{ "key": "age", "name": "Age", "dataType": "decimal" }] }"#,
+        )
+        .unwrap();
+
+        // Act
+        let result = run_cli(&["template", "--schema", schema.to_str().unwrap()]);
+
+        // Assert
+        let error = result.err().unwrap();
+        assert_eq!(cli::get_exit_code(error.as_ref()), 1);
+        let error: serde_json::Value =
+            serde_json::from_str(&cli::error_to_json(error.as_ref())).unwrap();
+        assert_eq!(error["code"], "INVALID_DEFINITION");
+        assert!(error["params"]["reason"]
+            .as_str()
+            .unwrap()
+            .contains("unsupported dataType 'decimal'"));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn infer_excel_info_from_exported_workbook_success() {
+        // Arrange
+        let info = ExcelInfo::new(
+            "Trainers",
+            "Trainers",
+            vec![
+                ExcelColumnInfo::new("name", "Name"),
+                ExcelColumnInfo::new("profile", "Profile"),
+                ExcelColumnInfo::new("age", "Age")
+                    .with_parent("profile")
+                    .with_data_type("number"),
+                ExcelColumnInfo::new("joined_on", "Joined On")
+                    .with_parent("profile")
+                    .with_data_type("date"),
+                ExcelColumnInfo::new("category", "Category")
+                    .with_allowed_values(vec!["Leader".into(), "Rival".into()]),
+            ],
+            "senlinz",
+            "2024-11-01T08:00:00",
+        )
+        .unwrap()
+        .with_offset(1, 2)
+        .with_title("Kanto Trainers");
+        let data = ExcelData::new(vec![
+            ExcelRowData::new(vec![
+                ExcelColumnData::new("name", "Brock"),
+                ExcelColumnData::new("age", "15"),
+                ExcelColumnData::new("joined_on", "2024-01-02"),
+                ExcelColumnData::new("category", "Leader"),
+            ]),
+            ExcelRowData::new(vec![
+                ExcelColumnData::new("name", "Gary"),
+                ExcelColumnData::new("age", "10"),
+                ExcelColumnData::new("joined_on", "2024-03-04"),
+                ExcelColumnData::new("category", "Rival"),
+            ]),
+        ]);
+        let excel_bytes = export_data_buffer(&info, &data).await.unwrap();
+
+        // Act
+        let result = infer_excel_info_buffer(None, None, &excel_bytes, None);
+
+        // Assert
+        let result = result.unwrap();
+        assert_eq!(result.sheet_name, "Trainers");
+        assert_eq!(result.title.as_deref(), Some("Kanto Trainers"));
+        assert_eq!((result.dx, result.dy), (1, 2));
+        let columns: Vec<_> = result
+            .columns
+            .iter()
+            .map(|column| {
+                (
+                    column.key.as_str(),
+                    column.parent.as_str(),
+                    column.data_type.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            columns,
+            vec![
+                ("Name", "", "text"),
+                ("Profile", "", "text"),
+                ("Age", "Profile", "number"),
+                ("Joined On", "Profile", "date"),
+                ("Category", "", "text"),
+            ]
+        );
+        assert_eq!(result.columns[4].allowed_values, vec!["Leader", "Rival"]);
+        let imported = import_data_buffer(result, &excel_bytes).unwrap();
+        assert_eq!(imported.rows.len(), 2);
+        assert_eq!(imported.rows[1].columns[0].value(), "Gary");
+    }
+
+    #[test]
+    fn infer_excel_info_reads_list_validation_from_another_sheet() {
+        // Arrange
+        let mut workbook = rust_xlsxwriter::Workbook::new();
+        let worksheet = workbook.add_worksheet().set_name("Trainers").unwrap();
+        worksheet.write_string(0, 0, "Name").unwrap();
+        worksheet.write_string(0, 1, "Category").unwrap();
+        worksheet.write_string(1, 0, "Misty").unwrap();
+        worksheet.write_string(1, 1, "Leader").unwrap();
+        worksheet
+            .add_data_validation(
+                1,
+                1,
+                100,
+                1,
+                &rust_xlsxwriter::DataValidation::new()
+                    .allow_list_formula("'Category List'!$A$1:$A$3".into()),
+            )
+            .unwrap();
+        let lists = workbook.add_worksheet().set_name("Category List").unwrap();
+        for (row, value) in ["Leader", "Rival", "Elite Four"].iter().enumerate() {
+            lists.write_string(row as u32, 0, *value).unwrap();
+        }
+        let excel_bytes = workbook.save_to_buffer().unwrap();
+
+        // Act
+        let result = infer_excel_info_buffer(Some("Trainers".into()), None, &excel_bytes, None);
+
+        // Assert
+        let result = result.unwrap();
+        assert!(result.title.is_none());
+        assert!(result.columns[0].allowed_values.is_empty());
+        assert_eq!(
+            result.columns[1].allowed_values,
+            vec!["Leader", "Rival", "Elite Four"]
+        );
+    }
+
+    #[test]
+    fn infer_excel_info_with_empty_header_fails() {
+        // Arrange
+        let mut workbook = rust_xlsxwriter::Workbook::new();
+        let worksheet = workbook.add_worksheet();
+        worksheet.write_string(0, 0, "Name").unwrap();
+        worksheet.write_string(0, 2, "Age").unwrap();
+        let excel_bytes = workbook.save_to_buffer().unwrap();
+
+        // Act
+        let result = infer_excel_info_buffer(None, None, &excel_bytes, None);
+
+        // Assert
+        let error = result.err().unwrap();
+        let error = error.downcast_ref::<ImportExportError>().unwrap();
+        assert_eq!(error.code(), "DYNAMIC_HEADER_EMPTY");
+        assert!(error.to_string().contains("B1"));
+    }
+
+    fn create_workbook_with_headers(headers: &[&str], rows: &[&[&str]]) -> Vec<u8> {
+        let mut workbook = rust_xlsxwriter::Workbook::new();
+        let worksheet = workbook.add_worksheet();
+        for (x, header) in headers.iter().enumerate() {
+            worksheet.write_string(0, x as u16, *header).unwrap();
+        }
+        for (y, row) in rows.iter().enumerate() {
+            for (x, value) in row.iter().enumerate() {
+                worksheet
+                    .write_string(y as u32 + 1, x as u16, *value)
+                    .unwrap();
+            }
+        }
+        workbook.save_to_buffer().unwrap()
+    }
+
+    #[test]
+    fn import_matching_headers_by_name_binds_reordered_and_aliased_columns() {
+        // Arrange
+        let info = ExcelInfo::new(
+            "Trainers",
+            "sheet1",
+            vec![
+                ExcelColumnInfo::new("name", "Name"),
+                ExcelColumnInfo::new("age", "Age"),
+                ExcelColumnInfo::new("category", "Category")
+                    .with_aliases(vec!["Kind".into(), "Type".into()]),
+            ],
+            "senlinz",
+            "2024-11-01T08:00:00",
+        )
+        .unwrap()
+        .with_match_headers_by_name(true)
+        .with_ignore_header_case(true)
+        .with_normalize_header_whitespace(true);
+        let excel_bytes = create_workbook_with_headers(
+            &["Notes", "TYPE", " age ", "Trainer\nName", "Name"],
+            &[&["-", "Cat", "3", "ignored", "Tom"]],
+        );
+
+        // Act
+        let result = import_data_buffer(info, &excel_bytes);
+
+        // Assert
+        let result = result.unwrap();
+        let row = &result.rows[0].columns;
+        assert_eq!(row[0].value(), "Tom");
+        assert_eq!(row[1].value(), "3");
+        assert_eq!(row[2].value(), "Cat");
+        let bindings: Vec<_> = result
+            .column_bindings
+            .iter()
+            .map(|binding| {
+                (
+                    binding.key.as_str(),
+                    binding.header.as_str(),
+                    binding.cell.as_str(),
+                    binding.column_index,
+                )
+            })
+            .collect();
+        assert_eq!(
+            bindings,
+            vec![
+                ("name", "Name", "E1", 4),
+                ("age", "age", "C1", 2),
+                ("category", "TYPE", "B1", 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn import_by_position_accepts_aliases_and_header_normalization() {
+        // Arrange
+        let create_info = || {
+            let columns = vec![
+                ExcelColumnInfo::new("name", "Name").with_aliases(vec!["Trainer".into()]),
+                ExcelColumnInfo::new("age", "Age"),
+            ];
+            ExcelInfo::new("Trainers", "sheet1", columns, "", "").unwrap()
+        };
+        let excel_bytes = create_workbook_with_headers(&["Trainer", "AGE"], &[&["Tom", "3"]]);
+
+        // Act
+        let strict_result = import_data_buffer(create_info(), &excel_bytes);
+        let result = import_data_buffer(create_info().with_ignore_header_case(true), &excel_bytes);
+
+        // Assert
+        let error = strict_result.err().unwrap();
+        let error = error.downcast_ref::<ImportExportError>().unwrap();
+        assert_eq!(error.code(), "HEADER_MISMATCH");
+        assert_eq!(error.params()[0], ("cell".to_string(), "B1".to_string()));
+        let result = result.unwrap();
+        assert_eq!(result.rows[0].columns[0].value(), "Tom");
+        assert_eq!(result.column_bindings[0].header, "Trainer");
+        assert_eq!(result.column_bindings[1].cell, "B1");
+    }
+
+    #[test]
+    fn import_matching_headers_by_name_reports_missing_header() {
+        // Arrange
+        let create_info = || create_trainer_excel_info().with_match_headers_by_name(true);
+        let excel_bytes = create_workbook_with_headers(
+            &["Category", "Name", "Joined On"],
+            &[&["Cat", "Tom", "2024-01-02"]],
+        );
+
+        // Act
+        let result = import_data_buffer(create_info(), &excel_bytes);
+        let report = import_data_with_report_buffer(create_info(), &excel_bytes).unwrap();
+
+        // Assert
+        let error = result.err().unwrap();
+        let error = error.downcast_ref::<ImportExportError>().unwrap();
+        assert_eq!(error.code(), "HEADER_NOT_FOUND");
+        assert_eq!(
+            error.to_string(),
+            "Header 'Age' not found in row 1 of sheet 'Sheet1'"
+        );
+        assert_eq!(report.issues.len(), 1);
+        assert_eq!(report.issues[0].column_key, "age");
+        assert_eq!(report.issues[0].code, "HEADER_NOT_FOUND");
+        assert_eq!(report.data.column_bindings.len(), 3);
+        assert!(report.data.rows[0]
+            .columns
+            .iter()
+            .all(|column| column.key != "age"));
+    }
+
+    fn create_flag_excel_info(labels: Option<(&str, &str)>) -> ExcelInfo {
+        let active = ExcelColumnInfo::new("active", "Active")
+            .with_data_type("boolean")
+            .with_boolean_dropdown(true);
+        let active = match labels {
+            Some((true_label, false_label)) => active.with_boolean_labels(true_label, false_label),
+            None => active,
+        };
+        ExcelInfo::new(
+            "Trainers",
+            "Trainers",
+            vec![ExcelColumnInfo::new("name", "Name"), active],
+            "senlinz",
+            "2024-11-01T08:00:00",
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn export_and_import_boolean_column_round_trip_success() {
+        // Arrange
+        let info = create_flag_excel_info(None);
+        let data = ExcelData::new(
+            [
+                ExcelColumnData::new("active", " Yes "),
+                ExcelColumnData::with_typed_value("active", ExcelValue::Bool(false)),
+                ExcelColumnData::new("active", "0"),
+                ExcelColumnData::new("active", ""),
+            ]
+            .into_iter()
+            .map(|active| ExcelRowData::new(vec![ExcelColumnData::new("name", "Ash"), active]))
+            .collect(),
+        );
+
+        // Act
+        let excel_bytes = export_data_buffer(&info, &data).await.unwrap();
+        let result = import_data_buffer(create_flag_excel_info(None), &excel_bytes);
+        let streamed_bytes = export_data_streaming_buffer(info, &data).unwrap();
+
+        // Assert
+        let mut workbook: calamine::Xlsx<_> =
+            calamine::open_workbook_from_rs(std::io::Cursor::new(excel_bytes.clone())).unwrap();
+        let range = workbook.worksheet_range("Trainers").unwrap();
+        assert_eq!(range.get_value((1, 1)), Some(&calamine::Data::Bool(true)));
+        let values: Vec<_> = result
+            .unwrap()
+            .rows
+            .iter()
+            .map(|row| row.columns[1].typed_value().clone())
+            .collect();
+        assert_eq!(
+            values,
+            vec![
+                ExcelValue::Bool(true),
+                ExcelValue::Bool(false),
+                ExcelValue::Bool(false),
+                ExcelValue::Empty,
+            ]
+        );
+        let streamed = import_data_buffer(create_flag_excel_info(None), &streamed_bytes).unwrap();
+        assert_eq!(
+            streamed.rows[0].columns[1].typed_value(),
+            &ExcelValue::Bool(true)
+        );
+        let inferred = infer_excel_info_buffer(None, None, &excel_bytes, None).unwrap();
+        assert_eq!(inferred.columns[1].data_type, "boolean");
+        assert_eq!(inferred.columns[1].allowed_values, vec!["TRUE", "FALSE"]);
+    }
+
+    #[tokio::test]
+    async fn export_boolean_column_with_custom_labels_success() {
+        // Arrange
+        let info = create_flag_excel_info(Some(("是", "否")));
+        let data = ExcelData::new(vec![
+            ExcelRowData::new(vec![ExcelColumnData::with_typed_value(
+                "active",
+                ExcelValue::Bool(true),
+            )]),
+            ExcelRowData::new(vec![ExcelColumnData::new("active", "false")]),
+        ]);
+
+        // Act
+        let template_bytes = create_template_buffer(&info).unwrap();
+        let excel_bytes = export_data_buffer(&info, &data).await.unwrap();
+
+        // Assert
+        let template = infer_excel_info_buffer(None, None, &template_bytes, None).unwrap();
+        assert_eq!(template.columns[1].allowed_values, vec!["是", "否"]);
+        let result =
+            import_data_buffer(create_flag_excel_info(Some(("是", "否"))), &excel_bytes).unwrap();
+        assert_eq!(result.rows[0].columns[1].value(), "true");
+        assert_eq!(
+            result.rows[1].columns[1].typed_value(),
+            &ExcelValue::Bool(false)
+        );
+        let dynamic = import_dynamic_data_buffer(None, None, &excel_bytes, None).unwrap();
+        assert_eq!(dynamic.rows[0].columns[1].value(), "是");
+    }
+
+    #[tokio::test]
+    async fn export_boolean_column_with_invalid_value_fails() {
+        // Arrange
+        let info = create_flag_excel_info(None);
+        let data = ExcelData::new(vec![ExcelRowData::new(vec![ExcelColumnData::new(
+            "active", "maybe",
+        )])]);
+
+        // Act
+        let result = export_data_buffer(&info, &data).await;
+
+        // Assert
+        let error = result.err().unwrap();
+        let error = error.downcast_ref::<ImportExportError>().unwrap();
+        assert_eq!(error.code(), "EXPORT_BOOLEAN_VALUE_INVALID");
+        assert_eq!(error.to_string(), "Invalid boolean value 'maybe'");
+    }
+
+    #[test]
+    fn import_boolean_column_with_invalid_value_fails() {
+        // Arrange
+        let excel_bytes = create_workbook_with_headers(
+            &["Name", "Active"],
+            &[&["Ash", "on"], &["Misty", "maybe"]],
+        );
+
+        // Act
+        let result = import_data_buffer(create_flag_excel_info(None), &excel_bytes);
+        let report =
+            import_data_with_report_buffer(create_flag_excel_info(None), &excel_bytes).unwrap();
+
+        // Assert
+        let error = result.err().unwrap();
+        let error = error.downcast_ref::<ImportExportError>().unwrap();
+        assert_eq!(error.code(), "IMPORT_BOOLEAN_VALUE_INVALID");
+        assert_eq!(
+            error.to_string(),
+            "Invalid boolean value 'maybe' at B3 in sheet 'Sheet1'"
+        );
+        assert_eq!(report.issues.len(), 1);
+        assert_eq!(report.issues[0].code, "CELL_BOOLEAN_INVALID");
+        assert_eq!(
+            report.data.rows[0].columns[1].typed_value(),
+            &ExcelValue::Bool(true)
+        );
+    }
+
+    fn create_order_excel_info() -> ExcelInfo {
+        ExcelInfo::new(
+            "Orders",
+            "Orders",
+            vec![
+                ExcelColumnInfo::new("orderNo", "Order No"),
+                ExcelColumnInfo::new("order", "Order")
+                    .with_data_type("link")
+                    .with_link_text_key("orderNo"),
+            ],
+            "senlinz",
+            "2024-11-01T08:00:00",
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn export_and_import_link_column_round_trip_success() {
+        // Arrange
+        let info = create_order_excel_info();
+        let data = ExcelData::new(vec![
+            ExcelRowData::new(vec![
+                ExcelColumnData::new("orderNo", "View order 1"),
+                ExcelColumnData::new("order", "https://example.com/orders/1"),
+            ]),
+            ExcelRowData::new(vec![ExcelColumnData::new(
+                "order",
+                "Order 2|https://example.com/orders/2",
+            )]),
+            ExcelRowData::new(vec![ExcelColumnData::new("order", "")]),
+            ExcelRowData::new(vec![ExcelColumnData::new_link(
+                "order".into(),
+                "mailto:ash@example.com".into(),
+                "".into(),
+            )]),
+        ]);
+
+        // Act
+        let excel_bytes = export_data_buffer(&info, &data).await.unwrap();
+        let streamed_bytes =
+            export_data_streaming_buffer(create_order_excel_info(), &data).unwrap();
+
+        // Assert
+        let expected = vec![
+            ExcelValue::Link {
+                url: "https://example.com/orders/1".into(),
+                text: "View order 1".into(),
+            },
+            ExcelValue::Link {
+                url: "https://example.com/orders/2".into(),
+                text: "Order 2".into(),
+            },
+            ExcelValue::Empty,
+            ExcelValue::Link {
+                url: "mailto:ash@example.com".into(),
+                text: "mailto:ash@example.com".into(),
+            },
+        ];
+        for bytes in [&excel_bytes, &streamed_bytes] {
+            let result = import_data_buffer(create_order_excel_info(), bytes).unwrap();
+            let values: Vec<_> = result
+                .rows
+                .iter()
+                .map(|row| row.columns[1].typed_value().clone())
+                .collect();
+            assert_eq!(values, expected);
+            assert_eq!(
+                result.rows[1].columns[1].value(),
+                "Order 2|https://example.com/orders/2"
+            );
+            assert_eq!(
+                result.rows[0].columns[1].link_url().as_deref(),
+                Some("https://example.com/orders/1")
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn import_and_export_link_column_keeps_plain_text_cells() {
+        // Arrange
+        let mut workbook = rust_xlsxwriter::Workbook::new();
+        let worksheet = workbook.add_worksheet().set_name("Orders").unwrap();
+        worksheet.write_string(0, 0, "Order No").unwrap();
+        worksheet.write_string(0, 1, "Order").unwrap();
+        worksheet.write_string(1, 1, "N/A").unwrap();
+        worksheet.write_string(2, 1, "www.example.com").unwrap();
+        worksheet
+            .write_url_with_text(3, 1, "https://example.com/orders/3", "Order 3")
+            .unwrap();
+        let excel_bytes = workbook.save_to_buffer().unwrap();
+        let expected = vec![
+            ExcelValue::Text("N/A".into()),
+            ExcelValue::Text("www.example.com".into()),
+            ExcelValue::Link {
+                url: "https://example.com/orders/3".into(),
+                text: "Order 3".into(),
+            },
+        ];
+
+        // Act
+        let imported = import_data_buffer(create_order_excel_info(), &excel_bytes).unwrap();
+        let exported = export_data_buffer(&create_order_excel_info(), &imported)
+            .await
+            .unwrap();
+        let streamed = export_data_streaming_buffer(create_order_excel_info(), &imported).unwrap();
+
+        // Assert
+        for bytes in [&excel_bytes, &exported, &streamed] {
+            let result = import_data_buffer(create_order_excel_info(), bytes).unwrap();
+            let values: Vec<_> = result
+                .rows
+                .iter()
+                .map(|row| row.columns[1].typed_value().clone())
+                .collect();
+            assert_eq!(values, expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn export_link_column_with_invalid_url_fails() {
+        // Arrange
+        let data = ExcelData::new(vec![ExcelRowData::new(vec![ExcelColumnData::new(
+            "order",
+            "Order 1|example.com/orders/1",
+        )])]);
+
+        // Act
+        let result = export_data_buffer(&create_order_excel_info(), &data).await;
+        let streamed = export_data_streaming_buffer(create_order_excel_info(), &data);
+
+        // Assert
+        for error in [result.err().unwrap(), streamed.err().unwrap()] {
+            let error = error.downcast_ref::<ImportExportError>().unwrap();
+            assert_eq!(error.code(), "EXPORT_LINK_VALUE_INVALID");
+            assert_eq!(
+                error.params()[0],
+                ("url".to_string(), "example.com/orders/1".to_string())
+            );
+        }
+    }
+
+    fn create_move_power_excel_info() -> ExcelInfo {
+        ExcelInfo::new(
+            "Moves",
+            "Moves",
+            vec![
+                ExcelColumnInfo::new("name", "Name"),
+                ExcelColumnInfo::new("total", "Total").with_formula("=SUM({power})"),
+                ExcelColumnInfo::new("moves", "Moves").with_data_group("moves"),
+                ExcelColumnInfo::new("move", "Move")
+                    .with_parent("moves")
+                    .with_data_group_parent("moves"),
+                ExcelColumnInfo::new("power", "Power")
+                    .with_parent("moves")
+                    .with_data_type("number")
+                    .with_data_group_parent("moves"),
+                ExcelColumnInfo::new("double", "Double")
+                    .with_parent("moves")
+                    .with_formula("={power}*2")
+                    .with_data_group_parent("moves"),
+            ],
+            "senlinz",
+            "2024-11-01T08:00:00",
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn export_formula_column_resolves_row_references_success() {
+        // Arrange
+        let info = create_move_power_excel_info();
+        let create_move = |name: &str, power: &str| {
+            ExcelRowData::new(vec![
+                ExcelColumnData::new("move", name),
+                ExcelColumnData::new("power", power),
+            ])
+        };
+        let data = ExcelData::new(vec![
+            ExcelRowData::new(vec![
+                ExcelColumnData::new("name", "Bulbasaur"),
+                ExcelColumnData::new("total", "ignored"),
+                ExcelColumnData::new_root_group(
+                    "moves".into(),
+                    vec![create_move("Tackle", "40"), create_move("Vine Whip", "35")],
+                ),
+            ]),
+            ExcelRowData::new(vec![
+                ExcelColumnData::new("name", "Charmander"),
+                ExcelColumnData::new_root_group("moves".into(), vec![create_move("Ember", "40")]),
+            ]),
+        ]);
+
+        // Act
+        let excel_bytes = export_data_buffer(&info, &data).await.unwrap();
+        let streamed_bytes =
+            export_data_streaming_buffer(create_move_power_excel_info(), &data).unwrap();
+
+        // Assert
+        for bytes in [excel_bytes, streamed_bytes] {
+            let mut workbook: calamine::Xlsx<_> =
+                calamine::open_workbook_from_rs(std::io::Cursor::new(bytes)).unwrap();
+            let formulas = workbook.worksheet_formula("Moves").unwrap();
+            let get_formula = |row: u32, column: u32| formulas.get_value((row, column)).cloned();
+            assert_eq!(get_formula(2, 1).as_deref(), Some("SUM(D3:D4)"));
+            assert_eq!(get_formula(2, 4).as_deref(), Some("D3*2"));
+            assert_eq!(get_formula(3, 4).as_deref(), Some("D4*2"));
+            assert_eq!(get_formula(4, 1).as_deref(), Some("SUM(D5)"));
+            assert_eq!(get_formula(4, 4).as_deref(), Some("D5*2"));
+            let merged_cells = workbook.merge_cells_by_sheet_name("Moves").unwrap();
+            assert!(merged_cells
+                .iter()
+                .any(|dimensions| dimensions.start == (2, 1) && dimensions.end == (3, 1)));
+        }
+    }
+
+    #[test]
+    fn import_formula_column_returns_cached_value() {
+        // Arrange
+        let mut workbook = rust_xlsxwriter::Workbook::new();
+        let worksheet = workbook.add_worksheet().set_name("Stats").unwrap();
+        for (x, header) in ["HP", "Attack", "Total"].into_iter().enumerate() {
+            worksheet.write_string(0, x as u16, header).unwrap();
+        }
+        worksheet.write_number(1, 0, 45).unwrap();
+        worksheet.write_number(1, 1, 49).unwrap();
+        worksheet
+            .write_formula(
+                1,
+                2,
+                rust_xlsxwriter::Formula::new("=SUM(A2:B2)").set_result("94"),
+            )
+            .unwrap();
+        let excel_bytes = workbook.save_to_buffer().unwrap();
+        let info = ExcelInfo::new(
+            "Stats",
+            "Stats",
+            vec![
+                ExcelColumnInfo::new("hp", "HP").with_data_type("number"),
+                ExcelColumnInfo::new("attack", "Attack").with_data_type("number"),
+                ExcelColumnInfo::new("total", "Total")
+                    .with_data_type("number")
+                    .with_formula("=SUM({hp}:{attack})"),
+            ],
+            "senlinz",
+            "2024-11-01T08:00:00",
+        )
+        .unwrap();
+
+        // Act
+        let result = import_data_buffer(info, &excel_bytes).unwrap();
+
+        // Assert
+        assert_eq!(
+            result.rows[0].columns[2].typed_value(),
+            &ExcelValue::Number(94.0)
+        );
+    }
+
+    #[test]
+    fn invalid_schema_formula_with_unknown_column_returns_error() {
+        // Arrange
+        let columns = vec![
+            ExcelColumnInfo::new("hp", "HP"),
+            ExcelColumnInfo::new("total", "Total").with_formula("=SUM({hp}:{speed}, {1,2})"),
+        ];
+
+        // Act
+        let result = ExcelInfo::new("Stats", "Stats", columns, "senlinz", "2024-11-01T08:00:00");
+
+        // Assert
+        assert_eq!(
+            result.err().unwrap(),
+            "Invalid definition: formula of column 'total' references unknown column 'speed'."
+        );
+    }
+
+    fn create_price_excel_info() -> ExcelInfo {
+        ExcelInfo::new(
+            "Prices",
+            "Prices",
+            vec![
+                ExcelColumnInfo::new("price", "Price")
+                    .with_data_type("number")
+                    .with_number_format(
+                        ExcelNumberFormat::new()
+                            .with_style("currency")
+                            .with_currency_symbol("¥")
+                            .with_thousands(true),
+                    ),
+                ExcelColumnInfo::new("rate", "Rate")
+                    .with_data_type("number")
+                    .with_number_format(
+                        ExcelNumberFormat::new()
+                            .with_style("percent")
+                            .with_decimals(1),
+                    ),
+                ExcelColumnInfo::new("weight", "Weight").with_data_type("number"),
+            ],
+            "senlinz",
+            "2024-11-01T08:00:00",
+        )
+        .unwrap()
+    }
+
+    fn read_zip_entry(bytes: &[u8], name: &str) -> String {
+        let mut archive = zip::ZipArchive::new(std::io::Cursor::new(bytes)).unwrap();
+        let mut content = String::new();
+        std::io::Read::read_to_string(&mut archive.by_name(name).unwrap(), &mut content).unwrap();
+        content
+    }
+
+    #[test]
+    fn number_format_builds_patterns_and_display_text() {
+        // Arrange
+        let accounting = ExcelNumberFormat::new().with_style("accounting");
+        let thousands = ExcelNumberFormat::new()
+            .with_thousands(true)
+            .with_decimals(2);
+
+        // Act
+        let patterns = [accounting.get_pattern(), thousands.get_pattern()];
+
+        // Assert
+        assert_eq!(
+            patterns,
+            [
+                r#"_("$"* #,##0.00_);_("$"* \(#,##0.00\);_("$"* "-"??_);_(@_)"#.to_string(),
+                "#,##0.00".to_string()
+            ]
+        );
+        assert_eq!(accounting.format_number(-1234.5), "$ (1,234.50)");
+        assert_eq!(accounting.format_number(0.0), "$ -");
+        assert_eq!(thousands.format_number(-1234567.891), "-1,234,567.89");
+        assert_eq!(ExcelNumberFormat::new().round_number(0.1 + 0.2), 0.3);
+    }
+
+    #[tokio::test]
+    async fn export_number_format_columns_success() {
+        // Arrange
+        let data = ExcelData::new(vec![ExcelRowData::new(vec![
+            ExcelColumnData::new("price", "1234.5"),
+            ExcelColumnData::new_number("rate".into(), 0.125),
+            ExcelColumnData::new("weight", "6.9"),
+        ])]);
+
+        // Act
+        let excel_bytes = export_data_buffer(&create_price_excel_info(), &data)
+            .await
+            .unwrap();
+        let streamed_bytes =
+            export_data_streaming_buffer(create_price_excel_info(), &data).unwrap();
+
+        // Assert
+        for bytes in [&excel_bytes, &streamed_bytes] {
+            let styles = read_zip_entry(bytes, "xl/styles.xml");
+            assert!(styles.contains(r#"formatCode="&quot;¥&quot;#,##0.00;-&quot;¥&quot;#,##0.00""#));
+            assert!(styles.contains(r#"formatCode="0.0%""#));
+            let result = import_data_buffer(create_price_excel_info(), bytes).unwrap();
+            assert_eq!(
+                result.rows[0].columns[0].typed_value(),
+                &ExcelValue::Number(1234.5)
+            );
+        }
+    }
+
+    #[test]
+    fn import_number_mode_rounds_and_formats_values() {
+        // Arrange
+        let mut workbook = rust_xlsxwriter::Workbook::new();
+        let worksheet = workbook.add_worksheet().set_name("Prices").unwrap();
+        for (x, header) in ["Price", "Rate", "Weight"].into_iter().enumerate() {
+            worksheet.write_string(0, x as u16, header).unwrap();
+        }
+        worksheet.write_number(1, 0, 1234.5).unwrap();
+        worksheet.write_number(1, 1, 0.1 + 0.025).unwrap();
+        worksheet.write_number(1, 2, 0.1 + 0.2).unwrap();
+        let excel_bytes = workbook.save_to_buffer().unwrap();
+        let import = |mode: &str| {
+            let info = create_price_excel_info().with_import_number_mode(mode);
+            let result = import_data_buffer(info, &excel_bytes).unwrap();
+            result.rows[0]
+                .columns
+                .iter()
+                .map(|column| column.value().to_string())
+                .collect::<Vec<String>>()
+        };
+
+        // Act
+        let raw = import("raw");
+        let rounded = import("round");
+        let formatted = import("format");
+
+        // Assert
+        assert_eq!(raw[2], "0.30000000000000004");
+        assert_eq!(rounded, vec!["1234.5", "0.125", "0.3"]);
+        assert_eq!(formatted, vec!["¥1,234.50", "12.5%", "0.3"]);
+    }
+
+    #[test]
+    fn import_with_unknown_number_mode_fails() {
+        // Arrange
+        let info = create_price_excel_info().with_import_number_mode("scientific");
+        let excel_bytes = create_template_buffer(&create_price_excel_info()).unwrap();
+
+        // Act
+        let result = import_data_buffer(info, &excel_bytes);
+
+        // Assert
+        let error = result.err().unwrap();
+        let error = error.downcast_ref::<ImportExportError>().unwrap();
+        assert_eq!(error.code(), "IMPORT_NUMBER_MODE_INVALID");
+    }
+
+    fn create_shift_excel_info() -> ExcelInfo {
+        ExcelInfo::new(
+            "Shifts",
+            "Shifts",
+            vec![
+                ExcelColumnInfo::new("startsAt", "Starts At")
+                    .with_data_type("date")
+                    .with_date_options(
+                        ExcelDateOptions::new()
+                            .with_parse_patterns(vec![
+                                "%d/%m/%Y %H:%M".into(),
+                                "%d/%m/%Y %H:%M %:z".into(),
+                            ])
+                            .with_output_pattern("%d/%m/%Y %H:%M %:z")
+                            .with_excel_pattern("dd/mm/yyyy hh:mm")
+                            .with_timezone_offset("+08:00"),
+                    ),
+                ExcelColumnInfo::new("day", "Day")
+                    .with_data_type("dateOnly")
+                    .with_date_options(
+                        ExcelDateOptions::new()
+                            .with_parse_patterns(vec!["%d/%m/%Y".into()])
+                            .with_output_pattern("%d/%m/%Y"),
+                    ),
+                ExcelColumnInfo::new("clockIn", "Clock In")
+                    .with_data_type("timeOnly")
+                    .with_date_options(ExcelDateOptions::new().with_output_pattern("%H:%M:%S%.f")),
+                ExcelColumnInfo::new("loggedAt", "Logged At")
+                    .with_data_type("date")
+                    .with_date_options(
+                        ExcelDateOptions::new().with_output_pattern("%Y-%m-%d %H:%M:%S%.3f"),
+                    ),
+            ],
+            "senlinz",
+            "2024-11-01T08:00:00",
+        )
+        .unwrap()
+    }
+
+    fn create_date_time(date: (i32, u32, u32), time: (u32, u32, u32)) -> chrono::NaiveDateTime {
+        chrono::NaiveDate::from_ymd_opt(date.0, date.1, date.2)
+            .unwrap()
+            .and_hms_opt(time.0, time.1, time.2)
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn export_and_import_date_options_round_trip_success() {
+        // Arrange
+        let info = create_shift_excel_info();
+        let data = ExcelData::new(vec![
+            ExcelRowData::new(vec![
+                ExcelColumnData::new("startsAt", "18/10/2026 09:30"),
+                ExcelColumnData::new("day", "18/10/2026"),
+                ExcelColumnData::new("clockIn", "08:15:30"),
+            ]),
+            ExcelRowData::new(vec![
+                ExcelColumnData::with_typed_value(
+                    "startsAt",
+                    ExcelValue::DateTime(create_date_time((2026, 10, 18), (1, 30, 0))),
+                ),
+                ExcelColumnData::with_typed_value(
+                    "day",
+                    ExcelValue::DateTime(create_date_time((2026, 10, 19), (23, 0, 0))),
+                ),
+                ExcelColumnData::new("clockIn", "2026-10-18T07:45:00Z"),
+            ]),
+        ]);
+
+        // Act
+        let excel_bytes = export_data_buffer(&info, &data).await.unwrap();
+        let streamed_bytes =
+            export_data_streaming_buffer(create_shift_excel_info(), &data).unwrap();
+        let csv_bytes = export_csv_buffer(&info, &data, &CsvOptions::new()).unwrap();
+
+        // Assert
+        let csv_text = String::from_utf8(csv_bytes.clone()).unwrap();
+        assert!(csv_text.contains("18/10/2026 09:30 +08:00,18/10/2026,08:15:30"));
+        let imported = [
+            import_data_buffer(create_shift_excel_info(), &excel_bytes).unwrap(),
+            import_data_buffer(create_shift_excel_info(), &streamed_bytes).unwrap(),
+            import_csv_buffer(create_shift_excel_info(), &csv_bytes, &CsvOptions::new()).unwrap(),
+        ];
+        for result in imported.iter() {
+            let values: Vec<Vec<String>> = result
+                .rows
+                .iter()
+                .map(|row| {
+                    row.columns[..3]
+                        .iter()
+                        .map(|c| c.value().to_string())
+                        .collect()
+                })
+                .collect();
+            assert_eq!(
+                values,
+                vec![
+                    vec!["18/10/2026 09:30 +08:00", "18/10/2026", "08:15:30"],
+                    vec!["18/10/2026 09:30 +08:00", "19/10/2026", "07:45:00"],
+                ]
+            );
+            let starts_at = create_date_time((2026, 10, 18), (1, 30, 0));
+            assert_eq!(
+                result.rows[0].columns[0].typed_value(),
+                &ExcelValue::DateTime(starts_at)
+            );
+            assert_eq!(
+                result.rows[1].columns[1].typed_value(),
+                &ExcelValue::DateTime(create_date_time((2026, 10, 19), (0, 0, 0)))
+            );
+        }
+        for bytes in [&excel_bytes, &streamed_bytes] {
+            let mut workbook: calamine::Xlsx<_> =
+                calamine::open_workbook_from_rs(std::io::Cursor::new(bytes.clone())).unwrap();
+            let range = workbook.worksheet_range("Shifts").unwrap();
+            let Some(calamine::Data::DateTime(starts_at)) = range.get_value((1, 0)) else {
+                panic!("startsAt should be written as a date");
+            };
+            let wall_time = rust_xlsxwriter::ExcelDateTime::parse_from_str("2026-10-18T09:30:00")
+                .unwrap()
+                .to_excel();
+            assert!((starts_at.as_f64() - wall_time).abs() < 1e-9);
+            let Some(calamine::Data::DateTime(clock_in)) = range.get_value((1, 2)) else {
+                panic!("clockIn should be written as a time");
+            };
+            assert!(clock_in.as_f64() < 1.0);
+            let styles_xml = read_zip_entry(bytes, "xl/styles.xml");
+            assert!(styles_xml.contains("dd/mm/yyyy hh:mm"));
+            assert!(styles_xml.contains("hh:mm:ss"));
+        }
+    }
+
+    #[test]
+    fn import_date_columns_keep_fractional_seconds_and_parse_text() {
+        // Arrange
+        let mut workbook = rust_xlsxwriter::Workbook::new();
+        let worksheet = workbook.add_worksheet().set_name("Shifts").unwrap();
+        for (x, header) in ["Starts At", "Day", "Clock In", "Logged At"]
+            .into_iter()
+            .enumerate()
+        {
+            worksheet.write_string(0, x as u16, header).unwrap();
+        }
+        let logged_at =
+            rust_xlsxwriter::ExcelDateTime::parse_from_str("2024-11-01T12:30:45.250").unwrap();
+        let clock_in = rust_xlsxwriter::ExcelDateTime::from_hms_milli(6, 5, 4, 3).unwrap();
+        worksheet.write_string(1, 0, "01/11/2024 12:30").unwrap();
+        worksheet.write_string(1, 1, "02/11/2024").unwrap();
+        worksheet.write_number(1, 2, clock_in.to_excel()).unwrap();
+        worksheet.write_number(1, 3, logged_at.to_excel()).unwrap();
+        worksheet.write_string(2, 1, "2024-11-02").unwrap();
+        let excel_bytes = workbook.save_to_buffer().unwrap();
+
+        // Act
+        let result = import_data_buffer(create_shift_excel_info(), &excel_bytes).unwrap();
+        let report =
+            import_data_with_report_buffer(create_shift_excel_info(), &excel_bytes).unwrap();
+
+        // Assert
+        let columns = &result.rows[0].columns;
+        assert_eq!(columns[0].value(), "01/11/2024 12:30 +08:00");
+        assert_eq!(
+            columns[0].typed_value(),
+            &ExcelValue::DateTime(create_date_time((2024, 11, 1), (4, 30, 0)))
+        );
+        assert_eq!(columns[1].value(), "02/11/2024");
+        assert_eq!(columns[2].value(), "06:05:04.003");
+        assert_eq!(columns[3].value(), "2024-11-01 12:30:45.250");
+        assert_eq!(result.rows[1].columns[1].value(), "02/11/2024");
+        assert!(report.issues.is_empty());
+    }
+
+    #[test]
+    fn import_date_column_truncates_to_whole_seconds_at_day_boundary() {
+        // Arrange
+        let mut workbook = rust_xlsxwriter::Workbook::new();
+        let worksheet = workbook.add_worksheet();
+        worksheet.write_string(0, 0, "Logged At").unwrap();
+        let almost_midnight =
+            rust_xlsxwriter::ExcelDateTime::parse_from_str("2024-11-01T23:59:59").unwrap();
+        let serial = almost_midnight.to_excel() + 0.9996 / 86400.0;
+        worksheet.write_number(1, 0, serial).unwrap();
+        let excel_bytes = workbook.save_to_buffer().unwrap();
+        let create_info = |date_options: Option<ExcelDateOptions>| {
+            let mut column = ExcelColumnInfo::new("loggedAt", "Logged At").with_data_type("date");
+            if let Some(date_options) = date_options {
+                column = column.with_date_options(date_options);
+            }
+            ExcelInfo::new(
+                "Logs",
+                "sheet1",
+                vec![column],
+                "senlinz",
+                "2024-11-01T08:00:00",
+            )
+            .unwrap()
+        };
+
+        // Act
+        let result = import_data_buffer(create_info(None), &excel_bytes).unwrap();
+        let precise = import_data_buffer(
+            create_info(Some(
+                ExcelDateOptions::new().with_output_pattern("%Y-%m-%d %H:%M:%S%.3f"),
+            )),
+            &excel_bytes,
+        )
+        .unwrap();
+
+        // Assert
+        let column = &result.rows[0].columns[0];
+        assert_eq!(column.value(), "2024-11-01 23:59:59");
+        assert_eq!(
+            column.typed_value(),
+            &ExcelValue::DateTime(create_date_time((2024, 11, 1), (23, 59, 59)))
+        );
+        assert_eq!(
+            precise.rows[0].columns[0].value(),
+            "2024-11-02 00:00:00.000"
+        );
+    }
+
+    #[test]
+    fn invalid_schema_date_options_returns_error() {
+        // Arrange
+        let create_info = |date_options: ExcelDateOptions| {
+            ExcelInfo::new(
+                "Shifts",
+                "Shifts",
+                vec![ExcelColumnInfo::new("startsAt", "Starts At")
+                    .with_data_type("date")
+                    .with_date_options(date_options)],
+                "senlinz",
+                "2024-11-01T08:00:00",
+            )
+        };
+
+        // Act
+        let invalid_offset = create_info(ExcelDateOptions::new().with_timezone_offset("+25:00"));
+        let invalid_pattern =
+            create_info(ExcelDateOptions::new().with_parse_patterns(vec!["%d/%m/%".into()]));
+
+        // Assert
+        assert_eq!(
+            invalid_offset.err().unwrap(),
+            "Invalid definition: column 'startsAt' uses invalid dateOptions timezoneOffset '+25:00'. Use Z or an offset such as +08:00."
+        );
+        assert_eq!(
+            invalid_pattern.err().unwrap(),
+            "Invalid definition: column 'startsAt' uses invalid dateOptions pattern '%d/%m/%'."
+        );
+    }
+
+    fn create_badge_excel_info() -> ExcelInfo {
+        ExcelInfo::new(
+            "Badges",
+            "Badges",
+            vec![
+                ExcelColumnInfo::new("code", "Code")
+                    .with_required(true)
+                    .with_constraints(ExcelConstraints::new().with_pattern("^[A-Z]{3}$")),
+                ExcelColumnInfo::new("level", "Level")
+                    .with_data_type("number")
+                    .with_constraints(ExcelConstraints::new().with_min(1.0).with_max(100.0)),
+                ExcelColumnInfo::new("title", "Title").with_constraints(
+                    ExcelConstraints::new()
+                        .with_min_length(2)
+                        .with_max_length(20)
+                        .with_error_message("Titles have 2 to 20 characters."),
+                ),
+            ],
+            "senlinz",
+            "2024-11-01T08:00:00",
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn create_template_with_constraints_writes_data_validations() {
+        // Arrange
+        let info = create_badge_excel_info();
+        let data = ExcelData::new(vec![ExcelRowData::new(vec![
+            ExcelColumnData::new("code", "ABC"),
+            ExcelColumnData::new("level", "5"),
+        ])]);
+
+        // Act
+        let template_bytes = create_template_buffer(&info).unwrap();
+        let streamed_bytes =
+            export_data_streaming_buffer(create_badge_excel_info(), &data).unwrap();
+
+        // Assert
+        for bytes in [&template_bytes, &streamed_bytes] {
+            let sheet_xml = read_zip_entry(bytes, "xl/worksheets/sheet1.xml");
+            assert!(sheet_xml.contains(r#"prompt="Required. Must match ^[A-Z]{3}$.""#));
+            assert!(sheet_xml.contains(r#"type="decimal""#));
+            assert!(sheet_xml.contains("<formula1>1</formula1><formula2>100</formula2>"));
+            assert!(sheet_xml.contains(r#"prompt="Enter a number from 1 to 100.""#));
+            assert!(sheet_xml.contains(r#"type="textLength""#));
+            assert!(sheet_xml.contains(r#"error="Titles have 2 to 20 characters.""#));
+            assert_eq!(sheet_xml.matches("allowBlank=\"1\"").count(), 2);
+        }
+    }
+
+    #[tokio::test]
+    async fn export_data_validates_only_the_data_rows() {
+        // Arrange
+        let info = create_badge_excel_info().with_summary(
+            ExcelSummaryOptions::new(vec![ExcelAggregate::new("level", "sum")]).with_label("Sum"),
+        );
+        let create_badge = |code: &str, level: &str| {
+            ExcelRowData::new(vec![
+                ExcelColumnData::new("code", code),
+                ExcelColumnData::new("level", level),
+            ])
+        };
+        let data = ExcelData::new(vec![create_badge("ABC", "5"), create_badge("DEF", "7")]);
+
+        // Act
+        let excel_bytes = export_data_buffer(&info, &data).await.unwrap();
+        let template_bytes = create_template_buffer(&info).unwrap();
+
+        // Assert
+        let sheet_xml = read_zip_entry(&excel_bytes, "xl/worksheets/sheet1.xml");
+        assert!(sheet_xml.contains(r#"sqref="A2:A3""#));
+        assert!(sheet_xml.contains(r#"sqref="B2:B3""#));
+        assert!(!sheet_xml.contains("A1048576"));
+        let template_xml = read_zip_entry(&template_bytes, "xl/worksheets/sheet1.xml");
+        assert!(template_xml.contains(r#"sqref="A2:A1048576""#));
+    }
+
+    #[test]
+    fn import_data_enforces_constraints_with_cell_codes() {
+        // Arrange
+        let create_bytes = |rows: &[[&str; 3]]| {
+            let mut workbook = rust_xlsxwriter::Workbook::new();
+            let worksheet = workbook.add_worksheet().set_name("Badges").unwrap();
+            for (x, header) in ["Code", "Level", "Title"].into_iter().enumerate() {
+                worksheet.write_string(0, x as u16, header).unwrap();
+            }
+            for (y, row) in rows.iter().enumerate() {
+                worksheet.write_string(y as u32 + 1, 0, row[0]).unwrap();
+                worksheet
+                    .write_number(y as u32 + 1, 1, row[1].parse::<f64>().unwrap())
+                    .unwrap();
+                worksheet.write_string(y as u32 + 1, 2, row[2]).unwrap();
+            }
+            workbook.save_to_buffer().unwrap()
+        };
+        let valid_bytes = create_bytes(&[["ABC", "1", "Gym leader"]]);
+        let invalid_bytes = create_bytes(&[
+            ["ABC", "100", "Gym leader"],
+            ["ABC", "0", "Rival"],
+            ["ABCD", "101", "Champion"],
+            ["", "5", "X"],
+        ]);
+
+        // Act
+        let valid = import_data_buffer(create_badge_excel_info(), &valid_bytes);
+        let invalid = import_data_buffer(create_badge_excel_info(), &invalid_bytes);
+        let report =
+            import_data_with_report_buffer(create_badge_excel_info(), &invalid_bytes).unwrap();
+
+        // Assert
+        assert_eq!(valid.unwrap().rows.len(), 1);
+        let error = invalid.err().unwrap();
+        let error = error.downcast_ref::<ImportExportError>().unwrap();
+        assert_eq!(error.code(), "IMPORT_CONSTRAINT_VIOLATED");
+        assert!(error
+            .params()
+            .contains(&("issueCode".to_string(), "CELL_BELOW_MIN".to_string())));
+        assert!(error
+            .params()
+            .contains(&("cell".to_string(), "B3".to_string())));
+        let issues = report
+            .issues
+            .iter()
+            .map(|issue| format!("{:?}", issue))
+            .collect::<Vec<String>>();
+        assert_eq!(
+            issues,
+            vec![
+                "Badges!B3 [level] CELL_BELOW_MIN: 0",
+                "Badges!A4 [code] CELL_PATTERN_MISMATCH: ABCD",
+                "Badges!B4 [level] CELL_ABOVE_MAX: 101",
+                "Badges!A5 [code] CELL_REQUIRED: ",
+                "Badges!C5 [title] CELL_TOO_SHORT: X",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn import_grouped_data_enforces_constraints() {
+        // Arrange
+        let mut info = create_move_power_excel_info();
+        for column in info
+            .columns
+            .iter_mut()
+            .filter(|column| column.key == "power")
+        {
+            column.constraints = Some(ExcelConstraints::new().with_max(100.0));
+        }
+        let create_move = |name: &str, power: &str| {
+            ExcelRowData::new(vec![
+                ExcelColumnData::new("move", name),
+                ExcelColumnData::new("power", power),
+            ])
+        };
+        let data = ExcelData::new(vec![ExcelRowData::new(vec![
+            ExcelColumnData::new("name", "Charizard"),
+            ExcelColumnData::new_root_group(
+                "moves".into(),
+                vec![create_move("Ember", "40"), create_move("Blast Burn", "150")],
+            ),
+        ])]);
+        let excel_bytes = export_data_buffer(&info, &data).await.unwrap();
+
+        // Act
+        let result = import_grouped_data_buffer(info, &excel_bytes);
+
+        // Assert
+        let error = result.err().unwrap();
+        let error = error.downcast_ref::<ImportExportError>().unwrap();
+        assert_eq!(error.code(), "IMPORT_CONSTRAINT_VIOLATED");
+        assert!(error
+            .params()
+            .contains(&("issueCode".to_string(), "CELL_ABOVE_MAX".to_string())));
+        assert!(error
+            .params()
+            .contains(&("cell".to_string(), "D4".to_string())));
+    }
+
+    #[test]
+    fn invalid_schema_constraints_returns_error() {
+        // Arrange
+        let create_info = |column: ExcelColumnInfo| {
+            ExcelInfo::new(
+                "Badges",
+                "Badges",
+                vec![column],
+                "senlinz",
+                "2024-11-01T08:00:00",
+            )
+        };
+
+        // Act
+        let text_range = create_info(
+            ExcelColumnInfo::new("code", "Code")
+                .with_constraints(ExcelConstraints::new().with_min(1.0)),
+        );
+        let invalid_pattern = create_info(
+            ExcelColumnInfo::new("code", "Code")
+                .with_constraints(ExcelConstraints::new().with_pattern("[A-Z")),
+        );
+
+        // Assert
+        assert_eq!(
+            text_range.err().unwrap(),
+            "Invalid definition: column 'code' can only use min and max constraints with the number dataType."
+        );
+        assert_eq!(
+            invalid_pattern.err().unwrap(),
+            "Invalid definition: column 'code' uses invalid constraint pattern '[A-Z'."
+        );
+    }
+
+    #[tokio::test]
+    async fn export_data_writes_conditional_formats_over_data_rows() {
+        // Arrange
+        let highlight = ExcelCellFormat::new()
+            .with_bold(true)
+            .with_background_color("#FFC7CE".into());
+        let info = ExcelInfo::new(
+            "Pokemon",
+            "Pokemon",
+            vec![
+                ExcelColumnInfo::new("name", "Name").with_conditional_formats(vec![
+                    ExcelConditionalFormat::new("contains")
+                        .with_value("saur")
+                        .with_format(highlight.clone()),
+                    ExcelConditionalFormat::new("regex")
+                        .with_value("^Char\\w+$")
+                        .with_format(highlight.clone()),
+                    ExcelConditionalFormat::new("blank").with_format(highlight.clone()),
+                ]),
+                ExcelColumnInfo::new("hp", "HP")
+                    .with_data_type("number")
+                    .with_conditional_formats(vec![
+                        ExcelConditionalFormat::new("gt")
+                            .with_value("50")
+                            .with_format(highlight.clone()),
+                        ExcelConditionalFormat::new("between")
+                            .with_value("40")
+                            .with_end_value("45")
+                            .with_format(highlight),
+                        ExcelConditionalFormat::new("colorScale")
+                            .with_colors(vec!["#F8696B".into(), "#63BE7B".into()]),
+                        ExcelConditionalFormat::new("dataBar").with_colors(vec!["#638EC6".into()]),
+                    ]),
+            ],
+            "senlinz",
+            "2024-11-01T08:00:00",
+        )
+        .unwrap();
+        let data = ExcelData::new(
+            [
+                ("Bulbasaur", "45"),
+                ("Charmander", "39"),
+                ("Squirtle", "44"),
+            ]
+            .iter()
+            .map(|(name, hp)| {
+                ExcelRowData::new(vec![
+                    ExcelColumnData::new("name", name),
+                    ExcelColumnData::new("hp", hp),
+                ])
+            })
+            .collect(),
+        );
+
+        // Act
+        let bytes = export_data_buffer(&info, &data).await.unwrap();
+
+        // Assert
+        let sheet_xml = read_zip_entry(&bytes, "xl/worksheets/sheet1.xml");
+        assert!(sheet_xml.contains(r#"<conditionalFormatting sqref="A2:A4">"#));
+        assert!(sheet_xml.contains(
+            r#"type="containsText" dxfId="0" priority="1" operator="containsText" text="saur""#
+        ));
+        assert!(sheet_xml.contains(r#"<formula>_xlfn.REGEXTEST(A2,"^Char\w+$")</formula>"#));
+        assert!(sheet_xml.contains(r#"type="containsBlanks""#));
+        assert!(sheet_xml.contains(r#"<conditionalFormatting sqref="B2:B4">"#));
+        assert!(sheet_xml.contains(r#"operator="greaterThan"><formula>50</formula>"#));
+        assert!(sheet_xml.contains("<formula>40</formula><formula>45</formula>"));
+        assert!(sheet_xml.contains(r#"<cfRule type="colorScale""#));
+        assert!(sheet_xml.contains(r#"<color rgb="FF638EC6"/>"#));
+        let styles_xml = read_zip_entry(&bytes, "xl/styles.xml");
+        assert!(styles_xml.contains(r#"<dxfs count="1">"#));
+        let imported = import_data_buffer(info, &bytes).unwrap();
+        assert_eq!(imported.rows[1].columns[0].value(), "Charmander");
+    }
+
+    #[test]
+    fn invalid_schema_conditional_formats_returns_error() {
+        // Arrange
+        let create_info = |conditional_format: ExcelConditionalFormat| {
+            ExcelInfo::new(
+                "Pokemon",
+                "Pokemon",
+                vec![ExcelColumnInfo::new("hp", "HP")
+                    .with_data_type("number")
+                    .with_conditional_formats(vec![conditional_format])],
+                "senlinz",
+                "2024-11-01T08:00:00",
+            )
+        };
+
+        // Act
+        let unknown_rule = create_info(ExcelConditionalFormat::new("startsWith"));
+        let missing_end_value = create_info(
+            ExcelConditionalFormat::new("between")
+                .with_value("1")
+                .with_format(ExcelCellFormat::new()),
+        );
+        let missing_format = create_info(ExcelConditionalFormat::new("notBlank"));
+        let too_many_colors = create_info(
+            ExcelConditionalFormat::new("dataBar").with_colors(vec!["red".into(), "blue".into()]),
+        );
+
+        // Assert
+        assert!(unknown_rule.err().unwrap().starts_with(
+            "Invalid definition: column 'hp' uses unsupported conditionalFormat rule 'startsWith'."
+        ));
+        assert_eq!(
+            missing_end_value.err().unwrap(),
+            "Invalid definition: column 'hp' uses conditionalFormat rule 'between' without an endValue."
+        );
+        assert_eq!(
+            missing_format.err().unwrap(),
+            "Invalid definition: column 'hp' uses conditionalFormat rule 'notBlank' without a format."
+        );
+        assert!(too_many_colors
+            .err()
+            .unwrap()
+            .starts_with("Invalid definition: column 'hp' passes 2 colors"));
+    }
+
+    #[test]
+    fn row_format_rules_trim_the_rule_value() {
+        let row_format = |rule: &str| ExcelRowFormat::new("status", rule).with_value("overdue ");
+
+        assert!(row_format("eq").matches(" overdue"));
+        assert!(!row_format("ne").matches("overdue"));
+        assert!(row_format("contains").matches("overdue"));
+    }
+
+    #[tokio::test]
+    async fn export_data_applies_row_formats_across_merged_and_missing_cells() {
+        // Arrange
+        let info = create_move_power_excel_info().with_row_formats(vec![
+            ExcelRowFormat::new("name", "eq")
+                .with_value("Bulbasaur")
+                .with_format(ExcelCellFormat::new().with_background_color("#FFC7CE".into())),
+            ExcelRowFormat::new("name", "contains")
+                .with_value("Char")
+                .with_format(ExcelCellFormat::new().with_bold(true))
+                .with_columns(vec!["total".into()]),
+        ]);
+        let data = ExcelData::new(vec![
+            ExcelRowData::new(vec![
+                ExcelColumnData::new("name", "Bulbasaur"),
+                ExcelColumnData::new_root_group(
+                    "moves".into(),
+                    vec![
+                        ExcelRowData::new(vec![
+                            ExcelColumnData::new("move", "Tackle"),
+                            ExcelColumnData::new("power", "40"),
+                        ]),
+                        ExcelRowData::new(vec![ExcelColumnData::new("power", "35")]),
+                    ],
+                ),
+            ]),
+            ExcelRowData::new(vec![
+                ExcelColumnData::new("name", "Charmander"),
+                ExcelColumnData::new_root_group(
+                    "moves".into(),
+                    vec![ExcelRowData::new(vec![ExcelColumnData::new(
+                        "move", "Ember",
+                    )])],
+                ),
+            ]),
+        ]);
+
+        // Act
+        let bytes = export_data_buffer(&info, &data).await.unwrap();
+
+        // Assert
+        let sheet_xml = read_zip_entry(&bytes, "xl/worksheets/sheet1.xml");
+        let get_style = |cell: &str| {
+            let pattern = format!(r#"<c r="{}" s="(\d+)""#, cell);
+            regex::Regex::new(&pattern)
+                .unwrap()
+                .captures(&sheet_xml)
+                .map(|captures| captures[1].to_string())
+        };
+        let row_style = get_style("A3");
+        assert!(row_style.is_some());
+        for cell in ["B3", "C3", "D3", "E3", "A4", "B4", "D4", "E4"] {
+            assert_eq!(get_style(cell), row_style, "cell {}", cell);
+        }
+        assert_eq!(get_style("C4"), row_style);
+        assert_ne!(get_style("A5"), row_style);
+        assert_ne!(get_style("B5"), get_style("A5"));
+        assert_eq!(get_style("E5"), get_style("A5"));
+    }
+
+    #[tokio::test]
+    async fn export_data_with_invalid_row_format_returns_error() {
+        // Arrange
+        let info = create_move_power_excel_info().with_row_formats(vec![ExcelRowFormat::new(
+            "level", "gt",
+        )
+        .with_value("10")
+        .with_format(ExcelCellFormat::new())]);
+        let json = r#"{"name":"Moves","columns":[{"key":"name","name":"Name"}],"rowFormats":[{"columnKey":"name","rule":"gt","value":"high","format":{"bold":true}}]}"#;
+
+        // Act
+        let result = export_data_buffer(&info, &ExcelData::new(vec![])).await;
+        let from_json = ExcelInfo::from_json(json);
+
+        // Assert
+        let error = result.err().unwrap();
+        let error = error.downcast_ref::<ImportExportError>().unwrap();
+        assert_eq!(error.code(), "INVALID_DEFINITION");
+        assert_eq!(
+            error.to_string(),
+            "Invalid definition: rowFormat references unknown column 'level'."
+        );
+        assert_eq!(
+            from_json.err().unwrap(),
+            "Invalid definition: rowFormat on column 'name' compares with 'high', which is not a number."
+        );
+    }
+
+    fn create_stats_excel_info() -> ExcelInfo {
+        ExcelInfo::new(
+            "Stats",
+            "Stats",
+            vec![
+                ExcelColumnInfo::new("name", "Name"),
+                ExcelColumnInfo::new("hp", "HP").with_data_type("number"),
+            ],
+            "senlinz",
+            "2024-11-01T08:00:00",
+        )
+        .unwrap()
+    }
+
+    fn create_stats_data() -> ExcelData {
+        ExcelData::new(vec![
+            ExcelRowData::new(vec![
+                ExcelColumnData::new("name", "Bulbasaur"),
+                ExcelColumnData::new("hp", "45"),
+            ]),
+            ExcelRowData::new(vec![
+                ExcelColumnData::new("name", "Charmander"),
+                ExcelColumnData::new("hp", "39"),
+            ]),
+        ])
+    }
+
+    #[tokio::test]
+    async fn export_data_writes_table_with_totals_row_and_import_skips_it() {
+        // Arrange
+        let create_info = || {
+            create_stats_excel_info().with_table(
+                ExcelTableOptions::new()
+                    .with_name("Pokedex")
+                    .with_style("Medium2")
+                    .with_totals(vec![ExcelTableTotal::new("hp", "sum")]),
+            )
+        };
+
+        // Act
+        let bytes = export_data_buffer(&create_info(), &create_stats_data())
+            .await
+            .unwrap();
+        let imported = import_data_buffer(create_info(), &bytes).unwrap();
+        let empty_bytes = export_data_buffer(&create_info(), &ExcelData::new(vec![]))
+            .await
+            .unwrap();
+
+        // Assert
+        let table_xml = read_zip_entry(&bytes, "xl/tables/table1.xml");
+        assert!(table_xml.contains(r#"name="Pokedex""#));
+        assert!(table_xml.contains(r#"ref="A1:B4""#));
+        assert!(table_xml.contains(r#"totalsRowCount="1""#));
+        assert!(table_xml.contains(r#"totalsRowLabel="Total""#));
+        assert!(table_xml.contains(r#"totalsRowFunction="sum""#));
+        assert!(table_xml.contains(r#"name="TableStyleMedium2""#));
+        assert!(table_xml.contains(r#"showRowStripes="1""#));
+        let sheet_xml = read_zip_entry(&bytes, "xl/worksheets/sheet1.xml");
+        assert!(sheet_xml.contains("<f>SUBTOTAL(109,[HP])</f>"));
+        assert_eq!(imported.rows.len(), 2);
+        assert_eq!(imported.rows[1].columns[0].value(), "Charmander");
+        let empty_table_xml = read_zip_entry(&empty_bytes, "xl/tables/table1.xml");
+        assert!(empty_table_xml.contains(r#"ref="A1:B3""#));
+    }
+
+    #[tokio::test]
+    async fn import_data_keeps_table_data_row_labelled_total() {
+        // Arrange
+        let mut data = create_stats_data();
+        data.rows.push(ExcelRowData::new(vec![
+            ExcelColumnData::new("name", "Total"),
+            ExcelColumnData::new("hp", "84"),
+        ]));
+        let with_totals = || {
+            create_stats_excel_info().with_table(
+                ExcelTableOptions::new().with_totals(vec![ExcelTableTotal::new("hp", "sum")]),
+            )
+        };
+        let without_totals = || create_stats_excel_info().with_table(ExcelTableOptions::new());
+        let totals_bytes = export_data_buffer(&with_totals(), &data).await.unwrap();
+        let plain_bytes = export_data_buffer(&without_totals(), &data).await.unwrap();
+
+        // Act
+        let from_totals = import_data_buffer(with_totals(), &totals_bytes).unwrap();
+        let from_plain = import_data_buffer(without_totals(), &plain_bytes).unwrap();
+        let from_plain_with_totals_schema =
+            import_data_buffer(with_totals(), &plain_bytes).unwrap();
+
+        // Assert
+        for imported in [from_totals, from_plain, from_plain_with_totals_schema] {
+            assert_eq!(imported.rows.len(), 3);
+            assert_eq!(imported.rows[2].columns[0].value(), "Total");
+            assert_eq!(imported.rows[2].columns[1].value(), "84");
+        }
+    }
+
+    #[tokio::test]
+    async fn export_data_with_autofilter_writes_filter_over_rows() {
+        // Arrange
+        let info = create_stats_excel_info().with_autofilter(true);
+        let data = create_stats_data();
+
+        // Act
+        let bytes = export_data_buffer(&info, &data).await.unwrap();
+        let streamed_bytes =
+            export_data_streaming_buffer(create_stats_excel_info().with_autofilter(true), &data)
+                .unwrap();
+
+        // Assert
+        for bytes in [&bytes, &streamed_bytes] {
+            let sheet_xml = read_zip_entry(bytes, "xl/worksheets/sheet1.xml");
+            assert!(sheet_xml.contains(r#"<autoFilter ref="A1:B3"/>"#));
+        }
+    }
+
+    #[test]
+    fn invalid_schema_table_options_returns_error() {
+        // Arrange
+        let create_json = |table: &str| {
+            format!(
+                r#"{{"name":"Stats","columns":[{{"key":"name","name":"Name"}},{{"key":"hp","name":"HP","dataType":"number"}}],"table":{}}}"#,
+                table
+            )
+        };
+        let grouped = r#"{"name":"Stats","columns":[{"key":"stats","name":"Stats"},{"key":"hp","name":"HP","parent":"stats"}],"table":{}}"#;
+
+        // Act
+        let invalid_name = ExcelInfo::from_json(&create_json(r#"{"name":"A1"}"#));
+        let invalid_style = ExcelInfo::from_json(&create_json(r#"{"style":"Medium29"}"#));
+        let first_column_total = ExcelInfo::from_json(&create_json(
+            r#"{"totals":[{"columnKey":"name","function":"count"}]}"#,
+        ));
+        let parent_columns = ExcelInfo::from_json(grouped);
+
+        // Assert
+        assert!(invalid_name
+            .err()
+            .unwrap()
+            .starts_with("Invalid definition: table name 'A1' must start"));
+        assert!(invalid_style
+            .err()
+            .unwrap()
+            .starts_with("Invalid definition: table style 'Medium29'"));
+        assert_eq!(
+            first_column_total.err().unwrap(),
+            "Invalid definition: table total cannot use column 'name', since the first column holds the total label."
+        );
+        assert_eq!(
+            parent_columns.err().unwrap(),
+            "Invalid definition: table output requires a single header row without parent or dataGroup columns."
+        );
+        assert!(ExcelInfo::from_json(&create_json(
+            r#"{"name":"Pokedex_2024","style":"TableStyleDark11"}"#
+        ))
+        .is_ok());
+    }
+
+    #[tokio::test]
+    async fn export_data_with_misspelled_table_style_fails() {
+        // Arrange
+        let info = create_stats_excel_info()
+            .with_table(ExcelTableOptions::new().with_style("TableStyleMeduim9"));
+
+        // Act
+        let error = export_data_buffer(&info, &create_stats_data())
+            .await
+            .unwrap_err();
+
+        // Assert
+        let error = error.downcast_ref::<ImportExportError>().unwrap();
+        assert_eq!(error.code(), "INVALID_DEFINITION");
+        assert!(error
+            .to_string()
+            .starts_with("Invalid definition: table style 'TableStyleMeduim9'"));
+    }
+
+    #[tokio::test]
+    async fn export_data_with_auto_width_fits_columns_to_cjk_text() {
+        // Arrange
+        let info = ExcelInfo::new(
+            "Products",
+            "Products",
+            vec![
+                ExcelColumnInfo::new("name", "Name"),
+                ExcelColumnInfo::new("sku", "SKU").with_auto_width(false),
+                ExcelColumnInfo::new("note", "Note"),
+                ExcelColumnInfo::new("hp", "HP").with_data_type("number"),
+            ],
+            "senlinz",
+            "2024-11-01T08:00:00",
+        )
+        .unwrap()
+        .with_auto_width(ExcelAutoWidth::new().with_max_width(30.0));
+        let data = ExcelData::new(vec![
+            ExcelRowData::new(vec![
+                ExcelColumnData::new("name", "皮卡丘电光一闪"),
+                ExcelColumnData::new("sku", "SKU-000000000001"),
+                ExcelColumnData::new(
+                    "note",
+                    "A very long note that keeps going past any sane column",
+                ),
+                ExcelColumnData::new("hp", "35"),
+            ]),
+            ExcelRowData::new(vec![
+                ExcelColumnData::new("name", "Pikachu"),
+                ExcelColumnData::new("sku", "SKU-2"),
+                ExcelColumnData::new("note", ""),
+                ExcelColumnData::new("hp", "120"),
+            ]),
+        ]);
+
+        // Act
+        let bytes = export_data_buffer(&info, &data).await.unwrap();
+
+        // Assert
+        let sheet_xml = read_zip_entry(&bytes, "xl/worksheets/sheet1.xml");
+        assert!(sheet_xml.contains(r#"<col min="1" max="1" width="16.7109375" customWidth="1"/>"#));
+        assert!(sheet_xml.contains(r#"<col min="2" max="2" width="10.7109375" customWidth="1"/>"#));
+        assert!(sheet_xml.contains(r#"<col min="3" max="3" width="30.7109375" customWidth="1"/>"#));
+        assert!(sheet_xml.contains(r#"<col min="4" max="4" width="8.7109375" customWidth="1"/>"#));
+        assert_eq!(get_display_width("皮卡丘 Pikachu\nピカチュウ"), 14);
+    }
+
+    #[tokio::test]
+    async fn export_data_with_auto_width_fits_date_columns_to_their_pattern() {
+        // Arrange
+        let info = ExcelInfo::new(
+            "Shifts",
+            "Shifts",
+            vec![
+                ExcelColumnInfo::new("day", "Day")
+                    .with_data_type("dateOnly")
+                    .with_date_options(ExcelDateOptions::new().with_excel_pattern("dd/mm/yyyy")),
+                ExcelColumnInfo::new("at", "At")
+                    .with_data_type("date")
+                    .with_date_options(
+                        ExcelDateOptions::new().with_excel_pattern("yyyy/mm/dd hh:mm:ss"),
+                    ),
+            ],
+            "senlinz",
+            "2024-11-01T08:00:00",
+        )
+        .unwrap()
+        .with_auto_width(ExcelAutoWidth::new());
+        let data = ExcelData::new(vec![ExcelRowData::new(vec![
+            ExcelColumnData::new("day", "2024-11-01"),
+            ExcelColumnData::new("at", "2024-11-01T20:05:09"),
+        ])]);
+
+        // Act
+        let bytes = export_data_buffer(&info, &data).await.unwrap();
+
+        // Assert
+        let sheet_xml = read_zip_entry(&bytes, "xl/worksheets/sheet1.xml");
+        assert!(sheet_xml.contains(r#"<col min="1" max="1" width="12.7109375" customWidth="1"/>"#));
+        assert!(sheet_xml.contains(r#"<col min="2" max="2" width="21.7109375" customWidth="1"/>"#));
+    }
+
+    #[test]
+    fn invalid_schema_auto_width_returns_error() {
+        // Arrange
+        let create_json = |auto_width: &str| {
+            format!(
+                r#"{{"name":"Stats","columns":[{{"key":"name","name":"Name","autoWidth":false}}],"autoWidth":{}}}"#,
+                auto_width
+            )
+        };
+
+        // Act
+        let min_over_max = ExcelInfo::from_json(&create_json(r#"{"minWidth":40,"maxWidth":20}"#));
+        let over_excel_max = ExcelInfo::from_json(&create_json(r#"{"maxWidth":300}"#));
+        let valid = ExcelInfo::from_json(&create_json(r#"{"minWidth":12,"padding":0}"#));
+
+        // Assert
+        assert_eq!(
+            min_over_max.err().unwrap(),
+            "Invalid definition: autoWidth needs 0 <= minWidth <= maxWidth <= 255 and a non-negative padding, got minWidth 40, maxWidth 20 and padding 2."
+        );
+        assert!(over_excel_max
+            .err()
+            .unwrap()
+            .starts_with("Invalid definition: autoWidth needs"));
+        let valid = valid.unwrap();
+        assert_eq!(valid.auto_width.as_ref().unwrap().max_width, 60.0);
+        assert_eq!(valid.get_auto_width(&valid.columns[0]), None);
+    }
+
+    #[tokio::test]
+    async fn export_data_writes_subtotals_and_totals_and_import_skips_them() {
+        // Arrange
+        let create_info = || {
+            let mut info = create_move_power_excel_info().with_summary(
+                ExcelSummaryOptions::new(vec![
+                    ExcelAggregate::new("power", "sum"),
+                    ExcelAggregate::new("move", "count"),
+                ])
+                .with_subtotals(true),
+            );
+            for column in info
+                .columns
+                .iter_mut()
+                .filter(|column| column.key == "power")
+            {
+                column.conditional_formats =
+                    vec![ExcelConditionalFormat::new("gt").with_value("38")];
+            }
+            info
+        };
+        let create_move = |name: &str, power: &str| {
+            ExcelRowData::new(vec![
+                ExcelColumnData::new("move", name),
+                ExcelColumnData::new("power", power),
+            ])
+        };
+        let data = ExcelData::new(vec![
+            ExcelRowData::new(vec![
+                ExcelColumnData::new("name", "Bulbasaur"),
+                ExcelColumnData::new_root_group(
+                    "moves".into(),
+                    vec![create_move("Tackle", "40"), create_move("Vine Whip", "35")],
+                ),
+            ]),
+            ExcelRowData::new(vec![
+                ExcelColumnData::new("name", "Charmander"),
+                ExcelColumnData::new_root_group("moves".into(), vec![create_move("Ember", "40")]),
+            ]),
+        ]);
+
+        // Act
+        let excel_bytes = export_data_buffer(&create_info(), &data).await.unwrap();
+        let grouped = import_grouped_data_buffer(create_info(), &excel_bytes).unwrap();
+        let flat = import_data_buffer(create_info(), &excel_bytes).unwrap();
+
+        // Assert
+        let sheet_xml = read_zip_entry(&excel_bytes, "xl/worksheets/sheet1.xml");
+        assert!(sheet_xml.contains(r#"<conditionalFormatting sqref="D3:D4 D6:D6">"#));
+        let mut workbook: calamine::Xlsx<_> =
+            calamine::open_workbook_from_rs(std::io::Cursor::new(excel_bytes)).unwrap();
+        let formulas = workbook.worksheet_formula("Moves").unwrap();
+        let get_formula = |row: u32, column: u32| formulas.get_value((row, column)).cloned();
+        assert_eq!(get_formula(4, 3).as_deref(), Some("SUBTOTAL(9,D3:D4)"));
+        assert_eq!(get_formula(4, 2).as_deref(), Some("SUBTOTAL(3,C3:C4)"));
+        assert_eq!(get_formula(6, 3).as_deref(), Some("SUBTOTAL(9,D6:D6)"));
+        assert_eq!(get_formula(7, 3).as_deref(), Some("SUBTOTAL(9,D3:D7)"));
+        let range = workbook.worksheet_range("Moves").unwrap();
+        assert_eq!(range.get_value((4, 0)).unwrap().to_string(), "Subtotal");
+        assert_eq!(range.get_value((7, 0)).unwrap().to_string(), "Total");
+        assert_eq!(range.get_value((7, 3)).unwrap().to_string(), "115");
+        assert_eq!(range.get_value((7, 2)).unwrap().to_string(), "3");
+
+        assert_eq!(grouped.rows.len(), 2);
+        assert_eq!(grouped.rows[0].columns[0].value(), "Bulbasaur");
+        assert_eq!(grouped.rows[0].columns[2].children.len(), 2);
+        assert_eq!(grouped.rows[1].columns[2].children.len(), 1);
+        let flat_moves = flat
+            .rows
+            .iter()
+            .map(|row| row.columns[2].value())
+            .collect::<Vec<&str>>();
+        assert_eq!(flat_moves, vec!["Tackle", "Vine Whip", "Ember"]);
+    }
+
+    #[tokio::test]
+    async fn export_data_with_precomputed_summary_writes_values() {
+        // Arrange
+        let create_info = || {
+            create_stats_excel_info().with_summary(
+                ExcelSummaryOptions::new(vec![ExcelAggregate::new("hp", "avg")
+                    .with_format(ExcelCellFormat::new().with_color("#FF0000"))])
+                .with_precomputed(true)
+                .with_label("Average"),
+            )
+        };
+
+        // Act
+        let excel_bytes = export_data_buffer(&create_info(), &create_stats_data())
+            .await
+            .unwrap();
+        let imported = import_data_buffer(create_info(), &excel_bytes).unwrap();
+
+        // Assert
+        let mut workbook: calamine::Xlsx<_> =
+            calamine::open_workbook_from_rs(std::io::Cursor::new(excel_bytes)).unwrap();
+        let range = workbook.worksheet_range("Stats").unwrap();
+        assert_eq!(range.get_value((3, 0)).unwrap().to_string(), "Average");
+        assert_eq!(range.get_value((3, 1)), Some(&calamine::Data::Float(42.0)));
+        let formulas = workbook.worksheet_formula("Stats").unwrap();
+        assert!(formulas.get_value((3, 1)).is_none_or(String::is_empty));
+        assert_eq!(imported.rows.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn import_data_with_summary_keeps_data_rows_using_the_totals_label() {
+        // Arrange
+        let create_info =
+            |summary: ExcelSummaryOptions| create_stats_excel_info().with_summary(summary);
+        let mut data = create_stats_data();
+        data.rows.insert(
+            1,
+            ExcelRowData::new(vec![
+                ExcelColumnData::new("name", " Total "),
+                ExcelColumnData::new("hp", "84"),
+            ]),
+        );
+        let formulas = ExcelSummaryOptions::new(vec![ExcelAggregate::new("hp", "sum")]);
+        let precomputed = formulas.clone().with_precomputed(true);
+
+        // Act
+        let formula_bytes = export_data_buffer(&create_info(formulas.clone()), &data)
+            .await
+            .unwrap();
+        let precomputed_bytes = export_data_buffer(&create_info(precomputed.clone()), &data)
+            .await
+            .unwrap();
+        let from_formulas = import_data_buffer(create_info(formulas), &formula_bytes).unwrap();
+        let from_precomputed =
+            import_data_buffer(create_info(precomputed), &precomputed_bytes).unwrap();
+        let without_summary = import_data_buffer(create_stats_excel_info(), &formula_bytes);
+
+        // Assert
+        let get_names = |data: &ExcelData| {
+            data.rows
+                .iter()
+                .map(|row| row.columns[0].value().to_string())
+                .collect::<Vec<String>>()
+        };
+        let names = vec!["Bulbasaur", " Total ", "Charmander"];
+        assert_eq!(get_names(&from_formulas), names);
+        assert_eq!(get_names(&from_precomputed), names);
+        // Without summary options the totals row is read as data.
+        assert_eq!(get_names(&without_summary.unwrap()).len(), 4);
+    }
+
+    #[tokio::test]
+    async fn export_data_with_subtotals_rejects_rows_using_the_subtotal_label() {
+        // Arrange
+        let create_info = |subtotals: bool| {
+            create_stats_excel_info().with_summary(
+                ExcelSummaryOptions::new(vec![ExcelAggregate::new("hp", "sum")])
+                    .with_subtotals(subtotals),
+            )
+        };
+        let mut data = create_stats_data();
+        data.rows.push(ExcelRowData::new(vec![
+            ExcelColumnData::new("name", " Subtotal "),
+            ExcelColumnData::new("hp", "84"),
+        ]));
+
+        // Act
+        let with_subtotals = export_data_buffer(&create_info(true), &data).await;
+        let without_subtotals = export_data_buffer(&create_info(false), &data).await;
+
+        // Assert
+        let error = with_subtotals.err().unwrap();
+        let error = error.downcast_ref::<ImportExportError>().unwrap();
+        assert_eq!(error.code(), "EXPORT_SUMMARY_LABEL_RESERVED");
+        assert!(error
+            .params()
+            .contains(&("columnKey".to_string(), "name".to_string())));
+        let imported = import_data_buffer(create_info(false), &without_subtotals.unwrap()).unwrap();
+        assert_eq!(imported.rows.len(), 3);
+    }
+
+    #[test]
+    fn invalid_schema_summary_returns_error() {
+        // Arrange
+        let create_json = |summary: &str| {
+            format!(
+                r#"{{"name":"Stats","columns":[{{"key":"name","name":"Name"}},{{"key":"hp","name":"HP","dataType":"number"}}],"summary":{}}}"#,
+                summary
+            )
+        };
+
+        // Act
+        let empty = ExcelInfo::from_json(&create_json("{}"));
+        let unsupported = ExcelInfo::from_json(&create_json(
+            r#"{"aggregates":[{"columnKey":"hp","function":"median"}]}"#,
+        ));
+        let label_column = ExcelInfo::from_json(&create_json(
+            r#"{"aggregates":[{"columnKey":"name","function":"count"}]}"#,
+        ));
+        let with_table = ExcelInfo::from_json(&create_json(
+            r#"{"aggregates":[{"columnKey":"hp","function":"sum"}]},"table":{}"#,
+        ));
+        let blank_label = ExcelInfo::from_json(&create_json(
+            r#"{"aggregates":[{"columnKey":"hp","function":"sum"}],"label":" "}"#,
+        ));
+        let with_autofilter = ExcelInfo::from_json(&create_json(
+            r#"{"aggregates":[{"columnKey":"hp","function":"sum"}],"subtotals":true},"autofilter":true"#,
+        ));
+
+        // Assert
+        assert_eq!(
+            empty.err().unwrap(),
+            "Invalid definition: summary must define at least one aggregate."
+        );
+        assert_eq!(
+            unsupported.err().unwrap(),
+            "Invalid definition: summary aggregate of column 'hp' uses unsupported function 'median'. Supported values are sum, avg, count, min, max."
+        );
+        assert_eq!(
+            label_column.err().unwrap(),
+            "Invalid definition: summary aggregate cannot use column 'name', since the first leaf column holds the label."
+        );
+        assert!(with_table
+            .err()
+            .unwrap()
+            .starts_with("Invalid definition: summary rows cannot be combined with table output."));
+        assert_eq!(
+            blank_label.err().unwrap(),
+            "Invalid definition: summary labels cannot be blank."
+        );
+        assert!(with_autofilter.err().unwrap().starts_with(
+            "Invalid definition: summary subtotals cannot be combined with autofilter"
+        ));
+    }
+
     #[test]
     fn validate_image_data_empty_bytes_returns_error_with_url() {
         let url = "https://example.com/photo.png";
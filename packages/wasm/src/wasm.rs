@@ -0,0 +1,325 @@
+use crate::{
+    create_structured_error, create_template_buffer, export_csv_buffer, export_data_buffer,
+    export_data_streaming_buffer, export_workbook_buffer, import_all_sheets_data_buffer,
+    import_csv_buffer, import_data_buffer, import_data_in_batches_buffer,
+    import_data_with_report_buffer, import_dynamic_csv_buffer, import_dynamic_data_buffer,
+    import_grouped_data_buffer, infer_excel_info_buffer, CsvOptions, DynamicExcelData, ExcelData,
+    ExcelInfo, ExcelRowData, ExcelValidationReport, ExcelWorkbook, ImageFetchFuture, ImageFetcher,
+    ImportExportError, ProgressReporter,
+};
+use js_sys::{Function, Object, Reflect};
+use std::future::Future;
+use std::mem::ManuallyDrop;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::thread::{self, ThreadId};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::future_to_promise;
+use wasm_bindgen_futures::JsFuture;
+
+/// Keeps a JS value on the thread that created it.
+///
+/// The core stores callbacks behind `Arc` with `Send + Sync` bounds, but JS
+/// values cannot leave their thread. Using the value from another thread
+/// panics and dropping it there leaks it, so it never actually crosses.
+struct ThreadBound<T> {
+    value: ManuallyDrop<T>,
+    thread_id: ThreadId,
+}
+
+// SAFETY: the value is only reached through `get`/`get_mut`, which check the
+// current thread, and `Drop` leaks it on any other thread.
+unsafe impl<T> Send for ThreadBound<T> {}
+unsafe impl<T> Sync for ThreadBound<T> {}
+
+impl<T> ThreadBound<T> {
+    fn new(value: T) -> Self {
+        ThreadBound {
+            value: ManuallyDrop::new(value),
+            thread_id: thread::current().id(),
+        }
+    }
+
+    fn is_owner_thread(&self) -> bool {
+        thread::current().id() == self.thread_id
+    }
+
+    fn get(&self) -> &T {
+        assert!(self.is_owner_thread(), "JS value used outside its thread");
+        &self.value
+    }
+
+    fn get_mut(&mut self) -> &mut T {
+        assert!(self.is_owner_thread(), "JS value used outside its thread");
+        &mut self.value
+    }
+}
+
+impl<T> Drop for ThreadBound<T> {
+    fn drop(&mut self) {
+        if self.is_owner_thread() {
+            // SAFETY: the value is dropped once, here, on its own thread.
+            unsafe { ManuallyDrop::drop(&mut self.value) }
+        }
+    }
+}
+
+impl<F: Future + Unpin> Future for ThreadBound<F> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<F::Output> {
+        Pin::new(self.get_mut().get_mut()).poll(cx)
+    }
+}
+
+/// Forwards progress to a JS function called with a single number.
+pub(crate) struct JsProgressReporter(ThreadBound<Function>);
+
+impl JsProgressReporter {
+    pub(crate) fn new(callback: Function) -> Self {
+        JsProgressReporter(ThreadBound::new(callback))
+    }
+}
+
+impl ProgressReporter for JsProgressReporter {
+    fn report(&self, progress: f64) {
+        let _ = self
+            .0
+            .get()
+            .call1(&JsValue::NULL, &JsValue::from_f64(progress));
+    }
+}
+
+type LocalImageFetchFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<Vec<u8>, Box<dyn std::error::Error>>> + 'a>>;
+
+/// Calls a JS function with the image URL and awaits the returned bytes.
+pub(crate) struct JsImageFetcher(ThreadBound<Function>);
+
+impl JsImageFetcher {
+    pub(crate) fn new(fetcher: Function) -> Self {
+        JsImageFetcher(ThreadBound::new(fetcher))
+    }
+}
+
+impl ImageFetcher for JsImageFetcher {
+    fn fetch<'a>(&'a self, url: &'a str) -> ImageFetchFuture<'a> {
+        let fetcher = self.0.get().clone();
+        let future: LocalImageFetchFuture<'a> = Box::pin(async move {
+            let url_value = JsValue::from_str(url);
+            let result = fetcher.call1(&JsValue::NULL, &url_value).map_err(|e| {
+                create_structured_error(
+                    format!("Failed to call image fetcher: {:?}", e),
+                    "IMAGE_FETCHER_CALL_FAILED",
+                    &[("reason", format!("{:?}", e))],
+                )
+            })?;
+            let promise = js_sys::Promise::resolve(&result);
+            let result = JsFuture::from(promise).await.map_err(|e| {
+                create_structured_error(
+                    format!("Failed to wait for image fetcher promise: {:?}", e),
+                    "IMAGE_FETCHER_WAIT_FAILED",
+                    &[("reason", format!("{:?}", e))],
+                )
+            })?;
+
+            if !result.is_object() {
+                return Err(create_structured_error(
+                    format!("Image fetcher returned invalid data for URL: {}", url),
+                    "IMAGE_FETCHER_INVALID_DATA",
+                    &[("url", url.to_string())],
+                )
+                .into());
+            }
+            Ok(js_sys::Uint8Array::new(&result).to_vec())
+        });
+        Box::pin(ThreadBound::new(future))
+    }
+}
+
+fn structured_error_to_js(error: &ImportExportError) -> JsValue {
+    let js_error = js_sys::Error::new(&error.message);
+    let js_value: JsValue = js_error.into();
+    let params = Object::new();
+
+    for (key, value) in &error.params {
+        let _ = Reflect::set(&params, &JsValue::from_str(key), &JsValue::from_str(value));
+    }
+
+    let _ = Reflect::set(
+        &js_value,
+        &JsValue::from_str("name"),
+        &JsValue::from_str("ImportExportWasmError"),
+    );
+    let _ = Reflect::set(
+        &js_value,
+        &JsValue::from_str("code"),
+        &JsValue::from_str(error.code),
+    );
+    let _ = Reflect::set(&js_value, &JsValue::from_str("params"), &params);
+    js_value
+}
+
+pub(crate) fn error_to_js_value(error: &(dyn std::error::Error + 'static)) -> JsValue {
+    if let Some(structured_error) = error.downcast_ref::<ImportExportError>() {
+        return structured_error_to_js(structured_error);
+    }
+    JsError::new(&error.to_string()).into()
+}
+
+#[wasm_bindgen(js_name= createTemplate)]
+pub fn create_template(info: ExcelInfo) -> Result<Vec<u8>, JsValue> {
+    create_template_buffer(&info).map_err(|e| error_to_js_value(e.as_ref()))
+}
+
+#[wasm_bindgen(js_name = importData)]
+pub fn import_data(info: ExcelInfo, excel_bytes: &[u8]) -> Result<ExcelData, JsValue> {
+    import_data_buffer(info, excel_bytes).map_err(|e| error_to_js_value(e.as_ref()))
+}
+
+#[wasm_bindgen(js_name = importDataWithReport)]
+pub fn import_data_with_report(
+    info: ExcelInfo,
+    excel_bytes: &[u8],
+) -> Result<ExcelValidationReport, JsValue> {
+    import_data_with_report_buffer(info, excel_bytes).map_err(|e| error_to_js_value(e.as_ref()))
+}
+
+#[wasm_bindgen(js_name = importDataInBatches)]
+pub fn import_data_in_batches(
+    info: ExcelInfo,
+    excel_bytes: Vec<u8>,
+    batch_size: Option<u32>,
+    on_batch: js_sys::Function,
+) -> js_sys::Promise {
+    let future = async move {
+        let call_on_batch = |rows: Vec<ExcelRowData>, progress: f64| {
+            let on_batch = on_batch.clone();
+            async move {
+                let rows = rows
+                    .into_iter()
+                    .map(JsValue::from)
+                    .collect::<js_sys::Array>();
+                let result = on_batch
+                    .call2(&JsValue::NULL, &rows, &JsValue::from_f64(progress))
+                    .map_err(|e| {
+                        create_structured_error(
+                            format!("Failed to call import batch callback: {:?}", e),
+                            "IMPORT_BATCH_CALLBACK_FAILED",
+                            &[("reason", format!("{:?}", e))],
+                        )
+                    })?;
+                let result = JsFuture::from(js_sys::Promise::resolve(&result))
+                    .await
+                    .map_err(|e| {
+                        create_structured_error(
+                            format!("Failed to wait for import batch callback: {:?}", e),
+                            "IMPORT_BATCH_CALLBACK_FAILED",
+                            &[("reason", format!("{:?}", e))],
+                        )
+                    })?;
+                Ok(result.as_bool() != Some(false))
+            }
+        };
+        match import_data_in_batches_buffer(info, &excel_bytes, batch_size, call_on_batch).await {
+            Ok(summary) => Ok(summary.into()),
+            Err(e) => Err(error_to_js_value(e.as_ref())),
+        }
+    };
+    future_to_promise(future)
+}
+
+#[wasm_bindgen(js_name = importGroupedData)]
+pub fn import_grouped_data(info: ExcelInfo, excel_bytes: &[u8]) -> Result<ExcelData, JsValue> {
+    import_grouped_data_buffer(info, excel_bytes).map_err(|e| error_to_js_value(e.as_ref()))
+}
+
+#[wasm_bindgen(js_name = importAllSheetsData)]
+pub fn import_all_sheets_data(info: ExcelInfo, excel_bytes: &[u8]) -> Result<ExcelData, JsValue> {
+    import_all_sheets_data_buffer(info, excel_bytes).map_err(|e| error_to_js_value(e.as_ref()))
+}
+
+#[wasm_bindgen(js_name = importDynamicData)]
+pub fn import_dynamic_data(
+    sheet_name: Option<String>,
+    header_row: Option<u32>,
+    excel_bytes: &[u8],
+    format: Option<String>,
+) -> Result<DynamicExcelData, JsValue> {
+    import_dynamic_data_buffer(sheet_name, header_row, excel_bytes, format.as_deref())
+        .map_err(|e| error_to_js_value(e.as_ref()))
+}
+
+#[wasm_bindgen(js_name = inferExcelInfo)]
+pub fn infer_excel_info(
+    sheet_name: Option<String>,
+    header_row: Option<u32>,
+    excel_bytes: &[u8],
+    format: Option<String>,
+) -> Result<ExcelInfo, JsValue> {
+    infer_excel_info_buffer(sheet_name, header_row, excel_bytes, format.as_deref())
+        .map_err(|e| error_to_js_value(e.as_ref()))
+}
+
+#[wasm_bindgen(js_name = exportData)]
+pub fn export_data(info: ExcelInfo, data: ExcelData) -> js_sys::Promise {
+    let future = async move {
+        match export_data_buffer(&info, &data).await {
+            Ok(buffer) => {
+                let uint8array = js_sys::Uint8Array::from(&buffer[..]);
+                Ok(uint8array.into())
+            }
+            Err(e) => Err(error_to_js_value(e.as_ref())),
+        }
+    };
+    future_to_promise(future)
+}
+
+#[wasm_bindgen(js_name = exportWorkbook)]
+pub fn export_workbook(workbook: ExcelWorkbook) -> js_sys::Promise {
+    let future = async move {
+        match export_workbook_buffer(&workbook).await {
+            Ok(buffer) => {
+                let uint8array = js_sys::Uint8Array::from(&buffer[..]);
+                Ok(uint8array.into())
+            }
+            Err(e) => Err(error_to_js_value(e.as_ref())),
+        }
+    };
+    future_to_promise(future)
+}
+
+#[wasm_bindgen(js_name = exportDataStreaming)]
+pub fn export_data_streaming(info: ExcelInfo, data: ExcelData) -> Result<Vec<u8>, JsValue> {
+    export_data_streaming_buffer(info, &data).map_err(|e| error_to_js_value(e.as_ref()))
+}
+
+#[wasm_bindgen(js_name = exportCsv)]
+pub fn export_csv(
+    info: ExcelInfo,
+    data: ExcelData,
+    options: Option<CsvOptions>,
+) -> Result<Vec<u8>, JsValue> {
+    export_csv_buffer(&info, &data, &options.unwrap_or_default())
+        .map_err(|e| error_to_js_value(e.as_ref()))
+}
+
+#[wasm_bindgen(js_name = importCsv)]
+pub fn import_csv(
+    info: ExcelInfo,
+    csv_bytes: &[u8],
+    options: Option<CsvOptions>,
+) -> Result<ExcelData, JsValue> {
+    import_csv_buffer(info, csv_bytes, &options.unwrap_or_default())
+        .map_err(|e| error_to_js_value(e.as_ref()))
+}
+
+#[wasm_bindgen(js_name = importDynamicCsv)]
+pub fn import_dynamic_csv(
+    header_row: Option<u32>,
+    csv_bytes: &[u8],
+    options: Option<CsvOptions>,
+) -> Result<DynamicExcelData, JsValue> {
+    import_dynamic_csv_buffer(header_row, csv_bytes, &options.unwrap_or_default())
+        .map_err(|e| error_to_js_value(e.as_ref()))
+}
@@ -0,0 +1,524 @@
+use crate::create_structured_error;
+use calamine::{
+    open_workbook_from_rs, Data, Dimensions, Ods, Reader, Sheets, Xls, Xlsb, Xlsx, XlsxCellReader,
+};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::XmlVersion;
+use std::collections::HashMap;
+use std::io::{Cursor, Read};
+use zip::ZipArchive;
+
+const OLE_MAGIC: &[u8] = &[0xD0, 0xCF, 0x11, 0xE0, 0xA1, 0xB1, 0x1A, 0xE1];
+const ZIP_MAGIC: &[u8] = &[0x50, 0x4B, 0x03, 0x04];
+const ODS_MIMETYPE: &[u8] = b"application/vnd.oasis.opendocument.spreadsheet";
+const XLSB_WORKBOOK_ENTRY: &[u8] = b"xl/workbook.bin";
+
+pub type ImportWorkbook<'a> = Sheets<Cursor<&'a [u8]>>;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum WorkbookFormat {
+    Xlsx,
+    Xlsb,
+    Xls,
+    Ods,
+}
+
+impl WorkbookFormat {
+    fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "xlsx" | "xlsm" => Some(WorkbookFormat::Xlsx),
+            "xlsb" => Some(WorkbookFormat::Xlsb),
+            "xls" => Some(WorkbookFormat::Xls),
+            "ods" => Some(WorkbookFormat::Ods),
+            _ => None,
+        }
+    }
+
+    fn detect(excel_bytes: &[u8]) -> Option<Self> {
+        if excel_bytes.starts_with(OLE_MAGIC) {
+            return Some(WorkbookFormat::Xls);
+        }
+        if !excel_bytes.starts_with(ZIP_MAGIC) {
+            return None;
+        }
+        // Zip entry names are stored uncompressed in the local file headers,
+        // which is enough to tell the zip based formats apart.
+        if contains_bytes(excel_bytes, ODS_MIMETYPE) {
+            Some(WorkbookFormat::Ods)
+        } else if contains_bytes(excel_bytes, XLSB_WORKBOOK_ENTRY) {
+            Some(WorkbookFormat::Xlsb)
+        } else {
+            Some(WorkbookFormat::Xlsx)
+        }
+    }
+}
+
+pub fn open_import_workbook<'a>(
+    excel_bytes: &'a [u8],
+    format: Option<&str>,
+) -> Result<ImportWorkbook<'a>, Box<dyn std::error::Error>> {
+    let format = match format
+        .map(|value| value.trim())
+        .filter(|value| !value.is_empty() && !value.eq_ignore_ascii_case("auto"))
+    {
+        Some(name) => WorkbookFormat::from_name(name).ok_or_else(|| {
+            create_structured_error(
+                format!("Unsupported workbook format '{}'", name),
+                "UNSUPPORTED_WORKBOOK_FORMAT",
+                &[("format", name.to_string())],
+            )
+        })?,
+        None => WorkbookFormat::detect(excel_bytes).ok_or_else(|| {
+            create_structured_error(
+                "Unsupported workbook format, expected xlsx, xlsb, xls or ods",
+                "UNSUPPORTED_WORKBOOK_FORMAT",
+                &[("format", "unknown".to_string())],
+            )
+        })?,
+    };
+
+    let cursor = Cursor::new(excel_bytes);
+    let workbook = match format {
+        WorkbookFormat::Xlsx => Sheets::Xlsx(open_workbook_from_rs::<Xlsx<_>, _>(cursor)?),
+        WorkbookFormat::Xlsb => Sheets::Xlsb(open_workbook_from_rs::<Xlsb<_>, _>(cursor)?),
+        WorkbookFormat::Xls => Sheets::Xls(open_workbook_from_rs::<Xls<_>, _>(cursor)?),
+        WorkbookFormat::Ods => Sheets::Ods(open_workbook_from_rs::<Ods<_>, _>(cursor)?),
+    };
+    Ok(workbook)
+}
+
+/// Returns every merged region of a sheet.
+/// Xlsb and Ods readers do not expose merged regions, so they return none.
+pub fn get_merged_regions(
+    workbook: &mut ImportWorkbook,
+    sheet_name: &str,
+) -> Result<Vec<Dimensions>, Box<dyn std::error::Error>> {
+    Ok(match workbook {
+        Sheets::Xlsx(workbook) => workbook.merge_cells_by_sheet_name(sheet_name)?,
+        Sheets::Xls(workbook) => workbook.merge_cells_by_sheet_name(sheet_name)?,
+        Sheets::Xlsb(_) | Sheets::Ods(_) => Vec::new(),
+    })
+}
+
+/// Returns the last row of every merged region keyed by its top-left cell.
+pub fn get_merged_row_ends(
+    workbook: &mut ImportWorkbook,
+    sheet_name: &str,
+) -> Result<HashMap<(u32, u32), u32>, Box<dyn std::error::Error>> {
+    Ok(get_merged_regions(workbook, sheet_name)?
+        .into_iter()
+        .map(|dimensions| (dimensions.start, dimensions.end.0))
+        .collect())
+}
+
+/// Returns every hyperlink of a sheet with its target. Internal targets keep
+/// the `internal:` prefix used on export; formats other than xlsx return none.
+pub fn get_hyperlinks(
+    workbook: &mut ImportWorkbook,
+    sheet_name: &str,
+) -> Result<Vec<(Dimensions, String)>, Box<dyn std::error::Error>> {
+    let Sheets::Xlsx(workbook) = workbook else {
+        return Ok(Vec::new());
+    };
+    Ok(workbook
+        .hyperlinks_by_sheet_name(sheet_name)?
+        .into_iter()
+        .filter_map(|hyperlink| {
+            let url = match (hyperlink.target, hyperlink.location) {
+                (Some(target), Some(location)) => format!("{}#{}", target, location),
+                (Some(target), None) => target,
+                (None, Some(location)) => format!("internal:{}", location),
+                (None, None) => return None,
+            };
+            Some((hyperlink.range, url))
+        })
+        .collect())
+}
+
+/// A non-empty cell of a sheet with its formula, if it has one.
+pub struct SheetCell {
+    pub pos: (u32, u32),
+    pub value: Data,
+    pub formula: Option<String>,
+}
+
+/// The non-empty cells of a sheet in row order. Xlsx sheets are read from the
+/// sheet XML one cell at a time; the other formats are parsed whole first.
+pub enum SheetCells<'a, 'b> {
+    Xlsx {
+        reader: Box<XlsxCellReader<'a, Cursor<&'b [u8]>>>,
+        with_formulas: bool,
+    },
+    Parsed {
+        cells: std::vec::IntoIter<SheetCell>,
+        end_row: u32,
+    },
+}
+
+impl SheetCells<'_, '_> {
+    /// The last row of the sheet as its dimension declares it.
+    pub fn get_end_row(&self) -> u32 {
+        match self {
+            SheetCells::Xlsx { reader, .. } => reader.dimensions().end.0,
+            SheetCells::Parsed { end_row, .. } => *end_row,
+        }
+    }
+
+    pub fn next_cell(&mut self) -> Result<Option<SheetCell>, Box<dyn std::error::Error>> {
+        let (reader, with_formulas) = match self {
+            SheetCells::Xlsx {
+                reader,
+                with_formulas,
+            } => (reader, *with_formulas),
+            SheetCells::Parsed { cells, .. } => return Ok(cells.next()),
+        };
+        loop {
+            let cell = if with_formulas {
+                reader.next_cell_with_formula()?.map(|cell| SheetCell {
+                    pos: cell.pos,
+                    value: Data::from(cell.value),
+                    formula: cell.formula.filter(|formula| !formula.is_empty()),
+                })
+            } else {
+                reader.next_cell()?.map(|cell| SheetCell {
+                    pos: cell.get_position(),
+                    value: Data::from(cell.get_value().clone()),
+                    formula: None,
+                })
+            };
+            match cell {
+                Some(cell) if cell.value == Data::Empty => continue,
+                cell => return Ok(cell),
+            }
+        }
+    }
+}
+
+/// Opens the cells of a sheet for reading in row order, with their formulas
+/// when `with_formulas` is set.
+pub fn read_sheet_cells<'a, 'b>(
+    workbook: &'a mut ImportWorkbook<'b>,
+    sheet_name: &str,
+    with_formulas: bool,
+) -> Result<SheetCells<'a, 'b>, Box<dyn std::error::Error>> {
+    if let Sheets::Xlsx(workbook) = workbook {
+        return Ok(SheetCells::Xlsx {
+            reader: Box::new(workbook.worksheet_cells_reader(sheet_name)?),
+            with_formulas,
+        });
+    }
+    let range = workbook.worksheet_range(sheet_name)?;
+    let formulas = if with_formulas {
+        Some(workbook.worksheet_formula(sheet_name)?)
+    } else {
+        None
+    };
+    let (Some(start), Some(end)) = (range.start(), range.end()) else {
+        return Ok(SheetCells::Parsed {
+            cells: Vec::new().into_iter(),
+            end_row: 0,
+        });
+    };
+    let cells = range
+        .used_cells()
+        .map(|(row, column, value)| {
+            let pos = (start.0 + row as u32, start.1 + column as u32);
+            let formula = formulas
+                .as_ref()
+                .and_then(|formulas| formulas.get_value(pos))
+                .filter(|formula| !formula.is_empty())
+                .cloned();
+            SheetCell {
+                pos,
+                value: value.clone(),
+                formula,
+            }
+        })
+        .collect::<Vec<SheetCell>>();
+    Ok(SheetCells::Parsed {
+        cells: cells.into_iter(),
+        end_row: end.0,
+    })
+}
+
+/// A `list` data validation with its target ranges and raw `formula1`.
+pub struct ListValidation {
+    pub ranges: Vec<Dimensions>,
+    pub formula: String,
+}
+
+/// Reads the `list` data validations of an xlsx sheet, including the ones
+/// stored in the x14 extension. Other formats return none.
+pub fn get_list_validations(
+    workbook: &ImportWorkbook,
+    excel_bytes: &[u8],
+    sheet_name: &str,
+) -> Result<Vec<ListValidation>, Box<dyn std::error::Error>> {
+    if !matches!(workbook, Sheets::Xlsx(_)) {
+        return Ok(Vec::new());
+    }
+    let mut archive = ZipArchive::new(Cursor::new(excel_bytes))?;
+    let Some(sheet_path) = find_sheet_path(&mut archive, sheet_name)? else {
+        return Ok(Vec::new());
+    };
+    let sheet_xml = read_zip_text(&mut archive, &sheet_path)?;
+    let mut reader = quick_xml::Reader::from_str(&sheet_xml);
+    let mut validations = Vec::new();
+    let mut current: Option<ListValidation> = None;
+    let mut text: Option<String> = None;
+    loop {
+        match reader.read_event()? {
+            Event::Start(element) if element.local_name().as_ref() == b"dataValidation" => {
+                current = get_attribute(&element, b"type")?
+                    .filter(|value| value == "list")
+                    .map(|_| -> Result<_, Box<dyn std::error::Error>> {
+                        Ok(ListValidation {
+                            ranges: parse_sqref(
+                                &get_attribute(&element, b"sqref")?.unwrap_or_default(),
+                            ),
+                            formula: String::new(),
+                        })
+                    })
+                    .transpose()?;
+            }
+            Event::Start(element)
+                if current.is_some()
+                    && matches!(element.local_name().as_ref(), b"formula1" | b"sqref") =>
+            {
+                text = Some(String::new());
+            }
+            Event::Text(value) => {
+                if let Some(text) = text.as_mut() {
+                    text.push_str(&value.decode()?);
+                }
+            }
+            Event::GeneralRef(value) => {
+                if let Some(text) = text.as_mut() {
+                    text.push('&');
+                    text.push_str(&value.decode()?);
+                    text.push(';');
+                }
+            }
+            Event::End(element) => match element.local_name().as_ref() {
+                b"formula1" | b"sqref" => {
+                    if let (Some(validation), Some(value)) = (current.as_mut(), text.take()) {
+                        let value = quick_xml::escape::unescape(&value)?.into_owned();
+                        if element.local_name().as_ref() == b"sqref" {
+                            validation.ranges = parse_sqref(&value);
+                        } else {
+                            validation.formula = value;
+                        }
+                    }
+                }
+                b"dataValidation" => validations.extend(current.take()),
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(validations)
+}
+
+/// Returns the totals rows of every table on an xlsx sheet, read from the
+/// `ref` and `totalsRowCount` of its table part. Other formats return none.
+pub fn get_table_totals_rows(
+    workbook: &ImportWorkbook,
+    excel_bytes: &[u8],
+    sheet_name: &str,
+) -> Result<Vec<u32>, Box<dyn std::error::Error>> {
+    if !matches!(workbook, Sheets::Xlsx(_)) {
+        return Ok(Vec::new());
+    }
+    let mut archive = ZipArchive::new(Cursor::new(excel_bytes))?;
+    let Some(sheet_path) = find_sheet_path(&mut archive, sheet_name)? else {
+        return Ok(Vec::new());
+    };
+    let (sheet_dir, sheet_file) = sheet_path.rsplit_once('/').unwrap_or(("", &sheet_path));
+    let relationships_path = format!("{}/_rels/{}.rels", sheet_dir, sheet_file);
+    if archive.index_for_name(&relationships_path).is_none() {
+        return Ok(Vec::new());
+    }
+    let relationships_xml = read_zip_text(&mut archive, &relationships_path)?;
+    let mut reader = quick_xml::Reader::from_str(&relationships_xml);
+    let mut table_paths = Vec::new();
+    loop {
+        match reader.read_event()? {
+            Event::Start(element) | Event::Empty(element)
+                if element.local_name().as_ref() == b"Relationship"
+                    && get_attribute(&element, b"Type")?
+                        .is_some_and(|value| value.ends_with("/table")) =>
+            {
+                if let Some(target) = get_attribute(&element, b"Target")? {
+                    table_paths.push(resolve_zip_path(sheet_dir, &target));
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    let mut totals_rows = Vec::new();
+    for table_path in table_paths {
+        let table_xml = read_zip_text(&mut archive, &table_path)?;
+        let mut reader = quick_xml::Reader::from_str(&table_xml);
+        loop {
+            match reader.read_event()? {
+                Event::Start(element) | Event::Empty(element)
+                    if element.local_name().as_ref() == b"table" =>
+                {
+                    let totals_row_count = get_attribute(&element, b"totalsRowCount")?
+                        .and_then(|value| value.parse::<u32>().ok())
+                        .unwrap_or(0);
+                    let table_ref = get_attribute(&element, b"ref")?.unwrap_or_default();
+                    if let Some(dimensions) = parse_range_ref(&table_ref) {
+                        let last_row = dimensions.end.0;
+                        let first_row = (last_row + 1).saturating_sub(totals_row_count);
+                        totals_rows.extend(first_row..last_row + 1);
+                    }
+                    break;
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+    }
+    Ok(totals_rows)
+}
+
+/// Parses an A1 style cell or range such as `$B$3:$B$20`.
+pub fn parse_range_ref(value: &str) -> Option<Dimensions> {
+    let mut parts = value.split(':');
+    let start = parse_cell_ref(parts.next()?)?;
+    let end = match parts.next() {
+        Some(end) => parse_cell_ref(end)?,
+        None => start,
+    };
+    Some(Dimensions { start, end })
+}
+
+fn parse_cell_ref(value: &str) -> Option<(u32, u32)> {
+    let value = value.trim().replace('$', "");
+    let split = value.find(|c: char| c.is_ascii_digit())?;
+    let (letters, digits) = value.split_at(split);
+    if letters.is_empty() || !letters.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    let column = letters.chars().fold(0u32, |acc, c| {
+        acc * 26 + (c.to_ascii_uppercase() as u32 - 'A' as u32 + 1)
+    });
+    let row = digits.parse::<u32>().ok()?.checked_sub(1)?;
+    Some((row, column - 1))
+}
+
+fn parse_sqref(value: &str) -> Vec<Dimensions> {
+    value
+        .split_whitespace()
+        .filter_map(parse_range_ref)
+        .collect()
+}
+
+fn get_attribute(
+    element: &BytesStart,
+    name: &[u8],
+) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    match element.try_get_attribute(name)? {
+        Some(attribute) => Ok(Some(
+            attribute
+                .decoded_and_normalized_value(XmlVersion::default(), element.decoder())?
+                .into_owned(),
+        )),
+        None => Ok(None),
+    }
+}
+
+fn read_zip_text(
+    archive: &mut ZipArchive<Cursor<&[u8]>>,
+    path: &str,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let mut text = String::new();
+    archive.by_name(path)?.read_to_string(&mut text)?;
+    Ok(text)
+}
+
+fn find_sheet_path(
+    archive: &mut ZipArchive<Cursor<&[u8]>>,
+    sheet_name: &str,
+) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    let workbook_xml = read_zip_text(archive, "xl/workbook.xml")?;
+    let mut reader = quick_xml::Reader::from_str(&workbook_xml);
+    let mut relationship_id = None;
+    loop {
+        match reader.read_event()? {
+            Event::Start(element) | Event::Empty(element)
+                if element.local_name().as_ref() == b"sheet"
+                    && get_attribute(&element, b"name")?.as_deref() == Some(sheet_name) =>
+            {
+                relationship_id = element
+                    .attributes()
+                    .flatten()
+                    .find(|attribute| attribute.key.local_name().as_ref() == b"id")
+                    .map(|attribute| {
+                        attribute
+                            .decoded_and_normalized_value(XmlVersion::default(), element.decoder())
+                            .map(|value| value.into_owned())
+                    })
+                    .transpose()?;
+                break;
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    let Some(relationship_id) = relationship_id else {
+        return Ok(None);
+    };
+
+    let relationships_xml = read_zip_text(archive, "xl/_rels/workbook.xml.rels")?;
+    let mut reader = quick_xml::Reader::from_str(&relationships_xml);
+    loop {
+        match reader.read_event()? {
+            Event::Start(element) | Event::Empty(element)
+                if element.local_name().as_ref() == b"Relationship"
+                    && get_attribute(&element, b"Id")?.as_deref()
+                        == Some(relationship_id.as_str()) =>
+            {
+                return Ok(get_attribute(&element, b"Target")?.map(|target| {
+                    match target.strip_prefix('/') {
+                        Some(absolute) => absolute.to_string(),
+                        None => format!("xl/{}", target),
+                    }
+                }));
+            }
+            Event::Eof => return Ok(None),
+            _ => {}
+        }
+    }
+}
+
+/// Resolves a relationship target against the folder of its source part.
+fn resolve_zip_path(base_dir: &str, target: &str) -> String {
+    if let Some(absolute) = target.strip_prefix('/') {
+        return absolute.to_string();
+    }
+    let mut segments = base_dir
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect::<Vec<&str>>();
+    for segment in target.split('/') {
+        match segment {
+            ".." => {
+                segments.pop();
+            }
+            "." | "" => {}
+            segment => segments.push(segment),
+        }
+    }
+    segments.join("/")
+}
+
+fn contains_bytes(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack
+        .windows(needle.len())
+        .any(|window| window == needle)
+}